@@ -9,6 +9,7 @@ mkdir -p "$DATA_DIR"
 
 DATE=$(date "+%Y-%m-%d")
 ORACLE_CARDS="scryfall-oracle-cards-$DATE.json"
+SETS="scryfall-sets-$DATE.json"
 CI=${LANDLORD_IS_CI:-0}
 
 #  0. Don't allow this script to run in a dirty working directory
@@ -24,9 +25,17 @@ fi
 ORACLE_URL=$(curl 'https://api.scryfall.com/bulk-data' | python3 -c "import sys, json; print(json.load(sys.stdin)['data'][0]['download_uri'])")
 #  2. Download the oracale cards
 curl $ORACLE_URL -o "$ORACLE_CARDS"
-#  3. Generate data/all_cards.landlord using the oracle cards
-RUST_BACKTRACE=1 RUST_LOG=info cargo run --release --bin scryfall2landlord "$ORACLE_CARDS" "$DATA_DIR/all_cards.landlord"
-#  4. Was a new artifact generated? If so and this is the CI pipeline, then test it and upload the input file to S3
+#  3. Download the sets
+curl 'https://api.scryfall.com/sets' -o "$SETS"
+#  4. Keep the current card database to list what changed
+OLD_CARDS="all_cards-$DATE.landlord"
+cp "$DATA_DIR/all_cards.landlord" "$OLD_CARDS"
+#  5. Generate data/all_cards.landlord and data/all_sets.landlord using the oracle cards and sets.
+#     Both files start with a .landlord header, so landlord rejects files from an older layout
+RUST_BACKTRACE=1 RUST_LOG=info cargo run --release --bin scryfall2landlord "$ORACLE_CARDS" "$DATA_DIR/all_cards.landlord" "$SETS" "$DATA_DIR/all_sets.landlord"
+#  6. List the cards added, removed and changed by the update
+cargo run --release --bin landlord-changelog "$OLD_CARDS" "$DATA_DIR/all_cards.landlord"
+#  7. Was a new artifact generated? If so and this is the CI pipeline, then test it and upload the input file to S3
 
 git diff --exit-code --quiet
 if [ $? -eq 1 ] && [ "$CI" -eq 1 ]; then
@@ -39,7 +48,7 @@ if [ $? -eq 1 ] && [ "$CI" -eq 1 ]; then
     aws s3 cp "$ORACLE_CARDS" "s3://mtgoncurve-scryfall-archive/$INPUT"
     git config --local user.name "Card Update Bot"
     git config --local user.email "bot@mtgoncurve.com"
-    git commit -am "Update all_cards.landlord ($ORACLE_CARDS)"
+    git commit -am "Update all_cards.landlord and all_sets.landlord ($ORACLE_CARDS)"
     git push origin master
 fi
-rm "$ORACLE_CARDS"
+rm "$ORACLE_CARDS" "$SETS" "$OLD_CARDS"
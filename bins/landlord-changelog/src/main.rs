@@ -0,0 +1,24 @@
+extern crate landlord;
+
+use landlord::collection::Collection;
+use landlord::data::LoadError;
+use std::env;
+
+fn main() -> Result<(), LoadError> {
+    let mut args: Vec<String> = env::args().collect();
+    let json = args.iter().any(|arg| arg == "--json");
+    args.retain(|arg| arg != "--json");
+    assert!(
+        args.len() == 3,
+        "Expected 2 arguments, the older and newer .landlord paths, optionally followed by --json"
+    );
+    let older = Collection::load_from_path(&args[1])?;
+    let newer = Collection::load_from_path(&args[2])?;
+    let diff = older.diff(&newer);
+    if json {
+        println!("{}", diff.to_json());
+    } else {
+        print!("{}", diff);
+    }
+    Ok(())
+}
@@ -5,23 +5,31 @@ extern crate serde_json;
 #[macro_use]
 extern crate log;
 extern crate landlord;
+extern crate sha2;
 
-use flate2::write::GzEncoder;
-use flate2::Compression;
-use landlord::card::{Card, Legality};
+use landlord::card::Card;
 use landlord::collection::Collection;
-use landlord::scryfall::ScryfallCard;
+use landlord::data::LandlordHeader;
+use landlord::scryfall::{
+    scryfall_cards_from_reader, DropReason, DropReport, PrintingDedup, PrintingPreference,
+    ScryfallCard, ScryfallSetList,
+};
+use landlord::set::SetRegistry;
+use sha2::{Digest, Sha256};
 use std::env;
 use std::fs::File;
 use std::fs::OpenOptions;
 use std::io::prelude::*;
+use std::io::{BufReader, BufWriter};
 use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug)]
 enum Error {
     Json(serde_json::Error),
     Bincode(bincode::Error),
     Io(std::io::Error),
+    Args(String),
 }
 
 impl From<std::io::Error> for Error {
@@ -44,80 +52,168 @@ impl From<bincode::Error> for Error {
 
 fn main() -> Result<(), Error> {
     let _ = env_logger::try_init();
-    let args: Vec<String> = env::args().collect();
-    assert!(args.len() > 2, "Expected 2 arguments, URI and output path");
+    let mut args: Vec<String> = Vec::new();
+    let mut default_cards = false;
+    let mut preference = PrintingPreference::default();
+    let mut report_path_string = None;
+    let mut env_args = env::args();
+    args.extend(env_args.next());
+    while let Some(arg) = env_args.next() {
+        match arg.as_str() {
+            "--default-cards" => default_cards = true,
+            "--prefer" => {
+                let value = env_args.next().unwrap_or_default();
+                preference = value.parse().map_err(Error::Args)?;
+            }
+            "--report" => report_path_string = env_args.next(),
+            _ => args.push(arg),
+        }
+    }
+    if args.len() != 3 && args.len() != 5 {
+        return Err(Error::Args(
+            "Expected 2 arguments, URI and output path, optionally followed by the sets URI and sets output path".to_string(),
+        ));
+    }
     let uri_string = &args[1];
     let out_path_string = &args[2];
 
     let uri_path = Path::new(uri_string);
-    info!("Loading JSON file @ {}", uri_string);
-    let mut json_file_contents = String::new();
-    File::open(uri_path)?.read_to_string(&mut json_file_contents)?;
-    let json_val = serde_json::from_str(&json_file_contents)?;
-    info!("Deserializing Scryfall JSON");
-    let mut scryfall_cards: Vec<ScryfallCard> = serde_json::from_value(json_val)?;
-    // Filter out any cards that are not legal in all formats
-    // This should filter out any tokens
-    // See https://github.com/mtgoncurve/landlord/issues/4
-    scryfall_cards = scryfall_cards
-        .into_iter()
-        .filter(|c| !c.legalities.values().all(|l| l == &Legality::NotLegal))
-        .collect();
-    /*
-    // TODO(jshrake):
-    // The following commented out code attempts to whittle down the defaul cards
-    // file from https://scryfall.com/docs/api/bulk-data so that it's similar to
-    // the oracle cards file. This is useful when the oracle cards file is missing
-    // cards from future sets.  Reconsider making this code more robust at a later time.
-    //
-    // Filter out non-english cards
-    scryfall_cards = scryfall_cards
-        .into_iter()
-        .filter(|c| c.lang.as_ref().map_or(false, |l| l == "en"))
-        .collect();
-    // Filter out promo cards
-    scryfall_cards = scryfall_cards.into_iter().filter(|c| !c.promo).collect();
-    // Filter out funny cards
-    scryfall_cards = scryfall_cards
-        .into_iter()
-        .filter(|c| c.set_type != "funny")
-        .collect();
-    scryfall_cards.sort_by_cached_key(|c| (c.oracle_id.clone(), std::cmp::Reverse(c.released_at)));
-    scryfall_cards.dedup_by_key(|c| c.oracle_id.clone());
-    */
-    // Flatten the card_faces out into scryfall_cards
-    // To do that, we clone and update the image_uris to that of the parent card
-    let mut card_faces = Vec::with_capacity(500);
-    for card in &scryfall_cards {
-        for face in &card.card_faces {
-            let mut face = face.clone();
-            // Copy various attributes from the parent card to the face
-            if face.image_uris.is_empty() {
-                face.image_uris = card.image_uris.clone();
-            }
-            face.set = card.set;
-            face.oracle_id = card.oracle_id.clone();
-            face.id = card.id.clone();
-            face.rarity = card.rarity;
-            face.collector_number = card.collector_number.clone();
-            card_faces.push(face);
+    info!("Streaming Scryfall JSON file @ {}", uri_string);
+    let mut reader = HashingReader::new(BufReader::new(File::open(uri_path)?));
+    // Dropped cards are written to the report as they are dropped
+    let mut report: DropReport<Box<dyn Write>> = match &report_path_string {
+        Some(report_path_string) => {
+            info!("Writing report {}", report_path_string);
+            DropReport::with_writer(Box::new(BufWriter::new(create_file(report_path_string)?)))
+        }
+        None => DropReport::with_writer(Box::new(std::io::sink())),
+    };
+    // The default cards list every printing of a card, which is useful when the
+    // oracle cards are missing cards from upcoming sets. The Arena ids of every
+    // printing are kept, so that Arena logs resolve whichever printing is owned
+    let mut dedup = if default_cards {
+        info!("Deduplicating default cards, preferring {:?}", preference);
+        Some(PrintingDedup::new(preference))
+    } else {
+        None
+    };
+    let mut landlord_cards: Vec<Card> = Vec::new();
+    let mut card_faces: Vec<Card> = Vec::with_capacity(500);
+    let mut newest_release = None;
+    scryfall_cards_from_reader(&mut reader, |card| {
+        // Filter out any cards that are not legal in all formats
+        // This should filter out any tokens
+        // See https://github.com/mtgoncurve/landlord/issues/4
+        if !card.is_legal_anywhere() {
+            report.push(card, DropReason::NotLegal);
+        } else if let Some(dedup) = dedup.as_mut() {
+            dedup.insert(card, &mut report);
+        } else {
+            newest_release = newest_release.max(Some(card.released_at));
+            push_landlord_cards(card, &mut landlord_cards, &mut card_faces);
+        }
+    })?;
+    if let Some(dedup) = dedup {
+        for card in dedup.into_cards() {
+            newest_release = newest_release.max(Some(card.released_at));
+            push_landlord_cards(card, &mut landlord_cards, &mut card_faces);
         }
     }
-    scryfall_cards.extend(card_faces);
+    for (reason, count) in report.counts() {
+        info!("Dropped {} cards: {:?}", count, reason);
+    }
+    report.finish()?;
+    landlord_cards.extend(card_faces);
     info!("Generating landlord output");
-    let landlord_cards: Vec<Card> = scryfall_cards.into_iter().map(|c| c.into()).collect();
     let collection = Collection::from_cards(landlord_cards);
-    info!("Running bincode::serialize on output");
-    let encoded_collection = bincode::serialize(&collection)?;
-    info!("Writing AllSets.landlord");
-    let file: File = OpenOptions::new()
+    let built_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let header = LandlordHeader::new(&collection, built_at, &reader.hex_digest());
+    info!("Writing {}", out_path_string);
+    landlord::data::write_landlord(create_file(out_path_string)?, &header, &collection)?;
+    if args.len() == 5 {
+        let sets_uri_string = &args[3];
+        let sets_out_path_string = &args[4];
+        info!("Loading sets JSON file @ {}", sets_uri_string);
+        let reader = BufReader::new(File::open(Path::new(sets_uri_string))?);
+        let scryfall_sets: ScryfallSetList = serde_json::from_reader(reader)?;
+        // Filter out announced sets that have not released as of the card data
+        let sets = scryfall_sets
+            .data
+            .into_iter()
+            .filter(|s| match newest_release {
+                Some(newest) => s.released_at <= newest,
+                None => true,
+            })
+            .map(|s| s.into())
+            .collect();
+        write_sets(&SetRegistry::from_sets(sets), sets_out_path_string)?;
+    }
+    Ok(())
+}
+
+// Converts the card, and each of its card_faces, to a landlord card
+fn push_landlord_cards(card: ScryfallCard, landlord_cards: &mut Vec<Card>, faces: &mut Vec<Card>) {
+    // Flatten the card_faces out into faces
+    // To do that, we clone and update the image_uris to that of the parent card
+    for face in &card.card_faces {
+        let mut face = face.clone();
+        // Copy various attributes from the parent card to the face
+        if face.image_uris.is_empty() {
+            face.image_uris = card.image_uris.clone();
+        }
+        face.set = card.set;
+        face.oracle_id = card.oracle_id.clone();
+        face.id = card.id.clone();
+        face.rarity = card.rarity;
+        face.collector_number = card.collector_number.clone();
+        faces.push(face.into());
+    }
+    landlord_cards.push(card.into());
+}
+
+// Wraps a reader to hash the bytes read through it, so that the header records
+// the Scryfall bulk data the card database was built from
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    fn hex_digest(&mut self) -> String {
+        // Hash any trailing bytes the JSON deserializer did not need
+        let mut rest = Vec::new();
+        let _ = self.read_to_end(&mut rest);
+        format!("{:x}", self.hasher.clone().finalize())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+fn create_file(out_path_string: &str) -> Result<File, Error> {
+    Ok(OpenOptions::new()
         .write(true)
         .create(true)
-        .open(out_path_string)
-        .unwrap();
-    let mut e = GzEncoder::new(file, Compression::default());
-    info!("Compressing");
-    e.write_all(&encoded_collection[..])?;
-    e.finish()?;
+        .truncate(true)
+        .open(out_path_string)?)
+}
+
+fn write_sets(sets: &SetRegistry, out_path_string: &str) -> Result<(), Error> {
+    info!("Writing {}", out_path_string);
+    landlord::data::write_landlord_sets(create_file(out_path_string)?, sets)?;
     Ok(())
 }
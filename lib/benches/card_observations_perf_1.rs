@@ -3,8 +3,9 @@ extern crate criterion;
 
 use criterion::Criterion;
 use landlord::deck::Deck;
+use landlord::hand::LandEntry;
 use landlord::mulligan::London;
-use landlord::simulation::{Simulation, SimulationConfig};
+use landlord::simulation::{LifePolicy, Simulation, SimulationConfig};
 
 fn criterion_function(c: &mut Criterion) {
     let code = "
@@ -39,6 +40,8 @@ fn criterion_function(c: &mut Criterion) {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: false,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
     });
     c.bench_function("reddit_deck card_observations", |b| {
         b.iter(|| {
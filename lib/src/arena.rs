@@ -0,0 +1,380 @@
+//! # MTG Arena log parser
+//!
+//! Parses the card inventory and saved decks out of the MTG Arena `Player.log`.
+//! Arena only writes the JSON this module reads when "Detailed Logs (Plugin Support)"
+//! is enabled in the Arena account options. The log lives at
+//! `%APPDATA%\..\LocalLow\Wizards Of The Coast\MTGA\Player.log` on Windows and
+//! `~/Library/Logs/Wizards Of The Coast/MTGA/Player.log` on macOS
+//!
+//! ```ignore
+//! let log = std::fs::read_to_string("Player.log")?;
+//! let arena = ArenaLog::parse(&log, &ALL_CARDS);
+//! let inventory = arena.inventory.unwrap();
+//! for deck in &arena.decks {
+//!     let (have, need) = deck.have_need(&inventory);
+//! }
+//! ```
+use crate::card::{Card, GameFormat};
+use crate::collection::Collection;
+use crate::deck::{CardModifiers, Deck, DeckBuilder, DeckCard, Printing};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+/// ArenaLog represents the card inventory and saved decks found in an MTG Arena log
+#[derive(Debug, Clone, Default)]
+pub struct ArenaLog {
+    /// The cards owned by the player, with up to the number of copies owned.
+    /// None if the log does not contain the inventory
+    pub inventory: Option<Deck>,
+    /// The saved decks, titled with the deck name
+    pub decks: Vec<Deck>,
+    /// The Arena ids found in the log that are missing from the collection, such as
+    /// printings missing from the `Card::arena_ids` of the card database
+    pub unknown_arena_ids: BTreeSet<u64>,
+}
+
+// Resolves Arena ids through the printings of the cards in the collection
+struct ArenaCards<'a> {
+    collection: &'a Collection,
+    unknown: BTreeSet<u64>,
+}
+
+impl<'a> ArenaCards<'a> {
+    fn card(&mut self, arena_id: u64) -> Option<&'a Card> {
+        let card = self.collection.card_from_arena_id(arena_id);
+        if card.is_none() {
+            self.unknown.insert(arena_id);
+        }
+        card
+    }
+
+    // Returns a deck from (arena id, count) pairs, adding up the counts of
+    // the printings of the same card
+    fn deck<I>(&mut self, cards: I) -> Deck
+    where
+        I: IntoIterator<Item = (u64, usize)>,
+    {
+        let mut builder = DeckBuilder::new();
+        for (arena_id, count) in cards {
+            if let Some(card) = self.card(arena_id) {
+                // Arena ids identify the printing of the card
+                builder = builder.insert_deck_card(DeckCard {
+                    card: card.clone(),
+                    count,
+                    printing: Printing::of_card(card),
+                    modifiers: CardModifiers::default(),
+                });
+            }
+        }
+        builder.build()
+    }
+}
+
+impl ArenaLog {
+    /// Returns the inventory and saved decks from the contents of an MTG Arena `Player.log`,
+    /// resolving Arena ids against the collection
+    ///
+    /// When the log contains several responses of the same kind, e.g. after the inventory
+    /// changed, the last response wins. Lines that are not valid JSON are ignored
+    pub fn parse(log: &str, collection: &Collection) -> Self {
+        let mut arena_cards = ArenaCards {
+            collection,
+            unknown: BTreeSet::new(),
+        };
+        let mut inventory = None;
+        let mut decks = None;
+        let mut deck_names = HashMap::new();
+        for (method, value) in responses(log) {
+            match method {
+                "PlayerInventory.GetPlayerCardsV3" => {
+                    let cards = payload(&value).as_object().map(|o| {
+                        o.iter()
+                            .filter_map(|(id, count)| {
+                                Some((id.parse().ok()?, count.as_u64()? as usize))
+                            })
+                            .collect::<Vec<_>>()
+                    });
+                    if let Some(cards) = cards {
+                        inventory = Some(cards);
+                    }
+                }
+                "Deck.GetDeckListsV3" => {
+                    if let Some(lists) = payload(&value).as_array() {
+                        decks = Some(lists.iter().filter_map(deck_list_v3).collect::<Vec<_>>());
+                    }
+                }
+                "StartHook" => {
+                    for summary in array(&value["DeckSummariesV2"]) {
+                        if let (Some(id), Some(name)) =
+                            (summary["DeckId"].as_str(), summary_name(summary))
+                        {
+                            deck_names.insert(id.to_string(), name);
+                        }
+                    }
+                    if let Some(hook_decks) = value["Decks"].as_object() {
+                        decks = Some(
+                            hook_decks
+                                .iter()
+                                .map(|(id, deck)| ArenaDeck {
+                                    id: id.clone(),
+                                    name: None,
+                                    format: None,
+                                    main: hook_deck_cards(&deck["MainDeck"]),
+                                    sideboard: hook_deck_cards(&deck["Sideboard"]),
+                                    commander: hook_deck_cards(&deck["CommandZone"]),
+                                    companion: hook_deck_cards(&deck["Companions"]),
+                                })
+                                .collect(),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+        let inventory = inventory.map(|cards| arena_cards.deck(cards));
+        let mut decks: Vec<_> = decks
+            .unwrap_or_default()
+            .into_iter()
+            .map(|arena_deck| {
+                let ArenaDeck {
+                    id,
+                    name,
+                    format,
+                    main,
+                    sideboard,
+                    commander,
+                    companion,
+                } = arena_deck;
+                let mut deck = arena_cards.deck(main);
+                deck.sideboard = arena_cards.deck(sideboard).cards;
+                deck.commander = arena_cards.deck(commander).cards;
+                deck.companion = arena_cards.deck(companion).cards;
+                deck.title = name.or_else(|| deck_names.get(&id).cloned());
+                if let Some(format) = format.as_deref().and_then(game_format) {
+                    deck.format = format;
+                }
+                deck
+            })
+            .collect();
+        decks.sort_by(|a, b| a.title.cmp(&b.title));
+        Self {
+            inventory,
+            decks,
+            unknown_arena_ids: arena_cards.unknown,
+        }
+    }
+}
+
+// A saved deck with unresolved Arena ids
+struct ArenaDeck {
+    id: String,
+    name: Option<String>,
+    format: Option<String>,
+    main: Vec<(u64, usize)>,
+    sideboard: Vec<(u64, usize)>,
+    commander: Vec<(u64, usize)>,
+    companion: Vec<(u64, usize)>,
+}
+
+// Returns each API response in the log as the method name and JSON value, e.g.
+// "[UnityCrossThreadLogger]<== PlayerInventory.GetPlayerCardsV3(12)" followed by the JSON,
+// which starts on the same line or the next line
+fn responses(log: &str) -> Vec<(&str, Value)> {
+    let mut result = Vec::new();
+    let mut offset = 0;
+    while let Some(i) = log[offset..].find("<== ") {
+        let rest = &log[offset + i + 4..];
+        offset += i + 4;
+        let method_end = rest
+            .find(|c: char| c.is_whitespace() || ['(', '{'].contains(&c))
+            .unwrap_or(rest.len());
+        let method = &rest[..method_end];
+        // The JSON starts at the first brace, unless another line comes first
+        let json_start = match rest.find(['{', '[']) {
+            Some(i) if rest[..i].matches('\n').count() <= 1 => i,
+            _ => continue,
+        };
+        let mut values = serde_json::Deserializer::from_str(&rest[json_start..]).into_iter();
+        if let Some(Ok(value)) = values.next() {
+            result.push((method, value));
+            offset += json_start + values.byte_offset();
+        }
+    }
+    result
+}
+
+// Older responses wrap the result in a payload object
+fn payload(value: &Value) -> &Value {
+    match value.get("payload") {
+        Some(payload) => payload,
+        None => value,
+    }
+}
+
+fn array(value: &Value) -> &[Value] {
+    value.as_array().map_or(&[], Vec::as_slice)
+}
+
+// Deck.GetDeckListsV3 lists cards as a flat array of arena id and count pairs
+fn flat_deck_cards(value: &Value) -> Vec<(u64, usize)> {
+    array(value)
+        .chunks(2)
+        .filter_map(|pair| match pair {
+            [id, count] => Some((id.as_u64()?, count.as_u64()? as usize)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn deck_list_v3(value: &Value) -> Option<ArenaDeck> {
+    Some(ArenaDeck {
+        id: value["id"].as_str()?.to_string(),
+        name: value["name"].as_str().map(str::to_string),
+        format: value["format"].as_str().map(str::to_string),
+        main: flat_deck_cards(&value["mainDeck"]),
+        sideboard: flat_deck_cards(&value["sideboard"]),
+        // The command zone and companion are listed by Arena id alone
+        commander: array(&value["commandZoneGRPIds"])
+            .iter()
+            .filter_map(|id| Some((id.as_u64()?, 1)))
+            .collect(),
+        companion: value["companionGRPId"]
+            .as_u64()
+            .filter(|id| *id != 0)
+            .map(|id| (id, 1))
+            .into_iter()
+            .collect(),
+    })
+}
+
+// StartHook lists cards as objects, e.g. {"cardId": 67330, "quantity": 4}
+fn hook_deck_cards(value: &Value) -> Vec<(u64, usize)> {
+    array(value)
+        .iter()
+        .filter_map(|card| {
+            Some((
+                card["cardId"].as_u64()?,
+                card["quantity"].as_u64()? as usize,
+            ))
+        })
+        .collect()
+}
+
+// StartHook deck summaries list the deck name as an attribute, with the format alongside it
+fn summary_name(summary: &Value) -> Option<String> {
+    array(&summary["Attributes"])
+        .iter()
+        .find(|attribute| attribute["name"] == "Name")
+        .and_then(|attribute| attribute["value"].as_str())
+        .map(str::to_string)
+}
+
+// Returns the format of an Arena format name, e.g. "Historic" or "TraditionalStandard"
+fn game_format(name: &str) -> Option<GameFormat> {
+    let name = name.to_lowercase();
+    let name = name.trim_start_matches("traditional");
+    let name = match name {
+        "historicbrawl" => "brawl",
+        name => name,
+    };
+    match serde_json::from_value(Value::String(name.to_string())) {
+        Ok(GameFormat::Other) | Err(_) => None,
+        Ok(format) => Some(format),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::*;
+
+    fn arena_collection() -> Collection {
+        let mut cards = Vec::new();
+        for (name, arena_id) in &[("Shock", 1), ("Negate", 2), ("Island", 3), ("Mountain", 4)] {
+            let mut card = card!(name).clone();
+            card.arena_id = *arena_id;
+            cards.push(card);
+        }
+        Collection::from_cards(cards)
+    }
+
+    #[test]
+    fn parse_inventory_and_deck_lists() {
+        let log = r#"
+[UnityCrossThreadLogger]10/17/2020 10:00:00 AM
+[UnityCrossThreadLogger]<== PlayerInventory.GetPlayerCardsV3(12)
+{ "id": 12, "payload": { "1": 4, "2": 2, "3": 40, "99": 1 } }
+[UnityCrossThreadLogger]<== Deck.GetDeckListsV3(13)
+{ "id": 13, "payload": [
+  { "id": "a", "name": "Izzet Burn", "format": "TraditionalHistoric", "mainDeck": [1, 4, 2, 4, 3, 10, 4, 10], "sideboard": [2, 3], "companionGRPId": 4 },
+  { "id": "b", "name": "Mono Blue", "format": "Standard", "mainDeck": [3, 20], "sideboard": [] }
+] }
+[UnityCrossThreadLogger]<== PlayerInventory.GetPlayerCardsV3(14)
+{ "id": 14, "payload": { "1": 4, "2": 3, "3": 40, "4": 10, "99": 1 } }
+"#;
+        let arena = ArenaLog::parse(log, &arena_collection());
+        let inventory = arena.inventory.unwrap();
+        assert_eq!(inventory.card_count_from_name("Negate").unwrap().count, 3);
+        assert_eq!(
+            inventory.card_count_from_name("Mountain").unwrap().count,
+            10
+        );
+        assert_eq!(arena.decks.len(), 2);
+        let burn = &arena.decks[0];
+        assert_eq!(burn.title.as_deref(), Some("Izzet Burn"));
+        assert_eq!(burn.format, GameFormat::Historic);
+        assert_eq!(burn.len(), 28);
+        assert_eq!(burn.sideboard_len(), 3);
+        assert_eq!(burn.companion[0].card.name, "Mountain");
+        let (have, need) = burn.have_need(&inventory);
+        assert_eq!(have.len(), 27);
+        assert_eq!(need.card_count_from_name("Negate").unwrap().count, 1);
+        assert_eq!(arena.decks[1].title.as_deref(), Some("Mono Blue"));
+        assert_eq!(
+            arena.unknown_arena_ids.iter().collect::<Vec<_>>(),
+            vec![&99]
+        );
+    }
+
+    #[test]
+    fn parse_start_hook() {
+        let log = r#"[UnityCrossThreadLogger]<== StartHook(2) {"InventoryInfo": {"Gems": 100}, "DeckSummariesV2": [{"DeckId": "c", "Attributes": [{"name": "Version", "value": "3"}, {"name": "Name", "value": "Mono Red"}]}], "Decks": {"c": {"MainDeck": [{"cardId": 1, "quantity": 4}, {"cardId": 4, "quantity": 20}], "Sideboard": [{"cardId": 7, "quantity": 2}], "CommandZone": [{"cardId": 2, "quantity": 1}]}}}
+not json <== Nothing
+"#;
+        let arena = ArenaLog::parse(log, &arena_collection());
+        assert!(arena.inventory.is_none());
+        assert_eq!(arena.decks.len(), 1);
+        let deck = &arena.decks[0];
+        assert_eq!(deck.title.as_deref(), Some("Mono Red"));
+        assert_eq!(deck.len(), 24);
+        assert_eq!(deck.sideboard_len(), 0);
+        assert_eq!(deck.commander[0].card.name, "Negate");
+        assert!(arena.unknown_arena_ids.contains(&7));
+    }
+
+    #[test]
+    fn printings_add_up() {
+        let mut collection = arena_collection();
+        collection.modify_cards(|cards| {
+            for card in cards.iter_mut().filter(|card| card.name == "Shock") {
+                card.arena_ids = vec![1, 10, 11];
+            }
+        });
+        let log = r#"[UnityCrossThreadLogger]<== PlayerInventory.GetPlayerCardsV3(12)
+{ "1": 1, "10": 2, "11": 1, "12": 4 }
+[UnityCrossThreadLogger]<== Deck.GetDeckListsV3(13)
+[{ "id": "a", "name": "Shocks", "mainDeck": [10, 2, 11, 2, 4, 20] }]
+"#;
+        let arena = ArenaLog::parse(log, &collection);
+        let inventory = arena.inventory.unwrap();
+        assert_eq!(inventory.card_count_from_name("Shock").unwrap().count, 4);
+        let deck = &arena.decks[0];
+        assert_eq!(deck.card_count_from_name("Shock").unwrap().count, 4);
+        assert_eq!(deck.len(), 24);
+        let (_, need) = deck.have_need(&inventory);
+        assert!(need.card_count_from_name("Shock").is_none());
+        assert_eq!(
+            arena.unknown_arena_ids.iter().collect::<Vec<_>>(),
+            vec![&12]
+        );
+    }
+}
@@ -1,11 +1,12 @@
 //! # Internal card representation
 //!
 pub use crate::card::mana_cost::*;
-pub use crate::scryfall::{GameFormat, Legality, Object, Rarity, SetCode};
+pub use crate::scryfall::{GameFormat, Legality, Object, Rarity};
+pub use crate::set::SetCode;
+use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
 
 // TODO: [image_uri] Consider storing only the suffix and concatenate with the hostname on the UI side
-// TODO: [mana_cost_string] Remove mana_cost_string and generate the string from a ManaCost
 // TODO: [mana_cost] Remove mana_cost and use all_mana_costs[0]
 // NOTE: PartialEq and Eq are implemented below
 /// Card represents a Magic: The Gathering card
@@ -15,7 +16,8 @@ pub struct Card {
     pub name: String,
     /// Scryfall oracle id
     pub oracle_id: String,
-    /// String representing the card mana cost, in "{X}{R}{R}" style format
+    /// String representing the printed card mana cost, in "{X}{R}{R}" style format.
+    /// Unlike `mana_cost.to_string()`, it keeps hybrid, Phyrexian and variable symbols
     pub mana_cost_string: String,
     /// A URI to an image of the card
     pub image_uri: String,
@@ -31,12 +33,70 @@ pub struct Card {
     pub all_mana_costs: Vec<ManaCost>,
     /// Arena id
     pub arena_id: u64,
+    /// The Arena ids of every printing of the card on Arena, including `arena_id`
+    pub arena_ids: Vec<u64>,
     /// Card rarity
     pub rarity: Rarity,
     /// Card release set code
     pub set: SetCode,
+    /// The collector number of the card printing in its set, e.g. "22"
+    pub collector_number: String,
     /// True if this card is a sub face
     pub is_face: bool,
+    /// The mana a nonland card produces once it is on the battlefield, such as
+    /// a [mana dork](https://mtg.gamepedia.com/Mana_dork) or a mana rock.
+    /// The card taps for mana starting the turn after it is cast
+    pub produced_mana: Option<ManaCost>,
+    /// The amount of mana the card adds each time it taps for `produced_mana`,
+    /// less the mana paid to activate the ability, e.g. 2 for Sol Ring
+    pub produced_mana_amount: u8,
+    /// The [basic land types](https://mtg.gamepedia.com/Basic_land_type) of a land card,
+    /// where Forest is represented by green, Island by blue, and so on
+    pub land_types: ManaCost,
+    /// The lands a [ramp](https://mtg.gamepedia.com/Ramp) spell or
+    /// [fetch land](https://mtg.gamepedia.com/Fetchland) searches the library for
+    pub ramp: Option<Ramp>,
+    /// The card legality in each format. Empty if the card database has no
+    /// legality data for the card
+    pub legalities: BTreeMap<GameFormat, Legality>,
+    /// The type line, e.g. "Legendary Creature — Elf Druid"
+    pub type_line: String,
+    /// The oracle text. For cards with multiple faces, the text of each face separated by a newline
+    pub oracle_text: String,
+    /// The card [colors](https://mtg.gamepedia.com/Color), in the same representation as `land_types`
+    pub colors: ManaCost,
+    /// The card [color identity](https://mtg.gamepedia.com/Color_identity), in the same
+    /// representation as `land_types`
+    pub color_identity: ManaCost,
+    /// The Scryfall [layout](https://scryfall.com/docs/api/layouts), e.g. "normal" or "transform"
+    pub layout: String,
+}
+
+/// Ramp represents a spell or land that searches the library for land cards and
+/// puts them onto the battlefield, such as Rampant Growth, Cultivate or Evolving Wilds
+#[derive(
+    Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Ramp {
+    /// The basic land types the lands may have, in the same representation as
+    /// `Card::land_types`. No types means a land of any type
+    pub land_types: ManaCost,
+    /// True if the lands must be basic lands
+    pub basic_only: bool,
+    /// The number of lands put onto the battlefield
+    pub battlefield_count: u8,
+    /// True if the lands put onto the battlefield enter tapped
+    pub tapped: bool,
+    /// The number of lands put into the hand
+    pub hand_count: u8,
+}
+
+impl Ramp {
+    /// Returns true if the land can be found by this ramp spell
+    pub fn can_find(&self, land_kind: CardKind, land_types: &ManaCost) -> bool {
+        (!self.basic_only || land_kind == CardKind::BasicLand)
+            && (self.land_types.bits == 0 || self.land_types.color_contribution(land_types) > 0)
+    }
 }
 
 /// CardKind represents an internal card type representation.
@@ -50,6 +110,8 @@ pub enum CardKind {
     ShockLand = 3,
     OtherLand = 4,
     ForcedLand = 5,
+    FastLand = 6,
+    SlowLand = 7,
     // Other
     Creature,
     Spell,
@@ -72,13 +134,30 @@ impl Card {
         self.mana_cost.cmc()
     }
 
+    /// Returns the Arena ids of the printings of the card on Arena, `arena_id` first
+    pub fn all_arena_ids(&self) -> impl Iterator<Item = u64> + '_ {
+        Some(self.arena_id).filter(|id| *id != 0).into_iter().chain(
+            self.arena_ids
+                .iter()
+                .copied()
+                .filter(move |id| *id != self.arena_id),
+        )
+    }
+
     /// Returns true if the card type is a land
     pub fn is_land(&self) -> bool {
         self.kind.is_land()
     }
 
+    /// Returns the card legality in the format, or None if the legality is unknown
+    pub fn legality(&self, format: GameFormat) -> Option<Legality> {
+        self.legalities.get(&format).copied()
+    }
+
+    /// Returns true if the card set is in Standard, see
+    /// [`SetRegistry::in_standard`](../../set/struct.SetRegistry.html#method.in_standard)
     pub fn in_standard(&self) -> bool {
-        self.set.in_standard()
+        crate::data::ALL_SETS.in_standard(self.set)
     }
 }
 
@@ -119,6 +198,8 @@ impl CardKind {
             || self == Self::TapLand
             || self == Self::OtherLand
             || self == Self::ForcedLand
+            || self == Self::FastLand
+            || self == Self::SlowLand
     }
 }
 
@@ -157,14 +238,14 @@ mod tests {
         assert_eq!(card.all_mana_costs[0].g, 0);
         assert_eq!(card.all_mana_costs[0].r, 1);
         assert_eq!(card.all_mana_costs[0].w, 0);
-        assert_eq!(card.all_mana_costs[0].c, 0);
+        assert_eq!(card.all_mana_costs[0].generic, 0);
 
         assert_eq!(card.all_mana_costs[1].b, 1);
         assert_eq!(card.all_mana_costs[1].u, 0);
         assert_eq!(card.all_mana_costs[1].g, 0);
         assert_eq!(card.all_mana_costs[1].r, 0);
         assert_eq!(card.all_mana_costs[1].w, 0);
-        assert_eq!(card.all_mana_costs[1].c, 0);
+        assert_eq!(card.all_mana_costs[1].generic, 0);
     }
 
     #[test]
@@ -183,7 +264,7 @@ mod tests {
     fn card_sulfur_falls() {
         let card = card!("Sulfur Falls");
         assert_eq!(card.is_land(), true);
-        assert_eq!(card.kind, CardKind::OtherLand);
+        assert_eq!(card.kind, CardKind::CheckLand);
         assert_eq!(card.turn, 2);
         assert_eq!(card.mana_cost.b, 0);
         assert_eq!(card.mana_cost.u, 1);
@@ -214,7 +295,7 @@ mod tests {
         assert_eq!(card.turn, 4);
         assert_eq!(card.mana_cost.b, 0);
         assert_eq!(card.mana_cost.u, 1);
-        assert_eq!(card.mana_cost.c, 1);
+        assert_eq!(card.mana_cost.generic, 1);
         assert_eq!(card.mana_cost.g, 1);
         assert_eq!(card.mana_cost.r, 0);
         assert_eq!(card.mana_cost.w, 1);
@@ -366,7 +447,7 @@ mod tests {
         assert_eq!(card.is_land(), false);
         assert_eq!(card.mana_cost.b, 0);
         assert_eq!(card.mana_cost.u, 0);
-        assert_eq!(card.mana_cost.c, 10);
+        assert_eq!(card.mana_cost.generic, 10);
         assert_eq!(card.mana_cost.g, 2);
         assert_eq!(card.mana_cost.r, 0);
         assert_eq!(card.mana_cost.w, 0);
@@ -379,7 +460,7 @@ mod tests {
         assert_eq!(card.turn, 4);
         assert_eq!(card.mana_cost.b, 1);
         assert_eq!(card.mana_cost.u, 1);
-        assert_eq!(card.mana_cost.c, 1);
+        assert_eq!(card.mana_cost.generic, 1);
         assert_eq!(card.mana_cost.g, 0);
         assert_eq!(card.mana_cost.r, 1);
         assert_eq!(card.mana_cost.w, 0);
@@ -404,7 +485,7 @@ mod tests {
         assert_eq!(card.is_land(), false);
         assert_eq!(card.mana_cost.b, 0);
         assert_eq!(card.mana_cost.u, 0);
-        assert_eq!(card.mana_cost.c, 2);
+        assert_eq!(card.mana_cost.generic, 2);
         assert_eq!(card.mana_cost.g, 0);
         assert_eq!(card.mana_cost.r, 0);
         assert_eq!(card.mana_cost.w, 0);
@@ -417,7 +498,7 @@ mod tests {
         assert_eq!(card.is_land(), false);
         assert_eq!(card.mana_cost.b, 0);
         assert_eq!(card.mana_cost.u, 1);
-        assert_eq!(card.mana_cost.c, 1);
+        assert_eq!(card.mana_cost.generic, 1);
         assert_eq!(card.mana_cost.g, 0);
         assert_eq!(card.mana_cost.r, 0);
         assert_eq!(card.mana_cost.w, 0);
@@ -439,7 +520,7 @@ mod tests {
         assert_eq!(card.turn, 5);
         assert_eq!(card.mana_cost.b, 0);
         assert_eq!(card.mana_cost.u, 1);
-        assert_eq!(card.mana_cost.c, 3);
+        assert_eq!(card.mana_cost.generic, 3);
         assert_eq!(card.mana_cost.g, 0);
         assert_eq!(card.mana_cost.r, 0);
         assert_eq!(card.mana_cost.w, 1);
@@ -452,7 +533,7 @@ mod tests {
         assert_eq!(card.turn, 2);
         assert_eq!(card.mana_cost.b, 0);
         assert_eq!(card.mana_cost.u, 1);
-        assert_eq!(card.mana_cost.c, 1);
+        assert_eq!(card.mana_cost.generic, 1);
         assert_eq!(card.mana_cost.g, 0);
         assert_eq!(card.mana_cost.r, 0);
         assert_eq!(card.mana_cost.w, 0);
@@ -479,7 +560,7 @@ mod tests {
         assert_eq!(card.turn, 5);
         assert_eq!(card.mana_cost.b, 0);
         assert_eq!(card.mana_cost.u, 0);
-        assert_eq!(card.mana_cost.c, 3);
+        assert_eq!(card.mana_cost.generic, 3);
         assert_eq!(card.mana_cost.g, 0);
         assert_eq!(card.mana_cost.r, 0);
         assert_eq!(card.mana_cost.w, 2);
@@ -496,14 +577,14 @@ mod tests {
         assert_eq!(card.turn, 2);
         assert_eq!(card.all_mana_costs[0].b, 1);
         assert_eq!(card.all_mana_costs[0].u, 0);
-        assert_eq!(card.all_mana_costs[0].c, 1);
+        assert_eq!(card.all_mana_costs[0].generic, 1);
         assert_eq!(card.all_mana_costs[0].g, 0);
         assert_eq!(card.all_mana_costs[0].r, 0);
         assert_eq!(card.all_mana_costs[0].w, 0);
 
         assert_eq!(card.all_mana_costs[1].b, 0);
         assert_eq!(card.all_mana_costs[1].u, 1);
-        assert_eq!(card.all_mana_costs[1].c, 1);
+        assert_eq!(card.all_mana_costs[1].generic, 1);
         assert_eq!(card.all_mana_costs[1].g, 0);
         assert_eq!(card.all_mana_costs[1].r, 0);
         assert_eq!(card.all_mana_costs[1].w, 0);
@@ -520,20 +601,20 @@ mod tests {
 
         assert_eq!(card.all_mana_costs[0].b, 0);
         assert_eq!(card.all_mana_costs[0].u, 0);
-        assert_eq!(card.all_mana_costs[0].c, 0);
+        assert_eq!(card.all_mana_costs[0].generic, 0);
         assert_eq!(card.all_mana_costs[0].g, 2);
         assert_eq!(card.all_mana_costs[0].r, 0);
         assert_eq!(card.all_mana_costs[0].w, 0);
 
         assert_eq!(card.all_mana_costs[1].u, 0);
-        assert_eq!(card.all_mana_costs[1].c, 0);
+        assert_eq!(card.all_mana_costs[1].generic, 0);
         assert_eq!(card.all_mana_costs[1].g, 0);
         assert_eq!(card.all_mana_costs[1].r, 0);
         assert_eq!(card.all_mana_costs[1].w, 0);
         assert_eq!(card.all_mana_costs[1].b, 2);
 
         assert_eq!(card.all_mana_costs[2].u, 0);
-        assert_eq!(card.all_mana_costs[2].c, 0);
+        assert_eq!(card.all_mana_costs[2].generic, 0);
         assert_eq!(card.all_mana_costs[2].g, 1);
         assert_eq!(card.all_mana_costs[2].r, 0);
         assert_eq!(card.all_mana_costs[2].w, 0);
@@ -547,7 +628,7 @@ mod tests {
         assert_eq!(card.turn, 5);
         assert_eq!(card.mana_cost.b, 1);
         assert_eq!(card.mana_cost.u, 1);
-        assert_eq!(card.mana_cost.c, 3);
+        assert_eq!(card.mana_cost.generic, 3);
         assert_eq!(card.mana_cost.g, 0);
         assert_eq!(card.mana_cost.r, 0);
         assert_eq!(card.mana_cost.w, 0);
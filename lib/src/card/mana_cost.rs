@@ -1,6 +1,11 @@
+use crate::card::{mana_symbols_from_str, ManaSymbol, ManaSymbolError};
 use std::collections::HashSet;
 
 /// ManaCost represents the card [mana cost](https://mtg.gamepedia.com/Mana_cost)
+///
+/// For spells, `c` counts colorless-specific {C} pips and `generic` counts generic pips,
+/// and `life` counts the life paid in place of [Phyrexian](https://mtg.gamepedia.com/Phyrexian_mana) pips.
+/// For lands, the color fields (including `c`) represent the mana the land can produce
 #[derive(
   Default, Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
 )]
@@ -12,6 +17,8 @@ pub struct ManaCost {
   pub u: u8,
   pub g: u8,
   pub c: u8,
+  pub generic: u8,
+  pub life: u8,
 }
 
 /// ManaColor represents a [color](https://mtg.gamepedia.com/Color)
@@ -55,19 +62,28 @@ impl ManaCost {
       u: 0,
       g: 0,
       c: 0,
+      generic: 0,
+      life: 0,
     }
   }
 
   /// Returns a new ManaCost with the given color counts
   pub fn from_rgbuwc(r: u8, g: u8, b: u8, u: u8, w: u8, c: u8) -> Self {
+    Self::from_rgbuwc_generic(r, g, b, u, w, c, 0)
+  }
+
+  /// Returns a new ManaCost with the given color and generic counts
+  pub fn from_rgbuwc_generic(r: u8, g: u8, b: u8, u: u8, w: u8, c: u8, generic: u8) -> Self {
     Self {
-      bits: Self::calculate_signature_rgbuwc(r, g, b, u, w, c),
+      bits: Self::calculate_signature(r, g, b, u, w, c, generic),
       r,
       w,
       b,
       u,
       g,
       c,
+      generic,
+      life: 0,
     }
   }
 
@@ -77,20 +93,24 @@ impl ManaCost {
     (self.bits & other.bits).count_ones()
   }
 
-  /// Returns the converted mana cost
+  /// Returns the converted mana cost, which does not include any life paid.
+  /// Saturates at `u8::MAX`, like `Add`
   #[inline]
   pub fn cmc(self) -> u8 {
-    self.r + self.w + self.b + self.u + self.g + self.c
+    [self.w, self.b, self.u, self.g, self.c, self.generic]
+      .iter()
+      .fold(self.r, |cmc, n| cmc.saturating_add(*n))
   }
 
   #[inline]
   pub fn update_bits(mut self) -> Self {
-    self.bits = Self::calculate_signature_rgbuwc(self.r, self.g, self.b, self.u, self.w, self.c);
+    self.bits =
+      Self::calculate_signature(self.r, self.g, self.b, self.u, self.w, self.c, self.generic);
     self
   }
 
   #[inline]
-  fn calculate_signature_rgbuwc(r: u8, g: u8, b: u8, u: u8, w: u8, c: u8) -> u8 {
+  fn calculate_signature(r: u8, g: u8, b: u8, u: u8, w: u8, c: u8, generic: u8) -> u8 {
     use std::cmp::min;
     (min(1, r) << 0 & Self::R_BITS)
       | (min(1, g) << 1 & Self::G_BITS)
@@ -98,6 +118,7 @@ impl ManaCost {
       | (min(1, u) << 3 & Self::U_BITS)
       | (min(1, w) << 4 & Self::W_BITS)
       | (min(1, c) << 5 & Self::C_BITS)
+      | (min(1, generic) << 6 & Self::GENERIC_BITS)
   }
 
   pub const R_BITS: u8 = 0b0000_0001;
@@ -106,10 +127,172 @@ impl ManaCost {
   pub const U_BITS: u8 = 0b0000_1000;
   pub const W_BITS: u8 = 0b0001_0000;
   pub const C_BITS: u8 = 0b0010_0000;
+  pub const GENERIC_BITS: u8 = 0b0100_0000;
+}
+
+impl ManaCost {
+  /// Returns what is left of self after paying other, or None if any field
+  /// of other is larger than the same field of self
+  ///
+  /// Every field is subtracted separately, so generic mana in other is only
+  /// paid by generic mana in self
+  pub fn checked_sub(self, other: Self) -> Option<Self> {
+    Some(
+      Self {
+        bits: 0,
+        r: self.r.checked_sub(other.r)?,
+        w: self.w.checked_sub(other.w)?,
+        b: self.b.checked_sub(other.b)?,
+        u: self.u.checked_sub(other.u)?,
+        g: self.g.checked_sub(other.g)?,
+        c: self.c.checked_sub(other.c)?,
+        generic: self.generic.checked_sub(other.generic)?,
+        life: self.life.checked_sub(other.life)?,
+      }
+      .update_bits(),
+    )
+  }
+
+  /// Returns the sum of self and other, or None if any field overflows
+  pub fn checked_add(self, other: Self) -> Option<Self> {
+    Some(
+      Self {
+        bits: 0,
+        r: self.r.checked_add(other.r)?,
+        w: self.w.checked_add(other.w)?,
+        b: self.b.checked_add(other.b)?,
+        u: self.u.checked_add(other.u)?,
+        g: self.g.checked_add(other.g)?,
+        c: self.c.checked_add(other.c)?,
+        generic: self.generic.checked_add(other.generic)?,
+        life: self.life.checked_add(other.life)?,
+      }
+      .update_bits(),
+    )
+  }
+}
+
+/// Adds every field separately, saturating at `u8::MAX`. Use
+/// [`checked_add`](struct.ManaCost.html#method.checked_add) to detect an overflow
+impl std::ops::Add for ManaCost {
+  type Output = Self;
+
+  fn add(self, other: Self) -> Self {
+    Self {
+      bits: 0,
+      r: self.r.saturating_add(other.r),
+      w: self.w.saturating_add(other.w),
+      b: self.b.saturating_add(other.b),
+      u: self.u.saturating_add(other.u),
+      g: self.g.saturating_add(other.g),
+      c: self.c.saturating_add(other.c),
+      generic: self.generic.saturating_add(other.generic),
+      life: self.life.saturating_add(other.life),
+    }
+    .update_bits()
+  }
+}
+
+impl std::ops::AddAssign for ManaCost {
+  fn add_assign(&mut self, other: Self) {
+    *self = *self + other;
+  }
+}
+
+/// Subtracts every field separately, saturating at 0. Use
+/// [`checked_sub`](struct.ManaCost.html#method.checked_sub) to detect a cost that cannot be paid
+impl std::ops::Sub for ManaCost {
+  type Output = Self;
+
+  fn sub(self, other: Self) -> Self {
+    Self {
+      bits: 0,
+      r: self.r.saturating_sub(other.r),
+      w: self.w.saturating_sub(other.w),
+      b: self.b.saturating_sub(other.b),
+      u: self.u.saturating_sub(other.u),
+      g: self.g.saturating_sub(other.g),
+      c: self.c.saturating_sub(other.c),
+      generic: self.generic.saturating_sub(other.generic),
+      life: self.life.saturating_sub(other.life),
+    }
+    .update_bits()
+  }
+}
+
+impl std::ops::SubAssign for ManaCost {
+  fn sub_assign(&mut self, other: Self) {
+    *self = *self - other;
+  }
+}
+
+impl std::iter::Sum for ManaCost {
+  fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+    iter.fold(Self::new(), |acc, cost| acc + cost)
+  }
+}
+
+impl<'a> std::iter::Sum<&'a ManaCost> for ManaCost {
+  fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+    iter.copied().sum()
+  }
+}
+
+/// Formats the cost in canonical order, generic first followed by {W}{U}{B}{R}{G}
+/// and {C}, e.g. "{2}{U}{U}". A cost worth nothing is formatted as "{0}"
+///
+/// Life is not part of the formatted string, since the color of the Phyrexian
+/// pips it paid for is not known
+impl std::fmt::Display for ManaCost {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if self.generic > 0 || self.cmc() == 0 {
+      write!(f, "{{{}}}", self.generic)?;
+    }
+    for (count, symbol) in &[
+      (self.w, "W"),
+      (self.u, "U"),
+      (self.b, "B"),
+      (self.r, "R"),
+      (self.g, "G"),
+      (self.c, "C"),
+    ] {
+      for _ in 0..*count {
+        write!(f, "{{{}}}", symbol)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Parses a mana cost string, e.g. "{2}{U}{U}", into the first of its potential
+/// mana costs, see [`mana_costs_from_str`](fn.mana_costs_from_str.html)
+impl std::str::FromStr for ManaCost {
+  type Err = ManaSymbolError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(mana_costs_from_str(s)?[0])
+  }
 }
 
-pub fn mana_costs_from_str(mana_cost_str: &str) -> Vec<ManaCost> {
-  let symbol_stack = mana_cost_symbols_from_str(mana_cost_str);
+/// Returns all potential mana cost combinations of a mana cost string, e.g. "{1}{B/G}"
+///
+/// Split card costs, e.g. "{B} // {2}{B}{R}", return the combinations of every face.
+/// Variable symbols such as {X} are worth 1 generic mana
+pub fn mana_costs_from_str(mana_cost_str: &str) -> Result<Vec<ManaCost>, ManaSymbolError> {
+  let mut results = Vec::new();
+  for face in mana_cost_str.split("//") {
+    let symbols = mana_symbols_from_str(face)?;
+    results.extend(mana_costs_from_symbols(&symbols, 1));
+  }
+  sort_mana_costs(&mut results);
+  results.dedup();
+  Ok(results)
+}
+
+/// Returns all potential mana cost combinations of the symbols, where
+/// every variable symbol is worth `x` generic mana
+pub fn mana_costs_from_symbols(symbols: &[ManaSymbol], x: u8) -> Vec<ManaCost> {
+  let symbol_stack: Vec<_> = symbols.iter().map(|s| s.mana_costs(x)).collect();
   // NOTE: The hashset ensures that we do not double count
   // the same mana cost multiple times. This is important for cards
   // that have multiple split costs, like Find {B/G}{B/G} -- i.e. we
@@ -118,103 +301,38 @@ pub fn mana_costs_from_str(mana_cost_str: &str) -> Vec<ManaCost> {
   mana_costs_from_str_recur(&mut results, ManaCost::new(), &symbol_stack, 0);
   // Guarantee the resulting order by sorting
   let mut results_as_vec: Vec<_> = results.into_iter().collect();
-  results_as_vec.sort();
+  sort_mana_costs(&mut results_as_vec);
   results_as_vec
 }
 
+// Costs that pay the least life come first, so that the first cost
+// of a card is the cost paid entirely with mana
+fn sort_mana_costs(costs: &mut [ManaCost]) {
+  costs.sort_by_key(|cost| (cost.life, *cost));
+}
+
 fn mana_costs_from_str_recur(
   results: &mut HashSet<ManaCost>,
   current: ManaCost,
-  symbol_stack: &[(ManaCost, Option<ManaCost>)],
+  symbol_stack: &[Vec<ManaCost>],
   idx: usize,
 ) {
   if symbol_stack.len() <= idx {
-    let current = current.update_bits();
     results.insert(current);
     return;
   }
-  let mut left = symbol_stack[idx].0;
-  left.r += current.r;
-  left.g += current.g;
-  left.b += current.b;
-  left.u += current.u;
-  left.w += current.w;
-  left.c += current.c;
-  mana_costs_from_str_recur(results, left, symbol_stack, idx + 1);
-  if let Some(mut right) = symbol_stack[idx].1 {
-    right.r += current.r;
-    right.g += current.g;
-    right.b += current.b;
-    right.u += current.u;
-    right.w += current.w;
-    right.c += current.c;
-    mana_costs_from_str_recur(results, right, symbol_stack, idx + 1);
+  for cost in &symbol_stack[idx] {
+    mana_costs_from_str_recur(results, current + *cost, symbol_stack, idx + 1);
   }
 }
 
-fn mana_cost_symbols_from_str(mana_cost_str: &str) -> Vec<(ManaCost, Option<ManaCost>)> {
-  let mut sigil = String::new();
-  let mut symbol_stack: Vec<(ManaCost, Option<ManaCost>)> = Vec::new();
-  let mut should_push_right = false;
-  let mut idx = 0;
-
-  for c in mana_cost_str.chars() {
-    match c {
-      '{' => {
-        sigil.clear();
-        symbol_stack.push((ManaCost::new(), None));
-        idx = symbol_stack.len() - 1;
-        should_push_right = false;
-      }
-      '/' | '\\' => {
-        let color = ManaColor::from_str(&sigil);
-        let count = sigil.parse::<u8>().unwrap_or(1);
-        let mut cost = ManaCost::new();
-        match color {
-          ManaColor::Black => cost.b += count,
-          ManaColor::Blue => cost.u += count,
-          ManaColor::Green => cost.g += count,
-          ManaColor::Red => cost.r += count,
-          ManaColor::White => cost.w += count,
-          ManaColor::Colorless => cost.c += count,
-        }
-        symbol_stack[idx].0 = cost;
-        should_push_right = true;
-        sigil.clear();
-      }
-      '}' => {
-        let color = ManaColor::from_str(&sigil);
-        let count = sigil.parse::<u8>().unwrap_or(1);
-        let mut cost = ManaCost::new();
-        match color {
-          ManaColor::Black => cost.b += count,
-          ManaColor::Blue => cost.u += count,
-          ManaColor::Green => cost.g += count,
-          ManaColor::Red => cost.r += count,
-          ManaColor::White => cost.w += count,
-          ManaColor::Colorless => cost.c += count,
-        }
-        if should_push_right {
-          symbol_stack[idx].1 = Some(cost);
-        } else {
-          symbol_stack[idx].0 = cost;
-        }
-      }
-      c => {
-        sigil.push(c);
-      }
-    }
-  }
-  symbol_stack
-}
-
 #[cfg(test)]
 mod tests {
   use crate::card::mana_cost::*;
 
   #[test]
   fn empty_string() {
-    let res = mana_costs_from_str("");
+    let res = mana_costs_from_str("").unwrap();
     assert_eq!(res.len(), 1);
     assert_eq!(res[0].c, 0);
     assert_eq!(res[0].r, 0);
@@ -227,9 +345,10 @@ mod tests {
 
   #[test]
   fn simple_test_0() {
-    let res = mana_costs_from_str("{1}{U}");
+    let res = mana_costs_from_str("{1}{U}").unwrap();
     assert_eq!(res.len(), 1);
-    assert_eq!(res[0].c, 1);
+    assert_eq!(res[0].generic, 1);
+    assert_eq!(res[0].c, 0);
     assert_eq!(res[0].r, 0);
     assert_eq!(res[0].w, 0);
     assert_eq!(res[0].b, 0);
@@ -239,9 +358,10 @@ mod tests {
 
   #[test]
   fn x_test_0() {
-    let res = mana_costs_from_str("{X}{U}");
+    let res = mana_costs_from_str("{X}{U}").unwrap();
     assert_eq!(res.len(), 1);
-    assert_eq!(res[0].c, 1);
+    assert_eq!(res[0].generic, 1);
+    assert_eq!(res[0].c, 0);
     assert_eq!(res[0].r, 0);
     assert_eq!(res[0].w, 0);
     assert_eq!(res[0].b, 0);
@@ -252,7 +372,7 @@ mod tests {
   // Hybrid mana is of the for {B/R}
   #[test]
   fn hybrid_test_0() {
-    let res = mana_costs_from_str("{B/R}");
+    let res = mana_costs_from_str("{B/R}").unwrap();
     assert_eq!(res.len(), 2);
     //
     assert_eq!(res[0].c, 0);
@@ -270,15 +390,12 @@ mod tests {
     assert_eq!(res[1].g, 0);
   }
 
-  // NOTE: Split cards are not handled correctly
   // Split cards are those that have multiple card faces, such as Carnival // Carnage
-  // The mana cost of this card looks like "{B/R} // {2}{B}{R}", which the code currently
-  // doesn't parse correctly. In practice, this isn't an issue since we only ever evaluate
-  // the individual card faces
+  // The mana cost of the card contains the cost of each face, and we return the
+  // costs of every face
   #[test]
-  #[should_panic]
   fn split_test_0() {
-    let res = mana_costs_from_str("{B} // {2}{B}{R}");
+    let res = mana_costs_from_str("{B} // {2}{B}{R}").unwrap();
     assert_eq!(res.len(), 2);
     //
     assert_eq!(res[0].c, 0);
@@ -288,11 +405,124 @@ mod tests {
     assert_eq!(res[0].u, 0);
     assert_eq!(res[0].g, 0);
     //
-    assert_eq!(res[1].c, 2);
+    assert_eq!(res[1].generic, 2);
+    assert_eq!(res[1].c, 0);
     assert_eq!(res[1].r, 1);
     assert_eq!(res[1].w, 0);
     assert_eq!(res[1].b, 1);
     assert_eq!(res[1].u, 0);
     assert_eq!(res[1].g, 0);
   }
+
+  #[test]
+  fn twobrid_test_0() {
+    let res = mana_costs_from_str("{2/W}{2/W}").unwrap();
+    assert_eq!(
+      res,
+      vec![
+        ManaCost::from_rgbuwc_generic(0, 0, 0, 0, 2, 0, 0),
+        ManaCost::from_rgbuwc_generic(0, 0, 0, 0, 0, 0, 4),
+        ManaCost::from_rgbuwc_generic(0, 0, 0, 0, 1, 0, 2),
+      ]
+    );
+  }
+
+  #[test]
+  fn x_test_1() {
+    let symbols = mana_symbols_from_str("{X}{X}{G}").unwrap();
+    let res = mana_costs_from_symbols(&symbols, 3);
+    assert_eq!(
+      res,
+      vec![ManaCost::from_rgbuwc_generic(0, 1, 0, 0, 0, 0, 6)]
+    );
+  }
+
+  #[test]
+  fn colorless_test_0() {
+    let res = mana_costs_from_str("{2}{C}{C}").unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].c, 2);
+    assert_eq!(res[0].generic, 2);
+    assert_eq!(res[0].cmc(), 4);
+    assert_eq!(res[0].bits, ManaCost::C_BITS | ManaCost::GENERIC_BITS);
+  }
+
+  #[test]
+  fn unknown_symbol_test_0() {
+    let res = mana_costs_from_str("{HW}");
+    assert_eq!(res, Err(ManaSymbolError::UnknownSymbol("HW".to_string())));
+  }
+
+  #[test]
+  fn add_and_sum() {
+    let cost =
+      ManaCost::from_rgbuwc(1, 0, 0, 1, 0, 0) + ManaCost::from_rgbuwc_generic(0, 0, 0, 1, 0, 0, 2);
+    assert_eq!(cost, ManaCost::from_rgbuwc_generic(1, 0, 0, 2, 0, 0, 2));
+    let costs = [
+      ManaCost::from_rgbuwc(1, 0, 0, 0, 0, 0),
+      ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+      ManaCost::from_rgbuwc(1, 0, 0, 0, 0, 1),
+    ];
+    assert_eq!(
+      costs.iter().sum::<ManaCost>(),
+      ManaCost::from_rgbuwc(2, 1, 0, 0, 0, 1)
+    );
+    assert_eq!(
+      Vec::<ManaCost>::new().into_iter().sum::<ManaCost>(),
+      ManaCost::new()
+    );
+  }
+
+  #[test]
+  fn add_saturates_and_checked_add() {
+    let big = ManaCost::from_rgbuwc_generic(200, 0, 0, 1, 0, 0, 255);
+    let more = ManaCost::from_rgbuwc_generic(100, 0, 0, 1, 0, 0, 1);
+    let sum = big + more;
+    assert_eq!(sum, ManaCost::from_rgbuwc_generic(255, 0, 0, 2, 0, 0, 255));
+    assert_eq!(big.checked_add(more), None);
+    let small = ManaCost::from_rgbuwc(1, 0, 0, 1, 0, 0);
+    assert_eq!(small.checked_add(small), Some(small + small));
+    let mut total = big;
+    total += more;
+    assert_eq!(total, sum);
+    assert_eq!(sum.cmc(), u8::MAX);
+  }
+
+  #[test]
+  fn sub_and_checked_sub() {
+    let pool = ManaCost::from_rgbuwc_generic(0, 0, 0, 2, 0, 0, 2);
+    let paid = ManaCost::from_rgbuwc_generic(0, 0, 0, 1, 0, 0, 2);
+    let left = ManaCost::from_rgbuwc(0, 0, 0, 1, 0, 0);
+    assert_eq!(pool.checked_sub(paid), Some(left));
+    assert_eq!(pool - paid, left);
+    assert_eq!((pool - paid).bits, ManaCost::U_BITS);
+    let red = ManaCost::from_rgbuwc(1, 0, 0, 0, 0, 0);
+    assert_eq!(pool.checked_sub(red), None);
+    assert_eq!(red - pool, red);
+  }
+
+  #[test]
+  fn display() {
+    let cost: ManaCost = "{U}{2}{U}".parse().unwrap();
+    assert_eq!(cost.to_string(), "{2}{U}{U}");
+    assert_eq!(ManaCost::new().to_string(), "{0}");
+    assert_eq!(
+      ManaCost::from_rgbuwc_generic(1, 1, 1, 1, 1, 1, 10).to_string(),
+      "{10}{W}{U}{B}{R}{G}{C}"
+    );
+  }
+
+  #[test]
+  fn from_str() {
+    let cost: ManaCost = "{1}{W/P}".parse().unwrap();
+    assert_eq!(cost, ManaCost::from_rgbuwc_generic(0, 0, 0, 0, 1, 0, 1));
+    assert_eq!("".parse::<ManaCost>(), Ok(ManaCost::new()));
+    assert_eq!(
+      "{HW}".parse::<ManaCost>(),
+      Err(ManaSymbolError::UnknownSymbol("HW".to_string()))
+    );
+    for s in &["{0}", "{3}{R}", "{1}{G}{G}{C}", "{W}{U}{B}{R}{G}"] {
+      assert_eq!(s.parse::<ManaCost>().unwrap().to_string(), *s);
+    }
+  }
 }
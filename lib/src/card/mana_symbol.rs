@@ -0,0 +1,269 @@
+use crate::card::{ManaColor, ManaCost};
+
+/// ManaSymbol represents a single [mana symbol](https://mtg.gamepedia.com/Mana_symbols)
+/// of a mana cost, such as `{2}`, `{W}`, `{B/R}` or `{G/U/P}`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ManaSymbol {
+  /// Generic mana, e.g. {3}
+  Generic(u8),
+  /// A single colored mana, e.g. {W}
+  Colored(ManaColor),
+  /// Hybrid mana payable with either color, e.g. {B/R}. The colorless
+  /// hybrid symbols, e.g. {C/W}, use `ManaColor::Colorless` on the left
+  Hybrid(ManaColor, ManaColor),
+  /// Monocolored hybrid mana payable with two generic or one colored mana, e.g. {2/W}
+  TwoBrid(ManaColor),
+  /// Phyrexian mana payable with one colored mana or 2 life, e.g. {W/P}
+  Phyrexian(ManaColor),
+  /// Hybrid phyrexian mana payable with either color or 2 life, e.g. {G/U/P}
+  HybridPhyrexian(ManaColor, ManaColor),
+  /// Snow mana, {S}
+  Snow,
+  /// Colorless-specific mana, {C}
+  ColorlessSpecific,
+  /// A variable amount of generic mana, e.g. {X}
+  Variable(char),
+}
+
+/// The amount of life paid in place of a single Phyrexian mana pip
+pub const PHYREXIAN_LIFE: u8 = 2;
+
+/// ManaSymbolError represents an error encountered while parsing a mana cost string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManaSymbolError {
+  /// The contents of a `{...}` symbol are not a known mana symbol
+  UnknownSymbol(String),
+  /// The mana cost string is not a sequence of `{...}` symbols
+  Malformed(String),
+}
+
+impl std::fmt::Display for ManaSymbolError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::UnknownSymbol(s) => write!(f, "Unknown mana symbol {{{}}}", s),
+      Self::Malformed(s) => write!(f, "Malformed mana cost \"{}\"", s),
+    }
+  }
+}
+
+impl std::error::Error for ManaSymbolError {}
+
+impl ManaSymbol {
+  /// Returns the symbol's contribution to the [mana value](https://mtg.gamepedia.com/Mana_value)
+  pub fn mana_value(self) -> u8 {
+    match self {
+      Self::Generic(n) => n,
+      Self::TwoBrid(_) => 2,
+      Self::Variable(_) => 0,
+      _ => 1,
+    }
+  }
+
+  /// Returns every mana cost that pays for this symbol, where any variable
+  /// symbol is worth `x` generic mana
+  pub fn mana_costs(self, x: u8) -> Vec<ManaCost> {
+    match self {
+      Self::Generic(n) => vec![generic(n)],
+      Self::Colored(color) => vec![colored(color)],
+      Self::Hybrid(left, right) => vec![colored(left), colored(right)],
+      Self::TwoBrid(color) => vec![generic(2), colored(color)],
+      Self::Phyrexian(color) => vec![colored(color), life(PHYREXIAN_LIFE)],
+      Self::HybridPhyrexian(left, right) => {
+        vec![colored(left), colored(right), life(PHYREXIAN_LIFE)]
+      }
+      // Snow sources are not modeled, so snow mana is treated as generic
+      Self::Snow => vec![generic(1)],
+      Self::ColorlessSpecific => vec![colored(ManaColor::Colorless)],
+      Self::Variable(_) => vec![generic(x)],
+    }
+  }
+}
+
+fn generic(count: u8) -> ManaCost {
+  let mut cost = ManaCost::new();
+  cost.generic = count;
+  cost
+}
+
+fn life(amount: u8) -> ManaCost {
+  let mut cost = ManaCost::new();
+  cost.life = amount;
+  cost
+}
+
+fn colored(color: ManaColor) -> ManaCost {
+  let mut cost = ManaCost::new();
+  match color {
+    ManaColor::Black => cost.b = 1,
+    ManaColor::Blue => cost.u = 1,
+    ManaColor::Green => cost.g = 1,
+    ManaColor::Red => cost.r = 1,
+    ManaColor::White => cost.w = 1,
+    ManaColor::Colorless => cost.c = 1,
+  }
+  cost
+}
+
+fn color_from_symbol(s: &str) -> Option<ManaColor> {
+  match s {
+    "W" => Some(ManaColor::White),
+    "U" => Some(ManaColor::Blue),
+    "B" => Some(ManaColor::Black),
+    "R" => Some(ManaColor::Red),
+    "G" => Some(ManaColor::Green),
+    _ => None,
+  }
+}
+
+fn color_to_symbol(color: ManaColor) -> &'static str {
+  match color {
+    ManaColor::White => "W",
+    ManaColor::Blue => "U",
+    ManaColor::Black => "B",
+    ManaColor::Red => "R",
+    ManaColor::Green => "G",
+    ManaColor::Colorless => "C",
+  }
+}
+
+impl std::str::FromStr for ManaSymbol {
+  type Err = ManaSymbolError;
+
+  /// Parses a single symbol, with or without the surrounding braces
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let inner = s.trim();
+    let inner = if inner.starts_with('{') && inner.ends_with('}') && inner.len() >= 2 {
+      &inner[1..inner.len() - 1]
+    } else {
+      inner
+    };
+    let unknown = || ManaSymbolError::UnknownSymbol(inner.to_string());
+    if !inner.is_empty() && inner.chars().all(|c| c.is_ascii_digit()) {
+      return inner
+        .parse::<u8>()
+        .map(Self::Generic)
+        .map_err(|_| unknown());
+    }
+    let parts: Vec<_> = inner.split('/').collect();
+    let symbol = match parts.as_slice() {
+      ["C"] => Self::ColorlessSpecific,
+      ["S"] => Self::Snow,
+      [v @ "X"] | [v @ "Y"] | [v @ "Z"] => Self::Variable(v.chars().next().unwrap()),
+      [color] => Self::Colored(color_from_symbol(color).ok_or_else(unknown)?),
+      ["2", color] => Self::TwoBrid(color_from_symbol(color).ok_or_else(unknown)?),
+      [color, "P"] => Self::Phyrexian(color_from_symbol(color).ok_or_else(unknown)?),
+      ["C", color] => Self::Hybrid(
+        ManaColor::Colorless,
+        color_from_symbol(color).ok_or_else(unknown)?,
+      ),
+      [left, right] => Self::Hybrid(
+        color_from_symbol(left).ok_or_else(unknown)?,
+        color_from_symbol(right).ok_or_else(unknown)?,
+      ),
+      [left, right, "P"] => Self::HybridPhyrexian(
+        color_from_symbol(left).ok_or_else(unknown)?,
+        color_from_symbol(right).ok_or_else(unknown)?,
+      ),
+      _ => return Err(unknown()),
+    };
+    Ok(symbol)
+  }
+}
+
+impl std::fmt::Display for ManaSymbol {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Generic(n) => write!(f, "{{{}}}", n),
+      Self::Colored(c) => write!(f, "{{{}}}", color_to_symbol(*c)),
+      Self::Hybrid(l, r) => write!(f, "{{{}/{}}}", color_to_symbol(*l), color_to_symbol(*r)),
+      Self::TwoBrid(c) => write!(f, "{{2/{}}}", color_to_symbol(*c)),
+      Self::Phyrexian(c) => write!(f, "{{{}/P}}", color_to_symbol(*c)),
+      Self::HybridPhyrexian(l, r) => {
+        write!(f, "{{{}/{}/P}}", color_to_symbol(*l), color_to_symbol(*r))
+      }
+      Self::Snow => write!(f, "{{S}}"),
+      Self::ColorlessSpecific => write!(f, "{{C}}"),
+      Self::Variable(v) => write!(f, "{{{}}}", v),
+    }
+  }
+}
+
+/// Parses the mana symbols of a single mana cost, e.g. "{2}{W}{U/P}"
+///
+/// Returns an error if the string contains anything other than whitespace
+/// and known `{...}` symbols. Split card costs, e.g. "{B} // {2}{B}{R}", are
+/// handled by [`mana_costs_from_str`](fn.mana_costs_from_str.html)
+pub fn mana_symbols_from_str(mana_cost_str: &str) -> Result<Vec<ManaSymbol>, ManaSymbolError> {
+  let malformed = || ManaSymbolError::Malformed(mana_cost_str.to_string());
+  let mut symbols = Vec::new();
+  let mut rest = mana_cost_str.trim_start();
+  while !rest.is_empty() {
+    if !rest.starts_with('{') {
+      return Err(malformed());
+    }
+    let end = rest.find('}').ok_or_else(malformed)?;
+    symbols.push(rest[..=end].parse()?);
+    rest = rest[end + 1..].trim_start();
+  }
+  Ok(symbols)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::card::mana_symbol::*;
+
+  #[test]
+  fn parse_all_symbol_kinds() {
+    let res = mana_symbols_from_str("{10}{W}{B/R}{2/U}{G/P}{G/U/P}{S}{C}{X}{C/W}").unwrap();
+    assert_eq!(
+      res,
+      vec![
+        ManaSymbol::Generic(10),
+        ManaSymbol::Colored(ManaColor::White),
+        ManaSymbol::Hybrid(ManaColor::Black, ManaColor::Red),
+        ManaSymbol::TwoBrid(ManaColor::Blue),
+        ManaSymbol::Phyrexian(ManaColor::Green),
+        ManaSymbol::HybridPhyrexian(ManaColor::Green, ManaColor::Blue),
+        ManaSymbol::Snow,
+        ManaSymbol::ColorlessSpecific,
+        ManaSymbol::Variable('X'),
+        ManaSymbol::Hybrid(ManaColor::Colorless, ManaColor::White),
+      ]
+    );
+  }
+
+  #[test]
+  fn display_round_trip() {
+    let s = "{10}{W}{B/R}{2/U}{G/P}{G/U/P}{S}{C}{X}{C/W}";
+    let res: String = mana_symbols_from_str(s)
+      .unwrap()
+      .iter()
+      .map(|s| s.to_string())
+      .collect();
+    assert_eq!(res, s);
+  }
+
+  #[test]
+  fn unknown_symbols() {
+    for s in &["{HW}", "{P}", "{W/W/W}", "{2/P}", "{}", "{1000}"] {
+      assert!(
+        matches!(
+          mana_symbols_from_str(s),
+          Err(ManaSymbolError::UnknownSymbol(_))
+        ),
+        "{}",
+        s
+      );
+    }
+  }
+
+  #[test]
+  fn malformed_costs() {
+    for s in &["W", "{W", "{W}}", "{B} // {R}"] {
+      assert_eq!(
+        mana_symbols_from_str(s),
+        Err(ManaSymbolError::Malformed(s.to_string()))
+      );
+    }
+  }
+}
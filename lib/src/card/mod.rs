@@ -2,7 +2,9 @@
 mod card;
 mod mana_color_count;
 mod mana_cost;
+mod mana_symbol;
 
 pub use card::*;
 pub use mana_color_count::*;
 pub use mana_cost::*;
+pub use mana_symbol::*;
@@ -1,13 +1,97 @@
 //! # Collection
 //!
-use crate::card::{Card, SetCode};
-use std::collections::HashMap;
+use crate::card::{Card, ManaCost, SetCode};
+use crate::data::{read_landlord, LoadError};
+use crate::search::{Query, SearchError};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 
 /// A Collection represents a deck or a library of cards
+///
+/// Card lookups go through indexes built when the collection is created or deserialized.
+/// The cards are read through `Deref<Target = [Card]>` and modified through `modify_cards`,
+/// which rebuilds the indexes
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(from = "CollectionCards")]
 pub struct Collection {
-  pub cards: Vec<Card>,
+  cards: Vec<Card>,
+  #[serde(skip)]
+  index: CollectionIndex,
+}
+
+// The serialized representation of a Collection, which is indexed after deserialization
+#[derive(Deserialize)]
+struct CollectionCards {
+  cards: Vec<Card>,
+}
+
+impl From<CollectionCards> for Collection {
+  fn from(collection: CollectionCards) -> Self {
+    let mut collection = Self {
+      cards: collection.cards,
+      index: CollectionIndex::default(),
+    };
+    collection.reindex();
+    collection
+  }
+}
+
+// Indexes into Collection::cards
+#[derive(Default, Clone)]
+struct CollectionIndex {
+  // Keyed by the case insensitive hash of the card name, see name_hash
+  by_name: HashMap<u64, Vec<usize>>,
+  // Keyed by normalize_name of the card name
+  by_normalized_name: HashMap<String, Vec<usize>>,
+  // The normalized name of each card, for suggest_names
+  normalized_names: Vec<String>,
+  by_oracle_id: HashMap<String, Vec<usize>>,
+  by_hash: HashMap<u64, usize>,
+  by_arena_id: HashMap<u64, usize>,
+}
+
+impl std::fmt::Debug for CollectionIndex {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("CollectionIndex")
+      .field("len", &self.normalized_names.len())
+      .finish()
+  }
+}
+
+impl CollectionIndex {
+  fn new(cards: &[Card]) -> Self {
+    let mut index = Self::default();
+    for (i, card) in cards.iter().enumerate() {
+      index
+        .by_name
+        .entry(name_hash(&card.name))
+        .or_default()
+        .push(i);
+      let normalized = normalize_name(&card.name);
+      index
+        .by_normalized_name
+        .entry(normalized.clone())
+        .or_default()
+        .push(i);
+      index.normalized_names.push(normalized);
+      index
+        .by_oracle_id
+        .entry(card.oracle_id.clone())
+        .or_default()
+        .push(i);
+      index.by_hash.entry(card.hash).or_insert(i);
+      // Card faces share the Arena ids of the parent card, if any
+      if !card.is_face {
+        for arena_id in card.all_arena_ids() {
+          index.by_arena_id.entry(arena_id).or_insert(i);
+        }
+      }
+    }
+    index
+  }
 }
 
 impl Collection {
@@ -46,29 +130,368 @@ impl Collection {
     m
   }
 
+  /// Returns the cards keyed by the MTG Arena id of each of their printings,
+  /// skipping card faces and cards missing from Arena
   pub fn group_by_arena_id<'a>(&'a self) -> HashMap<u64, &'a Card> {
     let mut m = HashMap::new();
     for card in &self.cards {
-      m.insert(card.arena_id, card);
+      if card.is_face {
+        continue;
+      }
+      for arena_id in card.all_arena_ids() {
+        m.insert(arena_id, card);
+      }
     }
     m
   }
 
   /// Returns a new collection of cards
   pub fn from_cards(mut cards: Vec<Card>) -> Self {
-    // sort for binary_search used in card_from_name
-    // note that Card implements Ord by
-    cards.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    Self { cards }
+    // Sorted by name, ignoring case
+    cards.sort_by(|a, b| cmp_ignore_case(&a.name, &b.name));
+    CollectionCards { cards }.into()
   }
 
-  /// Returns a card from the card name
+  /// Calls `f` to modify the cards of the collection, then rebuilds the card lookup indexes
+  pub fn modify_cards<F: FnOnce(&mut Vec<Card>)>(&mut self, f: F) {
+    f(&mut self.cards);
+    self.reindex();
+  }
+
+  /// Returns the cards of the collection
+  pub fn into_cards(self) -> Vec<Card> {
+    self.cards
+  }
+
+  // Rebuilds the card lookup indexes, which is required after modifying `cards`
+  fn reindex(&mut self) {
+    self.index = CollectionIndex::new(&self.cards);
+  }
+
+  /// Returns a new collection from a .landlord card database, such as data/all_cards.landlord,
+  /// so that card data can be swapped without rebuilding
+  pub fn load_from_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self, LoadError> {
+    let file = std::fs::File::open(path)?;
+    Self::load_from_reader(std::io::BufReader::new(file))
+  }
+
+  /// Returns a new collection from a reader of a .landlord card database
+  pub fn load_from_reader<R: std::io::Read>(reader: R) -> Result<Self, LoadError> {
+    read_landlord(reader).map(|(_, collection)| collection)
+  }
+
+  /// Returns the cards matching a Scryfall style search query, such as "t:land id<=wubrg -t:basic".
+  /// See the [search module](../search/index.html) for the supported syntax. Card faces are skipped,
+  /// as their text is searched through the parent card
+  pub fn search(&self, query: &str) -> Result<Vec<&Card>, SearchError> {
+    let query: Query = query.parse()?;
+    Ok(
+      self
+        .cards
+        .iter()
+        .filter(|card| !card.is_face && query.matches(card))
+        .collect(),
+    )
+  }
+
+  /// Returns a card from the card name, ignoring case
   pub fn card_from_name(&self, name: &str) -> Option<&Card> {
-    let name_lowercase = name.to_lowercase();
-    let res = self
+    let indices = self.index.by_name.get(&name_hash(name))?;
+    indices
+      .iter()
+      .filter_map(|i| self.cards.get(*i))
+      .find(|card| cmp_ignore_case(&card.name, name) == Ordering::Equal)
+  }
+
+  /// Returns a card from a card name that may differ from the card name by case, accents,
+  /// punctuation or an Alchemy "A-" prefix, e.g. "Lim-Dul's Vault" for "Lim-Dûl's Vault".
+  /// Returns None if the name matches no card, or more than one card
+  pub fn card_from_name_normalized(&self, name: &str) -> Option<&Card> {
+    if let Some(card) = self.card_from_name(name) {
+      return Some(card);
+    }
+    // Alchemy rebalanced cards are named after the original card, e.g. "A-Lightning Bolt"
+    let stripped = match name.get(..2) {
+      Some(prefix) if prefix.eq_ignore_ascii_case("a-") => Some(&name[2..]),
+      _ => None,
+    };
+    for name in std::iter::once(name).chain(stripped) {
+      let normalized = normalize_name(name);
+      if let Some([i]) = self
+        .index
+        .by_normalized_name
+        .get(&normalized)
+        .map(Vec::as_slice)
+      {
+        let card = self.cards.get(*i);
+        if let Some(card) = card.filter(|card| normalize_name(&card.name) == normalized) {
+          return Some(card);
+        }
+      }
+    }
+    None
+  }
+
+  /// Returns the card, rather than a card face, with the Scryfall oracle id
+  pub fn card_from_oracle_id(&self, oracle_id: &str) -> Option<&Card> {
+    let indices = self.index.by_oracle_id.get(oracle_id)?;
+    let mut cards = indices
+      .iter()
+      .filter_map(|i| self.cards.get(*i))
+      .filter(|card| card.oracle_id == oracle_id);
+    let first = cards.clone().next();
+    cards.find(|card| !card.is_face).or(first)
+  }
+
+  /// Returns the card with the card name hash, see `Card::hash`
+  pub fn card_from_hash(&self, hash: u64) -> Option<&Card> {
+    let i = self.index.by_hash.get(&hash)?;
+    self.cards.get(*i).filter(|card| card.hash == hash)
+  }
+
+  /// Returns the card with a printing of the MTG Arena id
+  pub fn card_from_arena_id(&self, arena_id: u64) -> Option<&Card> {
+    let i = self.index.by_arena_id.get(&arena_id)?;
+    self
+      .cards
+      .get(*i)
+      .filter(|card| card.all_arena_ids().any(|id| id == arena_id))
+  }
+
+  /// Returns up to `limit` cards with names similar to the card name, closest first,
+  /// along with the edit distance between the normalized names
+  pub fn suggest_names(&self, name: &str, limit: usize) -> Vec<(&Card, usize)> {
+    let normalized = normalize_name(name);
+    let len = normalized.chars().count();
+    let max_distance = len / 3 + 1;
+    let mut suggestions: Vec<_> = self
       .cards
-      .binary_search_by(|probe| probe.name.to_lowercase().cmp(&name_lowercase));
-    res.map(|idx| &self.cards[idx]).ok()
+      .iter()
+      .zip(&self.index.normalized_names)
+      .filter_map(|(card, card_name)| {
+        let card_len = card_name.chars().count();
+        // The length difference is a lower bound of the edit distance
+        if card_len + max_distance < len || len + max_distance < card_len {
+          return None;
+        }
+        let distance = edit_distance(&normalized, card_name);
+        if distance <= max_distance {
+          Some((card, distance))
+        } else {
+          None
+        }
+      })
+      .collect();
+    suggestions.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.name.cmp(&b.0.name)));
+    suggestions.dedup_by(|a, b| a.0.name == b.0.name);
+    suggestions.truncate(limit);
+    suggestions
+  }
+}
+
+/// Compares two card names, ignoring case, without allocating
+pub fn cmp_ignore_case(a: &str, b: &str) -> Ordering {
+  a.chars()
+    .flat_map(char::to_lowercase)
+    .cmp(b.chars().flat_map(char::to_lowercase))
+}
+
+// Returns a hash of the card name that ignores case, without allocating
+fn name_hash(name: &str) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  for c in name.chars().flat_map(char::to_lowercase) {
+    c.hash(&mut hasher);
+  }
+  hasher.finish()
+}
+
+/// Returns the card name folded for comparison: lowercase, without accents or punctuation,
+/// and with hyphens and slashes as spaces, e.g. "lim duls vault" for "Lim-Dûl's Vault"
+pub fn normalize_name(name: &str) -> String {
+  let mut folded = String::with_capacity(name.len());
+  for c in name.chars().flat_map(char::to_lowercase) {
+    match c {
+      'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' => folded.push('a'),
+      'é' | 'è' | 'ê' | 'ë' => folded.push('e'),
+      'í' | 'ì' | 'î' | 'ï' => folded.push('i'),
+      'ó' | 'ò' | 'ô' | 'ö' | 'õ' | 'ø' => folded.push('o'),
+      'ú' | 'ù' | 'û' | 'ü' => folded.push('u'),
+      'ñ' => folded.push('n'),
+      'ç' => folded.push('c'),
+      'æ' => folded.push_str("ae"),
+      // Curly and straight apostrophes are dropped along with other punctuation
+      '-' | '/' | '_' => folded.push(' '),
+      c if c.is_alphanumeric() || c.is_whitespace() => folded.push(c),
+      _ => {}
+    }
+  }
+  folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Returns the Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut row: Vec<usize> = (0..=b.len()).collect();
+  for (i, ca) in a.chars().enumerate() {
+    let mut diagonal = row[0];
+    row[0] = i + 1;
+    for (j, cb) in b.iter().enumerate() {
+      let above = row[j + 1];
+      row[j + 1] = if ca == *cb {
+        diagonal
+      } else {
+        1 + diagonal.min(above).min(row[j])
+      };
+      diagonal = above;
+    }
+  }
+  row[b.len()]
+}
+
+/// CardChange represents a change to a single field of a card between two collections
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardChange {
+  /// The card name, followed by " (face)" for card faces
+  pub name: String,
+  /// The name of the changed field, e.g. "kind"
+  pub field: String,
+  pub before: String,
+  pub after: String,
+}
+
+/// CollectionDiff represents the cards added, removed and changed between two collections,
+/// such as two versions of data/all_cards.landlord
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionDiff {
+  /// The names of the added cards, followed by " (face)" for card faces
+  pub added: Vec<String>,
+  /// The names of the removed cards, followed by " (face)" for card faces
+  pub removed: Vec<String>,
+  pub changed: Vec<CardChange>,
+}
+
+impl CollectionDiff {
+  /// Returns true if the collections have the same cards
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+  }
+
+  /// Returns the diff as pretty printed JSON
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).expect("CollectionDiff serializes to JSON")
+  }
+}
+
+impl std::fmt::Display for CollectionDiff {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    writeln!(f, "Added ({}):", self.added.len())?;
+    for name in &self.added {
+      writeln!(f, "  + {}", name)?;
+    }
+    writeln!(f, "Removed ({}):", self.removed.len())?;
+    for name in &self.removed {
+      writeln!(f, "  - {}", name)?;
+    }
+    writeln!(f, "Changed ({}):", self.changed.len())?;
+    for change in &self.changed {
+      writeln!(
+        f,
+        "  ~ {}: {} {} -> {}",
+        change.name, change.field, change.before, change.after
+      )?;
+    }
+    Ok(())
+  }
+}
+
+// Returns the mana cost string, including any life paid in place of mana
+fn mana_cost_string(cost: &ManaCost) -> String {
+  if cost.life > 0 {
+    format!("{}+{} life", cost, cost.life)
+  } else {
+    cost.to_string()
+  }
+}
+
+// Returns the name used to identify a card in a CollectionDiff
+fn diff_name(card: &Card) -> String {
+  if card.is_face {
+    format!("{} (face)", card.name)
+  } else {
+    card.name.clone()
+  }
+}
+
+// Returns the cards grouped by name, where card faces are grouped separately from cards
+fn cards_by_name(collection: &Collection) -> BTreeMap<(String, bool), Vec<&Card>> {
+  let mut m: BTreeMap<_, Vec<_>> = BTreeMap::new();
+  for card in &collection.cards {
+    m.entry((card.name.clone(), card.is_face))
+      .or_default()
+      .push(card);
+  }
+  m
+}
+
+impl Collection {
+  /// Returns the cards added, removed and changed in the newer collection. Changes
+  /// are reported for the kind, mana cost, all mana costs, turn, set and rarity of a card
+  pub fn diff(&self, newer: &Collection) -> CollectionDiff {
+    let older_cards = cards_by_name(self);
+    let newer_cards = cards_by_name(newer);
+    let mut diff = CollectionDiff::default();
+    for (key, older) in &older_cards {
+      let newer = newer_cards.get(key).map_or(&[][..], |cards| &cards[..]);
+      for card in older.iter().skip(newer.len()) {
+        diff.removed.push(diff_name(card));
+      }
+      for (before, after) in older.iter().zip(newer.iter()) {
+        let name = diff_name(before);
+        let mut change = |field: &str, b: String, a: String| {
+          if b != a {
+            diff.changed.push(CardChange {
+              name: name.clone(),
+              field: field.to_string(),
+              before: b,
+              after: a,
+            });
+          }
+        };
+        change(
+          "kind",
+          format!("{:?}", before.kind),
+          format!("{:?}", after.kind),
+        );
+        change(
+          "mana_cost",
+          mana_cost_string(&before.mana_cost),
+          mana_cost_string(&after.mana_cost),
+        );
+        let all_mana_costs = |card: &Card| {
+          let costs: Vec<_> = card.all_mana_costs.iter().map(mana_cost_string).collect();
+          costs.join(" | ")
+        };
+        change(
+          "all_mana_costs",
+          all_mana_costs(before),
+          all_mana_costs(after),
+        );
+        change("turn", before.turn.to_string(), after.turn.to_string());
+        change("set", before.set.to_string(), after.set.to_string());
+        change(
+          "rarity",
+          format!("{:?}", before.rarity),
+          format!("{:?}", after.rarity),
+        );
+      }
+    }
+    for (key, newer) in &newer_cards {
+      let older_len = older_cards.get(key).map_or(0, |cards| cards.len());
+      for card in newer.iter().skip(older_len) {
+        diff.added.push(diff_name(card));
+      }
+    }
+    diff
   }
 }
 
@@ -81,4 +504,110 @@ impl Deref for Collection {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+  use crate::card::*;
+  use crate::collection::*;
+  use crate::data::ALL_CARDS;
+
+  #[test]
+  fn indexed_lookups() {
+    let shock = card!("Shock");
+    assert_eq!(ALL_CARDS.card_from_name("sHoCk").unwrap().name, "Shock");
+    assert_eq!(ALL_CARDS.card_from_hash(shock.hash).unwrap().name, "Shock");
+    assert_eq!(
+      ALL_CARDS
+        .card_from_oracle_id(&shock.oracle_id)
+        .unwrap()
+        .name,
+      "Shock"
+    );
+    assert!(ALL_CARDS.card_from_name("Not a card").is_none());
+    let mut cards = vec![shock.clone(), card!("Negate").clone()];
+    cards[1].arena_id = 42;
+    let collection = Collection::from_cards(cards);
+    assert_eq!(collection.card_from_arena_id(42).unwrap().name, "Negate");
+    assert!(collection.card_from_arena_id(0).is_none());
+    // Deserialized collections are indexed
+    let bytes = bincode::serialize(&collection).unwrap();
+    let mut collection: Collection = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(collection.card_from_name("negate").unwrap().name, "Negate");
+    // Modified collections are reindexed
+    collection.modify_cards(|cards| cards.reverse());
+    assert_eq!(collection[0].name, "Shock");
+    assert_eq!(collection.card_from_name("negate").unwrap().name, "Negate");
+    assert_eq!(collection.card_from_arena_id(42).unwrap().name, "Negate");
+    collection.modify_cards(|cards| cards.retain(|card| card.name != "Negate"));
+    assert!(collection.card_from_name("negate").is_none());
+    assert_eq!(collection.card_from_name("shock").unwrap().name, "Shock");
+    assert_eq!(collection.into_cards().len(), 1);
+    assert_eq!(cmp_ignore_case("abc", "ABD"), Ordering::Less);
+  }
+
+  #[test]
+  fn normalized_names() {
+    assert_eq!(normalize_name("Lim-Dûl’s Vault"), "lim duls vault");
+    assert_eq!(normalize_name("  Æther   Hub "), "aether hub");
+    let vault = ALL_CARDS.card_from_name_normalized("lim-dul's vault");
+    assert_eq!(vault.unwrap().name, "Lim-Dûl's Vault");
+    let thrill = ALL_CARDS.card_from_name_normalized("A-Thrill of Possibility");
+    assert_eq!(thrill.unwrap().name, "Thrill of Possibility");
+    assert!(ALL_CARDS
+      .card_from_name_normalized("Thril of Possibility")
+      .is_none());
+  }
+
+  #[test]
+  fn suggested_names() {
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    let suggestions = ALL_CARDS.suggest_names("Thril of Posibility", 3);
+    assert_eq!(suggestions[0].0.name, "Thrill of Possibility");
+    assert_eq!(suggestions[0].1, 2);
+    assert!(suggestions.len() <= 3);
+    assert!(ALL_CARDS.suggest_names("qqqqqqqqqqqqqqqq", 3).is_empty());
+  }
+
+  #[test]
+  fn diff_same_collection_is_empty() {
+    let collection = Collection::from_cards(vec![card!("Shock").clone(), card!("Negate").clone()]);
+    let diff = collection.diff(&collection);
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "Added (0):\nRemoved (0):\nChanged (0):\n");
+  }
+
+  #[test]
+  fn diff_added_removed_and_changed() {
+    let older = Collection::from_cards(vec![card!("Shock").clone(), card!("Sulfur Falls").clone()]);
+    let mut sulfur_falls = card!("Sulfur Falls").clone();
+    sulfur_falls.kind = CardKind::OtherLand;
+    let mut shock = card!("Shock").clone();
+    shock.mana_cost = ManaCost::from_rgbuwc_generic(1, 0, 0, 0, 0, 0, 1);
+    shock.all_mana_costs = vec![shock.mana_cost];
+    let mut face = card!("Negate").clone();
+    face.is_face = true;
+    let newer = Collection::from_cards(vec![shock, sulfur_falls, card!("Negate").clone(), face]);
+    let diff = older.diff(&newer);
+    assert_eq!(diff.added, vec!["Negate", "Negate (face)"]);
+    assert!(diff.removed.is_empty());
+    let change = |name: &str, field: &str, before: &str, after: &str| CardChange {
+      name: name.to_string(),
+      field: field.to_string(),
+      before: before.to_string(),
+      after: after.to_string(),
+    };
+    assert_eq!(
+      diff.changed,
+      vec![
+        change("Shock", "mana_cost", "{R}", "{1}{R}"),
+        change("Shock", "all_mana_costs", "{R}", "{1}{R}"),
+        change("Sulfur Falls", "kind", "CheckLand", "OtherLand"),
+      ]
+    );
+    assert!(diff
+      .to_string()
+      .contains("  ~ Sulfur Falls: kind CheckLand -> OtherLand\n"));
+    let reverse = newer.diff(&older);
+    assert_eq!(reverse.removed, vec!["Negate", "Negate (face)"]);
+    let json: CollectionDiff = serde_json::from_str(&diff.to_json()).unwrap();
+    assert_eq!(json, diff);
+  }
+}
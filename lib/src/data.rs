@@ -1,24 +1,319 @@
 use crate::collection::Collection;
+use crate::set::SetRegistry;
 use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use std::io::prelude::*;
 
+/// The magic number at the start of every .landlord card database
+pub const LANDLORD_MAGIC: &[u8; 8] = b"LANDLORD";
+
+/// The version of the .landlord card database layout. Bump this whenever the
+/// serialized layout of a Collection changes, e.g. when a field is added to Card
+pub const LANDLORD_SCHEMA_VERSION: u32 = 5;
+
+/// The magic number at the start of every .landlord set registry
+pub const LANDLORD_SETS_MAGIC: &[u8; 8] = b"LANDSETS";
+
+/// The version of the .landlord set registry layout. Bump this whenever the
+/// serialized layout of a SetRegistry changes, e.g. when a field is added to Set
+pub const LANDLORD_SETS_SCHEMA_VERSION: u32 = 1;
+
+/// LandlordHeader represents the header of a .landlord card database
+///
+/// A .landlord file starts with the magic number and the little endian schema version,
+/// followed by the rest of the bincode serialized header and the gzip compressed,
+/// bincode serialized Collection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LandlordHeader {
+    /// The layout version of the file
+    pub schema_version: u32,
+    /// Seconds since the Unix epoch at which the file was built
+    pub built_at: u64,
+    /// The SHA-256 hash of the Scryfall bulk data the file was built from, as a hex string.
+    /// Empty if unknown
+    pub source_hash: String,
+    /// The number of cards in the file
+    pub card_count: u64,
+}
+
+impl LandlordHeader {
+    /// Returns the header of a card database of the current schema version for the collection
+    pub fn new(collection: &Collection, built_at: u64, source_hash: &str) -> Self {
+        Self {
+            schema_version: LANDLORD_SCHEMA_VERSION,
+            built_at,
+            source_hash: source_hash.to_string(),
+            card_count: collection.len() as u64,
+        }
+    }
+}
+
+/// LoadError represents an error encountered while loading a .landlord card database or set registry
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    Bincode(bincode::Error),
+    /// The file does not start with the .landlord magic number
+    BadMagic,
+    /// The file is a headerless card database from before the schema version was introduced
+    Unversioned,
+    /// The file was written with a different schema version than this version of landlord reads
+    VersionMismatch {
+        found: u32,
+        expected: u32,
+    },
+    /// The header card count does not match the number of cards in the file
+    CardCountMismatch {
+        header: u64,
+        found: u64,
+    },
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Cannot read card database: {}", e),
+            Self::Bincode(e) => write!(f, "Cannot deserialize card database: {}", e),
+            Self::BadMagic => write!(f, "Not a .landlord card database"),
+            Self::Unversioned => write!(
+                f,
+                "Unversioned .landlord card database, expected schema version {}. Regenerate it with scryfall2landlord",
+                LANDLORD_SCHEMA_VERSION
+            ),
+            Self::VersionMismatch { found, expected } => write!(
+                f,
+                ".landlord card database has schema version {}, expected schema version {}. Regenerate it with scryfall2landlord",
+                found, expected
+            ),
+            Self::CardCountMismatch { header, found } => write!(
+                f,
+                ".landlord card database header lists {} cards, found {}",
+                header, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<std::io::Error> for LoadError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<bincode::Error> for LoadError {
+    fn from(error: bincode::Error) -> Self {
+        Self::Bincode(error)
+    }
+}
+
+// Reads the magic number and schema version at the start of a .landlord file
+fn read_magic_and_version<R: Read>(
+    reader: &mut R,
+    expected_magic: &[u8; 8],
+    expected_version: u32,
+) -> Result<u32, LoadError> {
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != expected_magic {
+        // Unversioned files are a gzip stream
+        return Err(if magic[..2] == [0x1f, 0x8b] {
+            LoadError::Unversioned
+        } else {
+            LoadError::BadMagic
+        });
+    }
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    let schema_version = u32::from_le_bytes(version);
+    if schema_version != expected_version {
+        return Err(LoadError::VersionMismatch {
+            found: schema_version,
+            expected: expected_version,
+        });
+    }
+    Ok(schema_version)
+}
+
+/// Returns the header of a .landlord card database, leaving the reader at the start of the cards
+pub fn read_landlord_header<R: Read>(reader: &mut R) -> Result<LandlordHeader, LoadError> {
+    let schema_version = read_magic_and_version(reader, LANDLORD_MAGIC, LANDLORD_SCHEMA_VERSION)?;
+    let (built_at, source_hash, card_count) = bincode::deserialize_from(&mut *reader)?;
+    Ok(LandlordHeader {
+        schema_version,
+        built_at,
+        source_hash,
+        card_count,
+    })
+}
+
+/// Returns the header and collection of a .landlord card database
+pub fn read_landlord<R: Read>(mut reader: R) -> Result<(LandlordHeader, Collection), LoadError> {
+    let header = read_landlord_header(&mut reader)?;
+    let collection: Collection = bincode::deserialize_from(GzDecoder::new(reader))?;
+    let found = collection.len() as u64;
+    if found != header.card_count {
+        return Err(LoadError::CardCountMismatch {
+            header: header.card_count,
+            found,
+        });
+    }
+    Ok((header, collection))
+}
+
+/// Writes the header and collection as a .landlord card database
+pub fn write_landlord<W: Write>(
+    mut writer: W,
+    header: &LandlordHeader,
+    collection: &Collection,
+) -> Result<(), bincode::Error> {
+    writer.write_all(LANDLORD_MAGIC)?;
+    writer.write_all(&header.schema_version.to_le_bytes())?;
+    bincode::serialize_into(
+        &mut writer,
+        &(header.built_at, &header.source_hash, header.card_count),
+    )?;
+    let mut e = GzEncoder::new(writer, Compression::default());
+    bincode::serialize_into(&mut e, collection)?;
+    e.finish()?;
+    Ok(())
+}
+
+/// Returns the registry of a .landlord set registry
+pub fn read_landlord_sets<R: Read>(mut reader: R) -> Result<SetRegistry, LoadError> {
+    read_magic_and_version(
+        &mut reader,
+        LANDLORD_SETS_MAGIC,
+        LANDLORD_SETS_SCHEMA_VERSION,
+    )?;
+    Ok(bincode::deserialize_from(GzDecoder::new(reader))?)
+}
+
+/// Writes the registry as a .landlord set registry
+///
+/// A .landlord set registry starts with its own magic number and the little endian
+/// schema version, followed by the gzip compressed, bincode serialized SetRegistry
+pub fn write_landlord_sets<W: Write>(
+    mut writer: W,
+    sets: &SetRegistry,
+) -> Result<(), bincode::Error> {
+    writer.write_all(LANDLORD_SETS_MAGIC)?;
+    writer.write_all(&LANDLORD_SETS_SCHEMA_VERSION.to_le_bytes())?;
+    let mut e = GzEncoder::new(writer, Compression::default());
+    bincode::serialize_into(&mut e, sets)?;
+    e.finish()?;
+    Ok(())
+}
+
 /// Returns a new collection of all cards from data/all_cards.landlord
-pub fn all_cards() -> Result<Collection, bincode::Error> {
+pub fn all_cards() -> Result<Collection, LoadError> {
     let b = include_bytes!("../../data/all_cards.landlord");
-    let mut gz = GzDecoder::new(&b[..]);
-    let mut s: Vec<u8> = Vec::new();
-    gz.read_to_end(&mut s).expect("gz decode failed");
-    bincode::deserialize(&s)
+    Collection::load_from_reader(&b[..])
+}
+
+/// Returns a new registry of all sets from data/all_sets.landlord
+pub fn all_sets() -> Result<SetRegistry, LoadError> {
+    let b = include_bytes!("../../data/all_sets.landlord");
+    read_landlord_sets(&b[..])
 }
 
 lazy_static! {
     pub static ref ALL_CARDS: Collection = all_cards().expect("all_cards() failed");
+    pub static ref ALL_SETS: SetRegistry = all_sets().expect("all_sets() failed");
 }
 
 #[cfg(test)]
 mod tests {
     use crate::data::*;
 
+    fn landlord_bytes(header: &LandlordHeader, collection: &Collection) -> Vec<u8> {
+        let mut b = Vec::new();
+        write_landlord(&mut b, header, collection).unwrap();
+        b
+    }
+
+    #[test]
+    fn landlord_round_trip() {
+        let collection = Collection::from_cards(ALL_CARDS[..10].to_vec());
+        let header = LandlordHeader::new(&collection, 1_600_000_000, "abc123");
+        let b = landlord_bytes(&header, &collection);
+        assert_eq!(&b[..8], LANDLORD_MAGIC);
+        let (read_header, read_collection) = read_landlord(&b[..]).unwrap();
+        assert_eq!(read_header, header);
+        assert_eq!(read_header.card_count, 10);
+        assert_eq!(read_collection[..], collection[..]);
+        assert_eq!(read_landlord_header(&mut &b[..]).unwrap(), header);
+    }
+
+    #[test]
+    fn landlord_errors() {
+        let collection = Collection::from_cards(ALL_CARDS[..10].to_vec());
+        let mut header = LandlordHeader::new(&collection, 0, "");
+        header.schema_version = LANDLORD_SCHEMA_VERSION + 1;
+        let b = landlord_bytes(&header, &collection);
+        match Collection::load_from_reader(&b[..]) {
+            Err(LoadError::VersionMismatch { found, expected }) => {
+                assert_eq!(found, LANDLORD_SCHEMA_VERSION + 1);
+                assert_eq!(expected, LANDLORD_SCHEMA_VERSION);
+            }
+            r => panic!("Expected a version mismatch, found {:?}", r.map(|_| ())),
+        }
+        let mut header = LandlordHeader::new(&collection, 0, "");
+        header.card_count = 11;
+        let b = landlord_bytes(&header, &collection);
+        assert!(matches!(
+            Collection::load_from_reader(&b[..]),
+            Err(LoadError::CardCountMismatch {
+                header: 11,
+                found: 10
+            })
+        ));
+        // A headerless gzip+bincode card database
+        let mut unversioned = Vec::new();
+        let mut e = GzEncoder::new(&mut unversioned, Compression::default());
+        bincode::serialize_into(&mut e, &collection).unwrap();
+        e.finish().unwrap();
+        let err = Collection::load_from_reader(&unversioned[..]).unwrap_err();
+        assert!(matches!(err, LoadError::Unversioned));
+        assert!(err.to_string().contains("Unversioned"));
+        assert!(matches!(
+            Collection::load_from_reader(&b"not a card database"[..]),
+            Err(LoadError::BadMagic)
+        ));
+        assert!(matches!(
+            Collection::load_from_path("does/not/exist.landlord"),
+            Err(LoadError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn landlord_sets_round_trip() {
+        let mut b = Vec::new();
+        write_landlord_sets(&mut b, &ALL_SETS).unwrap();
+        assert_eq!(&b[..8], LANDLORD_SETS_MAGIC);
+        let sets = read_landlord_sets(&b[..]).unwrap();
+        assert_eq!(sets[..], ALL_SETS[..]);
+        // A card database is not a set registry
+        let collection = Collection::from_cards(ALL_CARDS[..10].to_vec());
+        let header = LandlordHeader::new(&collection, 0, "");
+        let b = landlord_bytes(&header, &collection);
+        assert!(matches!(
+            read_landlord_sets(&b[..]),
+            Err(LoadError::BadMagic)
+        ));
+        // A headerless gzip+bincode set registry
+        let mut unversioned = Vec::new();
+        let mut e = GzEncoder::new(&mut unversioned, Compression::default());
+        bincode::serialize_into(&mut e, &*ALL_SETS).unwrap();
+        e.finish().unwrap();
+        assert!(matches!(
+            read_landlord_sets(&unversioned[..]),
+            Err(LoadError::Unversioned)
+        ));
+    }
+
     #[test]
     fn all_cards_have_non_empty_image_uri() {
         let any_empty_image_uri = ALL_CARDS.iter().any(|c| c.image_uri.is_empty());
@@ -32,7 +327,7 @@ mod tests {
     #[should_panic]
     fn all_cards_have_unique_names() {
         let mut deduped = ALL_CARDS.clone();
-        deduped.cards.dedup();
-        assert_eq!(deduped.cards.len(), ALL_CARDS.len());
+        deduped.modify_cards(Vec::dedup);
+        assert_eq!(deduped.len(), ALL_CARDS.len());
     }
 }
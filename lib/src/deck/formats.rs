@@ -0,0 +1,765 @@
+//! # Deck list formats
+//!
+//! Reads and writes decks in the formats of other Magic: The Gathering clients and tools:
+//!
+//! - [`DeckFormat::Arena`], the MTG Arena export, see [`Deck::from_list`](../struct.Deck.html#method.from_list)
+//! - [`DeckFormat::Mtgo`], the Magic Online `.dek` XML
+//! - [`DeckFormat::Cockatrice`], the Cockatrice `.cod` XML
+//! - [`DeckFormat::Forge`], the Forge `.dck`
+//! - [`DeckFormat::Csv`], a CSV with count, name, set, collector number and zone columns
+//!
+//! Every format keeps the main deck and sideboard. Use [`import`] to detect the format of a deck list
+use crate::card::SetCode;
+use crate::deck::{
+  card_from_list_name, conflicting_printing_message, list_name, set_from_list_code,
+  unknown_card_error, CardModifiers, Deck, DeckBuilder, DeckCard, DeckListError, DeckListErrorKind,
+  DeckZone, DeckcodeError, Printing,
+};
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// DeckFormat represents a deck list format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DeckFormat {
+  Arena,
+  Mtgo,
+  Cockatrice,
+  Forge,
+  Csv,
+}
+
+impl DeckFormat {
+  /// Returns the format of the deck list. Lists in no other format are assumed to be Arena lists
+  pub fn detect(list: &str) -> Self {
+    let trimmed = list.trim_start();
+    if trimmed.starts_with('<') {
+      if trimmed.contains("<cockatrice_deck") {
+        return Self::Cockatrice;
+      }
+      return Self::Mtgo;
+    }
+    let mut lines = trimmed.lines().map(|line| line.trim().to_lowercase());
+    let first = lines.next().unwrap_or_default();
+    if first.starts_with('[') && first.ends_with(']') {
+      return Self::Forge;
+    }
+    // Arena lines may contain commas, e.g. "1 Jegantha, the Wellspring", so a CSV needs a header
+    let header = csv_fields(&first);
+    if header.len() > 1
+      && header
+        .iter()
+        .any(|field| CSV_NAME_COLUMNS.contains(&field.trim()))
+    {
+      return Self::Csv;
+    }
+    Self::Arena
+  }
+
+  /// Returns the file extension of the format, e.g. "dek"
+  pub fn extension(self) -> &'static str {
+    match self {
+      Self::Arena => "txt",
+      Self::Mtgo => "dek",
+      Self::Cockatrice => "cod",
+      Self::Forge => "dck",
+      Self::Csv => "csv",
+    }
+  }
+
+  /// Returns the format of a file extension, e.g. "dek" or ".dek"
+  pub fn from_extension(extension: &str) -> Option<Self> {
+    match extension.trim_start_matches('.').to_lowercase().as_str() {
+      "txt" => Some(Self::Arena),
+      "dek" => Some(Self::Mtgo),
+      "cod" => Some(Self::Cockatrice),
+      "dck" => Some(Self::Forge),
+      "csv" => Some(Self::Csv),
+      _ => None,
+    }
+  }
+}
+
+/// Returns a deck from a deck list in any supported format, see `DeckFormat::detect`
+pub fn import(list: &str) -> Result<Deck, DeckcodeError> {
+  import_as(list, DeckFormat::detect(list))
+}
+
+/// Returns a deck from a deck list in the format
+pub fn import_as(list: &str, format: DeckFormat) -> Result<Deck, DeckcodeError> {
+  match format {
+    DeckFormat::Arena => Deck::from_list(list),
+    DeckFormat::Mtgo => from_mtgo(list),
+    DeckFormat::Cockatrice => from_cockatrice(list),
+    DeckFormat::Forge => from_forge(list),
+    DeckFormat::Csv => from_csv(list),
+  }
+}
+
+/// Returns the deck as a deck list in the format
+pub fn export(deck: &Deck, format: DeckFormat) -> String {
+  match format {
+    DeckFormat::Arena => deck.to_export(),
+    DeckFormat::Mtgo => to_mtgo(deck),
+    DeckFormat::Cockatrice => to_cockatrice(deck),
+    DeckFormat::Forge => to_forge(deck),
+    DeckFormat::Csv => to_csv(deck),
+  }
+}
+
+// The line and character columns of a card in a deck list, and the text of the card
+struct Location<'a> {
+  line: usize,
+  columns: Range<usize>,
+  text: &'a str,
+}
+
+impl<'a> Location<'a> {
+  // Returns the location of a trimmed line, where index starts at 0
+  fn of_line(index: usize, line: &'a str) -> Self {
+    let indent = line.chars().count() - line.trim_start().chars().count();
+    Self {
+      line: index + 1,
+      columns: indent..indent + line.trim().chars().count(),
+      text: line.trim(),
+    }
+  }
+
+  // Returns the location of a byte range of the deck list, such as an XML element
+  fn of_range(list: &'a str, range: Range<usize>) -> Self {
+    let before = &list[..range.start];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let start = list[line_start..range.start].chars().count();
+    let text = &list[range];
+    Self {
+      line: before.matches('\n').count() + 1,
+      columns: start..start + text.chars().count(),
+      text,
+    }
+  }
+}
+
+// Collects the cards of each zone and the problems while reading a deck list
+#[derive(Default)]
+struct ZoneBuilder {
+  zones: HashMap<DeckZone, DeckBuilder>,
+  title: Option<String>,
+  errors: Vec<DeckListError>,
+}
+
+impl ZoneBuilder {
+  fn error(&mut self, at: &Location, kind: DeckListErrorKind, message: String) {
+    self.errors.push(DeckListError::new(
+      at.line,
+      at.columns.clone(),
+      kind,
+      message,
+    ));
+  }
+
+  // Returns the card amount, or None if it is not a number
+  fn count(&mut self, at: &Location, count: Option<&str>) -> Option<usize> {
+    let count = count.and_then(|count| count.trim().parse().ok());
+    if count.is_none() {
+      let message = format!(
+        "Cannot parse usize card amount from deck list line: {}",
+        at.text
+      );
+      self.error(at, DeckListErrorKind::BadCount, message);
+    }
+    count
+  }
+
+  // Returns a required value, such as an XML attribute or a CSV field
+  fn required<'b>(&mut self, at: &Location, value: Option<&'b str>, what: &str) -> Option<&'b str> {
+    if value.is_none() {
+      let message = format!("Missing {} in deck list line: {}", what, at.text);
+      self.error(at, DeckListErrorKind::BadLine, message);
+    }
+    value
+  }
+
+  fn insert(
+    &mut self,
+    at: &Location,
+    zone: DeckZone,
+    name: &str,
+    count: usize,
+    set: Option<&str>,
+    collector_number: Option<&str>,
+  ) {
+    let set = match set {
+      Some(code) => match set_from_list_code(code) {
+        Some(set) => set,
+        None => {
+          let message = format!(
+            "Invalid set code \"{}\" in deck list line: {}",
+            code, at.text
+          );
+          self.error(at, DeckListErrorKind::BadSet, message);
+          return;
+        }
+      },
+      None => SetCode::UNKNOWN,
+    };
+    // By default, we represent split cards with the left face
+    let left_card_name = name.split("//").next().unwrap_or_default().trim();
+    let card = match card_from_list_name(left_card_name, false) {
+      Ok(card) => card.clone(),
+      Err(suggestions) => {
+        let error = unknown_card_error(at.line, at.columns.clone(), name, suggestions);
+        self.errors.push(error);
+        return;
+      }
+    };
+    let printing = Printing::new(&card, set, collector_number.map(str::to_string));
+    let builder = self.zones.remove(&zone).unwrap_or_default();
+    let cc = DeckCard {
+      card,
+      count,
+      printing,
+      modifiers: CardModifiers::default(),
+    };
+    if let Some(kept) = builder.conflicting_printing(&cc) {
+      let message = conflicting_printing_message(&cc, kept, at.text);
+      self.zones.insert(zone, builder);
+      self.error(at, DeckListErrorKind::ConflictingPrinting, message);
+      return;
+    }
+    self.zones.insert(zone, builder.insert_deck_card(cc));
+  }
+
+  fn build(mut self) -> Result<Deck, DeckcodeError> {
+    if !self.errors.is_empty() {
+      return Err(DeckcodeError(self.errors));
+    }
+    let mut zone_cards = |zone| self.zones.remove(&zone).unwrap_or_default().build().cards;
+    let sideboard = zone_cards(DeckZone::Sideboard);
+    let commander = zone_cards(DeckZone::Commander);
+    let companion = zone_cards(DeckZone::Companion);
+    let maybeboard = zone_cards(DeckZone::Maybeboard);
+    let mut deck = self
+      .zones
+      .remove(&DeckZone::Main)
+      .unwrap_or_default()
+      .build();
+    deck.sideboard = sideboard;
+    deck.commander = commander;
+    deck.companion = companion;
+    deck.maybeboard = maybeboard;
+    deck.title = self.title;
+    Ok(deck)
+  }
+}
+
+// The zones every format writes, in order
+const ZONES: [DeckZone; 5] = [
+  DeckZone::Commander,
+  DeckZone::Companion,
+  DeckZone::Main,
+  DeckZone::Sideboard,
+  DeckZone::Maybeboard,
+];
+
+// Returns the set code of the card, if known
+fn set_code(cc: &DeckCard) -> Option<SetCode> {
+  Some(cc.printing.set).filter(|set| !set.is_unknown())
+}
+
+lazy_static! {
+  static ref XML_ATTRIBUTE_REGEX: Regex =
+    Regex::new(r#"([\w:]+)\s*=\s*"([^"]*)""#).expect("Failed to compile XML_ATTRIBUTE_REGEX regex");
+  static ref MTGO_CARD_REGEX: Regex =
+    Regex::new(r"<Cards\s([^>]*)>").expect("Failed to compile MTGO_CARD_REGEX regex");
+  static ref COCKATRICE_ZONE_REGEX: Regex =
+    Regex::new(r#"(?s)<zone\s+name\s*=\s*"([^"]*)"\s*>(.*?)</zone>"#)
+      .expect("Failed to compile COCKATRICE_ZONE_REGEX regex");
+  static ref COCKATRICE_CARD_REGEX: Regex =
+    Regex::new(r"<card\s([^>]*)>").expect("Failed to compile COCKATRICE_CARD_REGEX regex");
+  static ref COCKATRICE_NAME_REGEX: Regex = Regex::new(r"(?s)<deckname>(.*?)</deckname>")
+    .expect("Failed to compile COCKATRICE_NAME_REGEX regex");
+}
+
+// Returns the attributes of an XML element, e.g. Quantity="4" Name="Shock"
+fn xml_attributes(element: &str) -> HashMap<String, String> {
+  XML_ATTRIBUTE_REGEX
+    .captures_iter(element)
+    .map(|caps| (caps[1].to_string(), xml_unescape(&caps[2])))
+    .collect()
+}
+
+fn xml_unescape(s: &str) -> String {
+  s.replace("&quot;", "\"")
+    .replace("&apos;", "'")
+    .replace("&#39;", "'")
+    .replace("&lt;", "<")
+    .replace("&gt;", ">")
+    .replace("&amp;", "&")
+}
+
+fn xml_escape(s: &str) -> String {
+  s.replace('&', "&amp;")
+    .replace('"', "&quot;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+}
+
+/// Returns a deck from a Magic Online `.dek` XML deck list
+pub fn from_mtgo(list: &str) -> Result<Deck, DeckcodeError> {
+  let mut builder = ZoneBuilder::default();
+  for caps in MTGO_CARD_REGEX.captures_iter(list) {
+    let at = Location::of_range(list, caps.get(0).expect("match").range());
+    let attributes = xml_attributes(&caps[1]);
+    let attribute = |name| attributes.get(name).map(String::as_str);
+    let count = builder.count(&at, attribute("Quantity"));
+    let name = builder.required(&at, attribute("Name"), "Name attribute");
+    let zone = match attribute("Sideboard") {
+      Some("true") => DeckZone::Sideboard,
+      _ => DeckZone::Main,
+    };
+    if let (Some(count), Some(name)) = (count, name) {
+      // Magic Online names split cards with a single slash, e.g. "Fire/Ice"
+      let name = name.split('/').next().unwrap_or_default();
+      builder.insert(&at, zone, name, count, None, None);
+    }
+  }
+  builder.build()
+}
+
+/// Returns the deck as a Magic Online `.dek` XML deck list. Commander cards are written
+/// to the main deck and companions to the sideboard
+pub fn to_mtgo(deck: &Deck) -> String {
+  let mut xml = String::from(
+    "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Deck xmlns:xsd=\"http://www.w3.org/2001/XMLSchema\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">\n  <NetDeckID>0</NetDeckID>\n  <PreconstructedDeckID>0</PreconstructedDeckID>\n",
+  );
+  for zone in &ZONES {
+    let sideboard = match zone {
+      DeckZone::Commander | DeckZone::Main => false,
+      DeckZone::Companion | DeckZone::Sideboard => true,
+      DeckZone::Maybeboard => continue,
+    };
+    for cc in deck.zone(*zone) {
+      xml.push_str(&format!(
+        "  <Cards Quantity=\"{}\" Sideboard=\"{}\" Name=\"{}\" Annotation=\"0\" />\n",
+        cc.count,
+        sideboard,
+        // Magic Online names split cards with a single slash, e.g. "Fire/Ice"
+        xml_escape(&list_name(&cc.card).replace(" // ", "/"))
+      ));
+    }
+  }
+  xml.push_str("</Deck>\n");
+  xml
+}
+
+/// Returns a deck from a Cockatrice `.cod` XML deck list
+pub fn from_cockatrice(list: &str) -> Result<Deck, DeckcodeError> {
+  let title = COCKATRICE_NAME_REGEX
+    .captures(list)
+    .map(|caps| xml_unescape(caps[1].trim()))
+    .filter(|name| !name.is_empty());
+  let mut builder = ZoneBuilder {
+    title,
+    ..Default::default()
+  };
+  for zone_caps in COCKATRICE_ZONE_REGEX.captures_iter(list) {
+    let zone = match &zone_caps[1] {
+      "main" => DeckZone::Main,
+      "side" => DeckZone::Sideboard,
+      // Tokens are not part of the deck
+      _ => continue,
+    };
+    let zone_start = zone_caps.get(2).expect("match").start();
+    for caps in COCKATRICE_CARD_REGEX.captures_iter(&zone_caps[2]) {
+      let range = caps.get(0).expect("match").range();
+      let at = Location::of_range(list, zone_start + range.start..zone_start + range.end);
+      let attributes = xml_attributes(&caps[1]);
+      let attribute = |name| attributes.get(name).map(String::as_str);
+      let count = builder.count(&at, attribute("number"));
+      let name = builder.required(&at, attribute("name"), "name attribute");
+      if let (Some(count), Some(name)) = (count, name) {
+        builder.insert(&at, zone, name, count, attribute("setShortName"), None);
+      }
+    }
+  }
+  builder.build()
+}
+
+/// Returns the deck as a Cockatrice `.cod` XML deck list. Commander cards are written
+/// to the main deck and companions to the sideboard
+pub fn to_cockatrice(deck: &Deck) -> String {
+  let mut xml =
+    String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<cockatrice_deck version=\"1\">\n");
+  xml.push_str(&format!(
+    "    <deckname>{}</deckname>\n    <comments></comments>\n",
+    xml_escape(deck.title.as_deref().unwrap_or_default())
+  ));
+  let zones: [(&str, &[DeckZone]); 2] = [
+    ("main", &[DeckZone::Commander, DeckZone::Main]),
+    ("side", &[DeckZone::Companion, DeckZone::Sideboard]),
+  ];
+  for (name, zones) in &zones {
+    let cards: Vec<_> = zones.iter().flat_map(|zone| deck.zone(*zone)).collect();
+    if cards.is_empty() {
+      continue;
+    }
+    xml.push_str(&format!("    <zone name=\"{}\">\n", name));
+    for cc in cards {
+      let name = xml_escape(list_name(&cc.card));
+      match set_code(cc) {
+        Some(set) => xml.push_str(&format!(
+          "        <card number=\"{}\" setShortName=\"{}\" name=\"{}\"/>\n",
+          cc.count, set, name
+        )),
+        None => xml.push_str(&format!(
+          "        <card number=\"{}\" name=\"{}\"/>\n",
+          cc.count, name
+        )),
+      }
+    }
+    xml.push_str("    </zone>\n");
+  }
+  xml.push_str("</cockatrice_deck>\n");
+  xml
+}
+
+/// Returns a deck from a Forge `.dck` deck list
+pub fn from_forge(list: &str) -> Result<Deck, DeckcodeError> {
+  let mut builder = ZoneBuilder::default();
+  // The current section, or None for the metadata and unsupported sections
+  let mut zone = None;
+  for (i, line) in list.lines().enumerate() {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+      continue;
+    }
+    if trimmed.starts_with('[') && trimmed.ends_with(']') {
+      zone = match trimmed[1..trimmed.len() - 1].to_lowercase().as_str() {
+        "main" => Some(DeckZone::Main),
+        "sideboard" => Some(DeckZone::Sideboard),
+        "commander" => Some(DeckZone::Commander),
+        _ => None,
+      };
+      continue;
+    }
+    let zone = match zone {
+      Some(zone) => zone,
+      None => {
+        if let Some(name) = trimmed.strip_prefix("Name=") {
+          builder.title = Some(name.trim().to_string());
+        }
+        continue;
+      }
+    };
+    // Card lines are "4 Shock|M21", optionally followed by the art index
+    let at = Location::of_line(i, line);
+    let (count, card) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+    let count = builder.count(&at, Some(count));
+    let mut parts = card.split('|');
+    let name = parts.next().map(str::trim).filter(|name| !name.is_empty());
+    let name = builder.required(&at, name, "card name");
+    let set = parts.next().map(str::trim);
+    if let (Some(count), Some(name)) = (count, name) {
+      builder.insert(&at, zone, name, count, set, None);
+    }
+  }
+  builder.build()
+}
+
+/// Returns the deck as a Forge `.dck` deck list. Companions are written to the sideboard
+pub fn to_forge(deck: &Deck) -> String {
+  let mut dck = String::from("[metadata]\n");
+  dck.push_str(&format!(
+    "Name={}\n",
+    deck.title.as_deref().unwrap_or_default()
+  ));
+  let sections: [(&str, &[DeckZone]); 3] = [
+    ("Commander", &[DeckZone::Commander]),
+    ("Main", &[DeckZone::Main]),
+    ("Sideboard", &[DeckZone::Companion, DeckZone::Sideboard]),
+  ];
+  for (name, zones) in &sections {
+    let cards: Vec<_> = zones.iter().flat_map(|zone| deck.zone(*zone)).collect();
+    if cards.is_empty() && *name != "Main" {
+      continue;
+    }
+    dck.push_str(&format!("[{}]\n", name));
+    for cc in cards {
+      let name = list_name(&cc.card);
+      match set_code(cc) {
+        Some(set) => dck.push_str(&format!("{} {}|{}\n", cc.count, name, set)),
+        None => dck.push_str(&format!("{} {}\n", cc.count, name)),
+      }
+    }
+  }
+  dck
+}
+
+// The headers of the CSV name column
+const CSV_NAME_COLUMNS: &[&str] = &["name", "card name", "card"];
+
+// Returns the fields of a CSV line, where fields may be quoted and quotes are escaped as ""
+fn csv_fields(line: &str) -> Vec<String> {
+  let mut fields = vec![String::new()];
+  let mut in_quotes = false;
+  let mut chars = line.chars().peekable();
+  while let Some(c) = chars.next() {
+    let field = fields.last_mut().expect("fields is never empty");
+    match c {
+      '"' if in_quotes && chars.peek() == Some(&'"') => {
+        chars.next();
+        field.push('"');
+      }
+      '"' => in_quotes = !in_quotes,
+      ',' if !in_quotes => fields.push(String::new()),
+      c => field.push(c),
+    }
+  }
+  fields
+}
+
+fn csv_field(field: &str) -> String {
+  if field.contains(',') || field.contains('"') {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+/// Returns a deck from a CSV deck list with a header row. The count and name columns are
+/// required, and the set, collector number and zone columns are optional. Zones are
+/// "main", "sideboard", "commander", "companion" or "maybeboard"
+pub fn from_csv(list: &str) -> Result<Deck, DeckcodeError> {
+  let mut lines = list
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty());
+  let (header_index, header_line) = lines.next().unwrap_or((0, ""));
+  let header: Vec<_> = csv_fields(header_line)
+    .into_iter()
+    .map(|field| field.trim().to_lowercase())
+    .collect();
+  let column = |names: &[&str]| {
+    header
+      .iter()
+      .position(|field| names.contains(&field.as_str()))
+  };
+  let mut builder = ZoneBuilder::default();
+  let at = Location::of_line(header_index, header_line);
+  let count_column = column(&["count", "quantity", "qty"]);
+  let name_column = column(CSV_NAME_COLUMNS);
+  for (column, name) in &[(count_column, "count"), (name_column, "name")] {
+    if column.is_none() {
+      let message = format!("Missing {} column in CSV header: {}", name, at.text);
+      builder.error(&at, DeckListErrorKind::BadLine, message);
+    }
+  }
+  if !builder.errors.is_empty() {
+    return builder.build();
+  }
+  let set_column = column(&["set", "set code", "edition"]);
+  let number_column = column(&["collector number", "number", "cn"]);
+  let zone_column = column(&["zone", "board", "section"]);
+  for (i, line) in lines {
+    let at = Location::of_line(i, line);
+    let fields = csv_fields(line);
+    let field = |column: Option<usize>| {
+      column
+        .and_then(|column| fields.get(column))
+        .map(|field| field.trim())
+        .filter(|field| !field.is_empty())
+    };
+    let count = builder.count(&at, field(count_column));
+    let name = builder.required(&at, field(name_column), "card name");
+    let zone = match field(zone_column).map(str::to_lowercase).as_deref() {
+      None | Some("main") | Some("deck") | Some("mainboard") => DeckZone::Main,
+      Some("sideboard") | Some("side") => DeckZone::Sideboard,
+      Some("commander") => DeckZone::Commander,
+      Some("companion") => DeckZone::Companion,
+      Some("maybeboard") | Some("maybe") => DeckZone::Maybeboard,
+      Some(zone) => {
+        let message = format!("Unknown zone \"{}\" in deck list line: {}", zone, line);
+        builder.error(&at, DeckListErrorKind::BadLine, message);
+        continue;
+      }
+    };
+    if let (Some(count), Some(name)) = (count, name) {
+      builder.insert(
+        &at,
+        zone,
+        name,
+        count,
+        field(set_column),
+        field(number_column),
+      );
+    }
+  }
+  builder.build()
+}
+
+/// Returns the deck as a CSV deck list with count, name, set, collector number and zone columns
+pub fn to_csv(deck: &Deck) -> String {
+  let mut csv = String::from("Count,Name,Set,Collector Number,Zone\n");
+  for zone in &ZONES {
+    for cc in deck.zone(*zone) {
+      let set = set_code(cc).map(|set| set.to_string()).unwrap_or_default();
+      csv.push_str(&format!(
+        "{},{},{},{},{}\n",
+        cc.count,
+        csv_field(list_name(&cc.card)),
+        set,
+        csv_field(cc.printing.collector_number.as_deref().unwrap_or_default()),
+        format!("{:?}", zone).to_lowercase()
+      ));
+    }
+  }
+  csv
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::deck::formats::*;
+  use crate::decklist;
+
+  fn sample_deck() -> Deck {
+    let mut deck = decklist!(
+      "
+      4 Shock (M19)
+      4 Fire // Ice
+      4 Jegantha, the Wellspring
+      48 Mountain
+
+      2 Negate
+      "
+    );
+    deck.title = Some("Izzet & \"Friends\"".to_string());
+    deck
+  }
+
+  fn counts(cards: &[DeckCard]) -> Vec<(String, usize)> {
+    cards
+      .iter()
+      .map(|cc| (cc.card.name.clone(), cc.count))
+      .collect()
+  }
+
+  #[test]
+  fn formats_round_trip() {
+    let deck = sample_deck();
+    for format in &[
+      DeckFormat::Arena,
+      DeckFormat::Mtgo,
+      DeckFormat::Cockatrice,
+      DeckFormat::Forge,
+      DeckFormat::Csv,
+    ] {
+      let list = export(&deck, *format);
+      assert_eq!(DeckFormat::detect(&list), *format, "{}", list);
+      let imported = import(&list).unwrap_or_else(|e| panic!("{:?}: {}\n{}", format, e, list));
+      assert_eq!(imported.len(), 60, "{:?}", format);
+      assert_eq!(counts(&imported.cards), counts(&deck.cards), "{:?}", format);
+      assert_eq!(
+        counts(&imported.sideboard),
+        counts(&deck.sideboard),
+        "{:?}",
+        format
+      );
+      // Only the Magic Online and CSV formats have no deck name
+      if *format != DeckFormat::Mtgo && *format != DeckFormat::Csv {
+        assert_eq!(imported.title, deck.title, "{:?}", format);
+      }
+    }
+  }
+
+  #[test]
+  fn read_mtgo() {
+    let dek = r#"<?xml version="1.0" encoding="utf-8"?>
+<Deck xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <NetDeckID>0</NetDeckID>
+  <PreconstructedDeckID>0</PreconstructedDeckID>
+  <Cards CatID="68048" Quantity="4" Sideboard="false" Name="Fire/Ice" Annotation="0" />
+  <Cards CatID="12345" Quantity="20" Sideboard="false" Name="Island" Annotation="0" />
+  <Cards CatID="54321" Quantity="3" Sideboard="true" Name="Negate" Annotation="0" />
+</Deck>"#;
+    let deck = import(dek).unwrap();
+    assert_eq!(deck.len(), 24);
+    assert_eq!(deck.card_count_from_name("Fire").unwrap().count, 4);
+    assert_eq!(deck.sideboard_len(), 3);
+    assert!(to_mtgo(&deck).contains(" Name=\"Fire/Ice\" "));
+  }
+
+  #[test]
+  fn read_cockatrice_forge_and_csv() {
+    let cod = r#"<?xml version="1.0" encoding="UTF-8"?>
+<cockatrice_deck version="1">
+    <deckname>Mono Blue</deckname>
+    <comments>Counter everything</comments>
+    <zone name="main">
+        <card number="20" name="Island"/>
+        <card number="4" setShortName="M20" name="Negate"/>
+    </zone>
+    <zone name="side">
+        <card number="2" name="Opt"/>
+    </zone>
+    <zone name="tokens">
+        <card number="1" name="Not a card"/>
+    </zone>
+</cockatrice_deck>"#;
+    let deck = import(cod).unwrap();
+    assert_eq!(deck.title.as_deref(), Some("Mono Blue"));
+    assert_eq!(deck.len(), 24);
+    assert_eq!(
+      deck
+        .card_count_from_name("Negate")
+        .unwrap()
+        .printing
+        .set
+        .to_string(),
+      "M20"
+    );
+    assert_eq!(deck.sideboard_len(), 2);
+    let round_trip = import(&to_cockatrice(&deck)).unwrap();
+    let negate = round_trip.card_count_from_name("Negate").unwrap();
+    assert_eq!(negate.printing.set.to_string(), "M20");
+    assert!(to_cockatrice(&deck).contains("<card number=\"20\" name=\"Island\"/>"));
+    let dck =
+      "[metadata]\nName=Mono Blue\n[Main]\n20 Island|M20|1\n4 Negate|M20\n[Sideboard]\n2 Opt|XLN\n";
+    let deck = import(dck).unwrap();
+    assert_eq!(deck.title.as_deref(), Some("Mono Blue"));
+    assert_eq!(deck.len(), 24);
+    assert_eq!(deck.sideboard[0].printing.set.to_string(), "XLN");
+    let csv = "Quantity,Card Name,Edition\n20,Island,M20\n4,Negate,\n\"2\",\"Jegantha, the Wellspring\",IKO\n";
+    let deck = import(csv).unwrap();
+    assert_eq!(deck.len(), 26);
+    assert!(deck.card_from_name("Jegantha, the Wellspring").is_some());
+    // Arena lists with commas in card names are not CSV
+    assert_eq!(
+      DeckFormat::detect("1 Jegantha, the Wellspring\n59 Mountain"),
+      DeckFormat::Arena
+    );
+    assert_eq!(DeckFormat::from_extension(".DEK"), Some(DeckFormat::Mtgo));
+    assert!(import("Count,Name\nfour,Island").is_err());
+  }
+
+  #[test]
+  fn every_format_problem() {
+    let csv = "Count,Name,Set\nfour,Island,M20\n4,Shok,M20\n4,Opt,TOOLONG\n20,Island,M20\n";
+    let errors = import(csv).unwrap_err().0;
+    let kinds: Vec<_> = errors.iter().map(|e| (e.line, e.kind)).collect();
+    assert_eq!(
+      kinds,
+      vec![
+        (2, DeckListErrorKind::BadCount),
+        (3, DeckListErrorKind::UnknownCard),
+        (4, DeckListErrorKind::BadSet),
+      ]
+    );
+    assert_eq!(errors[1].suggestions[0], "Shock");
+    let dek = "<Deck>\n  <Cards Quantity=\"4\" Sideboard=\"false\" Name=\"Shok\" />\n  <Cards Quantity=\"4\" Sideboard=\"false\" />\n</Deck>";
+    let errors = import(dek).unwrap_err().0;
+    assert_eq!(errors.len(), 2);
+    assert_eq!((errors[0].line, errors[0].columns.clone()), (2, 2..54));
+    assert_eq!(errors[1].kind, DeckListErrorKind::BadLine);
+    assert!(import("Name\nIsland").is_err());
+  }
+}
@@ -0,0 +1,2187 @@
+use crate::card::*;
+use crate::collection::cmp_ignore_case;
+use crate::data::*;
+use regex::Regex;
+use std::collections::HashMap;
+use std::ops::{Deref, Range};
+
+pub mod formats;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Deck {
+  pub title: Option<String>,
+  pub url: Option<String>,
+  pub cards: Vec<DeckCard>,
+  /// The sideboard cards, which are not part of the simulated library
+  #[serde(default)]
+  pub sideboard: Vec<DeckCard>,
+  /// The commander cards, which are not part of the simulated library
+  #[serde(default)]
+  pub commander: Vec<DeckCard>,
+  /// The [companion](https://mtg.gamepedia.com/Companion) cards, which are not part of the simulated library
+  #[serde(default)]
+  pub companion: Vec<DeckCard>,
+  /// The maybeboard cards, the cards under consideration for the deck
+  #[serde(default)]
+  pub maybeboard: Vec<DeckCard>,
+  pub format: GameFormat,
+  pub card_count: usize,
+}
+
+/// DeckZone represents a section of a deck list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DeckZone {
+  Main,
+  Sideboard,
+  Commander,
+  Companion,
+  Maybeboard,
+}
+
+// A section of a deck list, either a zone or the About section that names the deck
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+  About,
+  Zone(DeckZone),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeckCard {
+  pub card: Card,
+  pub count: usize,
+  /// The printing from the deck list, e.g. "(XLN) 22" in "4 Legion's Landing (XLN) 22"
+  #[serde(default)]
+  pub printing: Printing,
+  /// The modifiers from the deck list, e.g. "X=2" in "4 Mass Manipulation # X=2"
+  #[serde(default)]
+  pub modifiers: CardModifiers,
+}
+
+/// Printing represents the printing of a deck card, which may differ from the printing of the
+/// card in the card database
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Printing {
+  /// The set code, or the unknown set if the deck list names no set
+  pub set: SetCode,
+  /// The collector number, e.g. "22"
+  pub collector_number: Option<String>,
+  /// A URI to an image of the printing, if the card database has the printing
+  pub image_uri: Option<String>,
+  /// The MTG Arena id of the printing, if the card database has the printing
+  pub arena_id: Option<u64>,
+}
+
+impl Printing {
+  /// Returns the printing of the card with the set and collector number. The printing has
+  /// the image and Arena id of the card when the card database has the same printing
+  pub fn new(card: &Card, set: SetCode, collector_number: Option<String>) -> Self {
+    // An unknown card database collector number never matches a deck list number
+    let same_number = match &collector_number {
+      Some(number) => !card.collector_number.is_empty() && *number == card.collector_number,
+      None => true,
+    };
+    if !set.is_unknown() && set == card.set && same_number {
+      return Self {
+        collector_number: collector_number.or_else(|| Self::of_card(card).collector_number),
+        ..Self::of_card(card)
+      };
+    }
+    Self {
+      set,
+      collector_number,
+      image_uri: None,
+      arena_id: None,
+    }
+  }
+
+  /// Returns the printing of the card in the card database
+  pub fn of_card(card: &Card) -> Self {
+    Self {
+      set: card.set,
+      collector_number: Some(card.collector_number.clone()).filter(|number| !number.is_empty()),
+      image_uri: Some(card.image_uri.clone()).filter(|uri| !uri.is_empty()),
+      arena_id: Some(card.arena_id).filter(|id| *id != 0),
+    }
+  }
+
+  /// Returns true if both printings name a set, and the printings differ in their set or
+  /// collector number
+  pub fn conflicts_with(&self, other: &Printing) -> bool {
+    if self.set.is_unknown() || other.set.is_unknown() {
+      return false;
+    }
+    let numbers_differ = match (&self.collector_number, &other.collector_number) {
+      (Some(number), Some(other_number)) => number != other_number,
+      _ => false,
+    };
+    self.set != other.set || numbers_differ
+  }
+}
+
+impl std::fmt::Display for Printing {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "({})", self.set)?;
+    if let Some(number) = &self.collector_number {
+      write!(f, " {}", number)?;
+    }
+    Ok(())
+  }
+}
+
+impl DeckCard {
+  /// Returns a URI to an image of the printing, or of the card if the card database
+  /// does not have the printing
+  pub fn image_uri(&self) -> &str {
+    self
+      .printing
+      .image_uri
+      .as_deref()
+      .unwrap_or(&self.card.image_uri)
+  }
+}
+
+/// CardModifiers represents the modifiers of a deck list line, which change how the
+/// simulation plays the card. The modifiers are written after the card, e.g. "# X=2 T=1 M={R}"
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardModifiers {
+  /// The X = modifier, the value of X in the mana cost of the card
+  pub x: Option<u8>,
+  /// The T = modifier, the number of turns to wait before playing the card
+  pub turn: Option<u8>,
+  /// The M = modifier, a mana cost the card produces as a land, e.g. "{R}"
+  pub mana: Option<String>,
+}
+
+impl CardModifiers {
+  /// Returns true if the deck list line has no modifiers
+  pub fn is_empty(&self) -> bool {
+    self.x.is_none() && self.turn.is_none() && self.mana.is_none()
+  }
+}
+
+impl std::fmt::Display for CardModifiers {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let mut modifiers = Vec::new();
+    if let Some(x) = self.x {
+      modifiers.push(format!("X={}", x));
+    }
+    if let Some(turn) = self.turn {
+      modifiers.push(format!("T={}", turn));
+    }
+    if let Some(mana) = &self.mana {
+      modifiers.push(format!("M={}", mana));
+    }
+    write!(f, "{}", modifiers.join(" "))
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DeckBuilder {
+  pub cards: HashMap<Card, usize>,
+  // The printing and modifiers of the first deck card inserted for a card, or the
+  // printing of a later deck card if the first names no set
+  details: HashMap<Card, (Printing, CardModifiers)>,
+}
+
+impl DeckBuilder {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn insert(mut self, mut card: Card) -> Self {
+    card.name = card.name.clone();
+    let total_count = self.cards.entry(card).or_insert(0);
+    *total_count += 1;
+    self
+  }
+
+  pub fn insert_count(mut self, mut card: Card, count: usize) -> Self {
+    card.name = card.name.clone();
+    let total_count = self.cards.entry(card).or_insert(0);
+    *total_count += count;
+    self
+  }
+
+  /// Inserts the deck card, keeping its printing and modifiers
+  ///
+  /// A card holds a single printing, so check `conflicting_printing` first: the printing
+  /// of a deck card is dropped if the card was inserted with another printing before
+  pub fn insert_deck_card(mut self, cc: DeckCard) -> Self {
+    match self.details.get_mut(&cc.card) {
+      Some((printing, _)) => {
+        if printing.set.is_unknown() {
+          *printing = cc.printing;
+        }
+      }
+      None => {
+        self
+          .details
+          .insert(cc.card.clone(), (cc.printing, cc.modifiers));
+      }
+    }
+    self.insert_count(cc.card, cc.count)
+  }
+
+  /// Returns the printing the card was inserted with, if it conflicts with the printing
+  /// of the deck card, see `Printing::conflicts_with`
+  pub fn conflicting_printing(&self, cc: &DeckCard) -> Option<&Printing> {
+    self
+      .details
+      .get(&cc.card)
+      .map(|(printing, _)| printing)
+      .filter(|printing| printing.conflicts_with(&cc.printing))
+  }
+
+  pub fn build(mut self) -> Deck {
+    let mut deck = Deck::new();
+    let mut count = 0;
+    for (k, v) in self.cards {
+      let (printing, modifiers) = self.details.remove(&k).unwrap_or_default();
+      deck.cards.push(DeckCard {
+        card: k,
+        count: v,
+        printing,
+        modifiers,
+      });
+      count += v;
+    }
+    deck.card_count = count;
+    // Sorted by name, ignoring case, for the binary search in card_count_from_name
+    deck
+      .cards
+      .sort_unstable_by(|a, b| cmp_ignore_case(&a.card.name, &b.card.name));
+    deck
+  }
+}
+
+/// DeckcodeError represents every problem with a deck list, in deck list order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckcodeError(pub Vec<DeckListError>);
+
+/// DeckListError represents a problem with a line of a deck list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckListError {
+  /// The line number, starting at 1
+  pub line: usize,
+  /// The character columns of the problem in the line, starting at 0
+  pub columns: Range<usize>,
+  pub kind: DeckListErrorKind,
+  /// The closest card names, for an unknown card name
+  pub suggestions: Vec<String>,
+  /// The problem, e.g. "Cannot find card named "Shok" in collection"
+  pub message: String,
+}
+
+/// DeckListErrorKind represents the kinds of deck list problems
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DeckListErrorKind {
+  /// A line that is neither a card nor a section header
+  BadLine,
+  /// A card amount that is not a number
+  BadCount,
+  /// A card name missing from the collection
+  UnknownCard,
+  /// A set code that is not a valid set code, e.g. longer than six characters
+  BadSet,
+  /// An X =, T = or M = modifier with a bad value
+  BadModifier,
+  /// A card listed again in the same section with a different printing
+  ConflictingPrinting,
+}
+
+impl DeckListError {
+  pub fn new(line: usize, columns: Range<usize>, kind: DeckListErrorKind, message: String) -> Self {
+    Self {
+      line,
+      columns,
+      kind,
+      suggestions: Vec::new(),
+      message,
+    }
+  }
+}
+
+impl std::fmt::Display for DeckListError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+impl std::error::Error for DeckListError {}
+
+impl std::fmt::Display for DeckcodeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let messages: Vec<_> = self.0.iter().map(|e| e.message.as_str()).collect();
+    write!(f, "{}", messages.join("\n"))
+  }
+}
+
+impl std::error::Error for DeckcodeError {}
+
+impl From<DeckListError> for DeckcodeError {
+  fn from(e: DeckListError) -> Self {
+    Self(vec![e])
+  }
+}
+
+/// FormatRules represents the deck construction rules of a GameFormat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatRules {
+  /// The minimum number of main deck and commander cards
+  pub min_deck_size: usize,
+  /// The maximum number of main deck and commander cards, if any
+  pub max_deck_size: Option<usize>,
+  /// The maximum number of sideboard cards
+  pub max_sideboard_size: usize,
+  /// The maximum number of copies of a card other than a basic land,
+  /// counted across the main deck, sideboard and commander
+  pub max_copies: usize,
+  /// True if every card must be a common, see [Pauper](https://mtg.gamepedia.com/Pauper)
+  pub commons_only: bool,
+}
+
+impl FormatRules {
+  const CONSTRUCTED: Self = Self {
+    min_deck_size: 60,
+    max_deck_size: None,
+    max_sideboard_size: 15,
+    max_copies: 4,
+    commons_only: false,
+  };
+
+  const SINGLETON: Self = Self {
+    min_deck_size: 100,
+    max_deck_size: Some(100),
+    max_sideboard_size: 0,
+    max_copies: 1,
+    commons_only: false,
+  };
+}
+
+impl GameFormat {
+  /// Returns the deck construction rules of the format
+  pub fn rules(&self) -> FormatRules {
+    match self {
+      Self::Pauper => FormatRules {
+        commons_only: true,
+        ..FormatRules::CONSTRUCTED
+      },
+      Self::PauperCommander => FormatRules {
+        commons_only: true,
+        ..FormatRules::SINGLETON
+      },
+      Self::StandardBrawl | Self::Oathbreaker => FormatRules {
+        min_deck_size: 60,
+        max_deck_size: Some(60),
+        ..FormatRules::SINGLETON
+      },
+      Self::Commander | Self::Duel | Self::Brawl | Self::Gladiator | Self::Predh => {
+        FormatRules::SINGLETON
+      }
+      Self::Other => FormatRules {
+        min_deck_size: 0,
+        max_deck_size: None,
+        max_sideboard_size: usize::MAX,
+        max_copies: usize::MAX,
+        commons_only: false,
+      },
+      _ => FormatRules::CONSTRUCTED,
+    }
+  }
+}
+
+/// DeckViolation represents a reason a deck is not legal in a GameFormat
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeckViolation {
+  /// The card is banned in the format
+  Banned(String),
+  /// The card is not legal in the format
+  NotLegal(String),
+  /// More than one copy of a restricted card
+  Restricted { name: String, count: usize },
+  /// More copies of a card than the format allows
+  TooManyCopies {
+    name: String,
+    count: usize,
+    max: usize,
+  },
+  /// A card that is not a common in a commons only format
+  NotCommon { name: String, rarity: Rarity },
+  /// Fewer main deck cards than the format requires
+  TooFewCards { count: usize, min: usize },
+  /// More main deck cards than the format allows
+  TooManyCards { count: usize, max: usize },
+  /// More sideboard cards than the format allows
+  SideboardTooLarge { count: usize, max: usize },
+}
+
+impl std::fmt::Display for DeckViolation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Banned(name) => write!(f, "{} is banned", name),
+      Self::NotLegal(name) => write!(f, "{} is not legal", name),
+      Self::Restricted { name, count } => {
+        write!(f, "{} is restricted to 1 copy, found {}", name, count)
+      }
+      Self::TooManyCopies { name, count, max } => {
+        write!(f, "{} is limited to {} copies, found {}", name, max, count)
+      }
+      Self::NotCommon { name, rarity } => write!(f, "{} is not a common ({:?})", name, rarity),
+      Self::TooFewCards { count, min } => {
+        write!(f, "Deck has {} cards, at least {} are required", count, min)
+      }
+      Self::TooManyCards { count, max } => {
+        write!(f, "Deck has {} cards, at most {} are allowed", count, max)
+      }
+      Self::SideboardTooLarge { count, max } => {
+        write!(
+          f,
+          "Sideboard has {} cards, at most {} are allowed",
+          count, max
+        )
+      }
+    }
+  }
+}
+
+impl Deck {
+  pub fn new() -> Self {
+    Self {
+      title: None,
+      url: None,
+      cards: Vec::with_capacity(20),
+      sideboard: Vec::new(),
+      commander: Vec::new(),
+      companion: Vec::new(),
+      maybeboard: Vec::new(),
+      format: GameFormat::Standard,
+      card_count: 0,
+    }
+  }
+
+  pub fn common_count(&self) -> usize {
+    self
+      .cards
+      .iter()
+      .filter(|cc| cc.card.rarity == Rarity::Common && cc.card.kind != CardKind::BasicLand)
+      .fold(0, |accum, cc| accum + cc.count)
+  }
+
+  pub fn uncommon_count(&self) -> usize {
+    self
+      .cards
+      .iter()
+      .filter(|cc| cc.card.rarity == Rarity::Uncommon && cc.card.kind != CardKind::BasicLand)
+      .fold(0, |accum, cc| accum + cc.count)
+  }
+
+  pub fn rare_count(&self) -> usize {
+    self
+      .cards
+      .iter()
+      .filter(|cc| cc.card.rarity == Rarity::Rare && cc.card.kind != CardKind::BasicLand)
+      .fold(0, |accum, cc| accum + cc.count)
+  }
+
+  pub fn mythic_count(&self) -> usize {
+    self
+      .cards
+      .iter()
+      .filter(|cc| cc.card.rarity == Rarity::Mythic && cc.card.kind != CardKind::BasicLand)
+      .fold(0, |accum, cc| accum + cc.count)
+  }
+
+  pub fn mana_counts(&self) -> ManaColorCount {
+    let mut mcc = ManaColorCount::new();
+    for cc in &self.cards {
+      for _ in 0..cc.count {
+        mcc.count(&cc.card.mana_cost);
+      }
+    }
+    mcc
+  }
+
+  pub fn mana_counts_for_lands(&self) -> ManaColorCount {
+    let mut mcc = ManaColorCount::new();
+    for cc in &self.cards {
+      if !cc.card.is_land() {
+        continue;
+      }
+      for _ in 0..cc.count {
+        mcc.count(&cc.card.mana_cost);
+      }
+    }
+    mcc
+  }
+
+  pub fn mana_counts_for_nonlands(&self) -> ManaColorCount {
+    let mut mcc = ManaColorCount::new();
+    for cc in &self.cards {
+      if cc.card.is_land() {
+        continue;
+      }
+      for _ in 0..cc.count {
+        mcc.count(&cc.card.mana_cost);
+      }
+    }
+    mcc
+  }
+
+  pub fn mana_counts_for_craftables(&self) -> ManaColorCount {
+    let mut mcc = ManaColorCount::new();
+    for cc in &self.cards {
+      if cc.card.kind == CardKind::BasicLand {
+        continue;
+      }
+      for _ in 0..cc.count {
+        mcc.count(&cc.card.mana_cost);
+      }
+    }
+    mcc
+  }
+
+  pub fn from_cards<I>(cards: I) -> Self
+  where
+    I: IntoIterator<Item = Card>,
+  {
+    let mut b = DeckBuilder::new();
+    for card in cards {
+      b = b.insert(card);
+    }
+    b.build()
+  }
+
+  pub fn flatten(&self) -> Vec<&Card> {
+    let mut result = Vec::with_capacity(self.card_count);
+    for card_count in &self.cards {
+      for _ in 0..card_count.count {
+        result.push(&card_count.card);
+      }
+    }
+    result
+  }
+
+  pub fn card_from_name(&self, name: &str) -> Option<&Card> {
+    self.card_count_from_name(name).map(|o| &o.card)
+  }
+
+  pub fn card_count_from_name(&self, name: &str) -> Option<&DeckCard> {
+    let res = self
+      .cards
+      .binary_search_by(|probe| cmp_ignore_case(&probe.card.name, name));
+    res.map(|idx| &self.cards[idx]).ok()
+  }
+
+  pub fn len(&self) -> usize {
+    self.card_count
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Returns a deck from an Arena style deck list. Card names that differ from the card name by case,
+  /// accents, punctuation or an Alchemy "A-" prefix are accepted. The error lists every problem
+  /// with the deck list, and the closest card names for each unknown card name
+  ///
+  /// The "About", "Deck", "Commander", "Companion", "Sideboard" and "Maybeboard" headers start
+  /// a section. Without a "Sideboard" header, an empty line after the main deck starts the
+  /// sideboard, and an empty line after the sideboard or maybeboard ends the deck list
+  pub fn from_list(list: &str) -> Result<Self, DeckcodeError> {
+    Self::parse_list(list, false)
+  }
+
+  /// Returns a deck from an Arena style deck list like `from_list`, but also accepts a misspelled
+  /// card name when a single card name is closest to it
+  pub fn from_list_lenient(list: &str) -> Result<Self, DeckcodeError> {
+    Self::parse_list(list, true)
+  }
+
+  fn parse_list(list: &str, lenient: bool) -> Result<Self, DeckcodeError> {
+    lazy_static! {
+        //https://regex101.com/r/OluNfe/3
+        static ref ARENA_LINE_REGEX: Regex =
+            Regex::new(r"^\s*(?P<amount>\d+)\s+(?P<name>[^\(#\n\r]+)(?:\s*\((?P<set>\w+)\)\s+(?P<setnum>\d+\w*))?\s*#?(?:\s*[Xx]\s*=\s*(?P<X>\d+))?(?:\s*[Tt]\s*=\s*(?P<T>\d+))?(?:\s*[Mm]\s*=\s*(?P<M>[RGWUB\d{}]+))?")
+                .expect("Failed to compile ARENA_LINE_REGEX regex");
+    }
+    let mut zones: HashMap<DeckZone, DeckBuilder> = HashMap::new();
+    let mut title = None;
+    let mut section = Section::Zone(DeckZone::Main);
+    // True once the list ends, so that only a section header continues it
+    let mut ended = false;
+    let mut errors = Vec::new();
+    for (i, line) in list.lines().enumerate() {
+      let trimmed = line.trim();
+      // Section headers, e.g. "Sideboard"
+      let header = match trimmed.to_lowercase().as_str() {
+        "about" => Some(Section::About),
+        "deck" => Some(Section::Zone(DeckZone::Main)),
+        "commander" => Some(Section::Zone(DeckZone::Commander)),
+        "companion" => Some(Section::Zone(DeckZone::Companion)),
+        "sideboard" => Some(Section::Zone(DeckZone::Sideboard)),
+        "maybeboard" => Some(Section::Zone(DeckZone::Maybeboard)),
+        _ => None,
+      };
+      if let Some(header) = header {
+        section = header;
+        ended = false;
+        continue;
+      }
+      // Ignore line comments
+      if trimmed.starts_with('#') || ended {
+        continue;
+      }
+      let zone = match section {
+        Section::Zone(zone) => zone,
+        Section::About => {
+          // The About section names the deck, e.g. "Name Mono Red"
+          if let Some(name) = trimmed.strip_prefix("Name ") {
+            title = Some(name.trim().to_string());
+          } else if trimmed.is_empty() {
+            section = Section::Zone(DeckZone::Main);
+          }
+          continue;
+        }
+      };
+      // An empty line ends the commander and companion sections, and divides the main
+      // board cards from the side board cards
+      if trimmed.is_empty() {
+        let has_cards = zones.get(&zone).into_iter().any(|b| !b.cards.is_empty());
+        match zone {
+          DeckZone::Commander | DeckZone::Companion => section = Section::Zone(DeckZone::Main),
+          DeckZone::Main if has_cards => section = Section::Zone(DeckZone::Sideboard),
+          DeckZone::Sideboard | DeckZone::Maybeboard if has_cards => ended = true,
+          _ => {}
+        }
+        continue;
+      }
+      let line_number = i + 1;
+      // Returns the character columns of a byte range of the trimmed line
+      let indent = line.len() - line.trim_start().len();
+      let columns = |start: usize, end: usize| {
+        line[..indent + start].chars().count()..line[..indent + end].chars().count()
+      };
+      let caps = match ARENA_LINE_REGEX.captures(trimmed) {
+        Some(caps) => caps,
+        None => {
+          errors.push(DeckListError::new(
+            line_number,
+            columns(0, trimmed.len()),
+            DeckListErrorKind::BadLine,
+            format!("Cannot regex capture deck list line: {}", line),
+          ));
+          continue;
+        }
+      };
+      let span = |name: &str| {
+        caps
+          .name(name)
+          .map_or(0..0, |m| columns(m.start(), m.end()))
+      };
+      let line_errors = errors.len();
+      let amount = caps["amount"].parse::<usize>().unwrap_or_else(|_| {
+        errors.push(DeckListError::new(
+          line_number,
+          span("amount"),
+          DeckListErrorKind::BadCount,
+          format!(
+            "Cannot parse usize card amount from deck list line: {}",
+            line
+          ),
+        ));
+        0
+      });
+      let name = caps["name"].trim();
+      // Arena set codes, e.g. DAR, resolve to the set code, e.g. DOM
+      let set = match caps.name("set") {
+        Some(set) => set_from_list_code(set.as_str()).unwrap_or_else(|| {
+          errors.push(DeckListError::new(
+            line_number,
+            span("set"),
+            DeckListErrorKind::BadSet,
+            format!(
+              "Invalid set code \"{}\" in deck list line: {}",
+              set.as_str(),
+              line
+            ),
+          ));
+          SetCode::UNKNOWN
+        }),
+        None => SetCode::UNKNOWN,
+      };
+      // By default, we represent split cards with the left face
+      let left_card_name = name.split("//").next().unwrap_or_default().trim();
+      // Find the card from the name, and clone it so we can apply card modifiers
+      let mut card = match card_from_list_name(left_card_name, lenient) {
+        Ok(card) => card.clone(),
+        Err(suggestions) => {
+          let name_start = caps.name("name").map_or(0, |m| m.start());
+          let name_columns = columns(name_start, name_start + name.len());
+          errors.push(unknown_card_error(
+            line_number,
+            name_columns,
+            name,
+            suggestions,
+          ));
+          continue;
+        }
+      };
+      let mut modifiers = CardModifiers::default();
+      let bad_modifier = |modifier: &str, message: String| {
+        DeckListError::new(
+          line_number,
+          span(modifier),
+          DeckListErrorKind::BadModifier,
+          message,
+        )
+      };
+      // Handle the X = modifier
+      if let Some(x_val) = caps.name("X") {
+        // Only modify the colorless mana cost if the mana cost string contains an X value
+        // otherwise ignore the attribute
+        if card.mana_cost_string.contains('X') {
+          let x_val = x_val.as_str().parse::<u8>().map_err(|_| {
+            bad_modifier(
+              "X",
+              format!("Cannot parse u8 X= value from deck list line: {}", line),
+            )
+          });
+          let symbols = mana_symbols_from_str(&card.mana_cost_string)
+            .map_err(|e| bad_modifier("X", format!("{} for card at deck list line: {}", e, line)));
+          match (x_val, symbols) {
+            (Ok(x_val), Ok(symbols)) => {
+              card.all_mana_costs = mana_costs_from_symbols(&symbols, x_val);
+              card.mana_cost = card.all_mana_costs[0];
+              card.mana_cost_string = symbols
+                .iter()
+                .map(|symbol| match symbol {
+                  ManaSymbol::Variable(_) => ManaSymbol::Generic(x_val).to_string(),
+                  _ => symbol.to_string(),
+                })
+                .collect();
+              card.turn = card.mana_cost.cmc();
+              modifiers.x = Some(x_val);
+            }
+            (Err(e), _) | (_, Err(e)) => errors.push(e),
+          }
+        }
+      }
+      // Handle the M = modifier
+      if let Some(m_val) = caps.name("M") {
+        let mana_cost_str = m_val.as_str();
+        match mana_costs_from_str(mana_cost_str) {
+          Ok(all_mana_costs) => {
+            card.mana_cost = all_mana_costs[0];
+            card.all_mana_costs = all_mana_costs;
+            card.turn = card.mana_cost.cmc();
+            card.kind = CardKind::ForcedLand;
+            modifiers.mana = Some(mana_cost_str.to_string());
+          }
+          Err(e) => errors.push(bad_modifier(
+            "M",
+            format!(
+              "Problematic mana cost ('M = ') specifed at line {}: {}",
+              line, e
+            ),
+          )),
+        }
+      }
+      // Hanlde the T = modifier
+      if let Some(turn_val) = caps.name("T") {
+        // TODO(jshrake): Set the desired turn to play this card
+        match turn_val.as_str().parse::<u8>() {
+          Ok(turn_val) => {
+            card.turn += turn_val;
+            modifiers.turn = Some(turn_val);
+          }
+          Err(_) => errors.push(bad_modifier(
+            "T",
+            format!("Cannot parse u8 T= value from deck list line: {}", line),
+          )),
+        }
+      }
+      // Skip the card if the line has problems, to report the problems with the other lines
+      if errors.len() > line_errors {
+        continue;
+      }
+      let collector_number = caps.name("setnum").map(|num| num.as_str().to_string());
+      let printing = Printing::new(&card, set, collector_number);
+      let builder = zones.remove(&zone).unwrap_or_default();
+      let cc = DeckCard {
+        card,
+        count: amount,
+        printing,
+        modifiers,
+      };
+      if let Some(kept) = builder.conflicting_printing(&cc) {
+        errors.push(DeckListError::new(
+          line_number,
+          span("set"),
+          DeckListErrorKind::ConflictingPrinting,
+          conflicting_printing_message(&cc, kept, line),
+        ));
+        zones.insert(zone, builder);
+        continue;
+      }
+      zones.insert(zone, builder.insert_deck_card(cc));
+    }
+    let mut zone_cards = |zone| zones.remove(&zone).unwrap_or_default().build().cards;
+    let sideboard = zone_cards(DeckZone::Sideboard);
+    let commander = zone_cards(DeckZone::Commander);
+    let companion = zone_cards(DeckZone::Companion);
+    let maybeboard = zone_cards(DeckZone::Maybeboard);
+    let mut deck = zones.remove(&DeckZone::Main).unwrap_or_default().build();
+    deck.sideboard = sideboard;
+    deck.commander = commander;
+    deck.companion = companion;
+    deck.maybeboard = maybeboard;
+    deck.title = title;
+    if !errors.is_empty() {
+      return Err(DeckcodeError(errors));
+    }
+    Ok(deck)
+  }
+
+  /// Returns the cards in the zone
+  pub fn zone(&self, zone: DeckZone) -> &[DeckCard] {
+    match zone {
+      DeckZone::Main => &self.cards,
+      DeckZone::Sideboard => &self.sideboard,
+      DeckZone::Commander => &self.commander,
+      DeckZone::Companion => &self.companion,
+      DeckZone::Maybeboard => &self.maybeboard,
+    }
+  }
+
+  /// Returns the deck as a deck list, see `to_export`
+  pub fn to_string(&self) -> String {
+    self.to_export()
+  }
+
+  /// Returns the deck as an Arena style deck list, with a section for each zone and the
+  /// modifiers of each card, such that `Deck::from_list` returns the same deck
+  pub fn to_export(&self) -> String {
+    self.export_list(true)
+  }
+
+  /// Returns the deck as a deck list for MTG Arena to import. Unlike `to_export`, it leaves
+  /// out the modifiers and the maybeboard, which Arena does not support
+  pub fn to_arena_export(&self) -> String {
+    self.export_list(false)
+  }
+
+  fn export_list(&self, landlord: bool) -> String {
+    let mut sections = Vec::new();
+    if let Some(title) = &self.title {
+      sections.push(format!("About\nName {}\n", title));
+    }
+    let zones = [
+      (DeckZone::Commander, "Commander"),
+      (DeckZone::Companion, "Companion"),
+      (DeckZone::Main, "Deck"),
+      (DeckZone::Sideboard, "Sideboard"),
+      (DeckZone::Maybeboard, "Maybeboard"),
+    ];
+    for (zone, header) in &zones {
+      let cards = self.zone(*zone);
+      if cards.is_empty() || (*zone == DeckZone::Maybeboard && !landlord) {
+        continue;
+      }
+      let mut section = format!("{}\n", header);
+      for cc in cards {
+        section.push_str(&format!("{} {}", cc.count, list_name(&cc.card)));
+        // Arena requires both the set and the collector number, and uses its own set codes
+        let printing = &cc.printing;
+        if let (false, Some(number)) = (printing.set.is_unknown(), &printing.collector_number) {
+          let set = ALL_SETS.set_from_code(printing.set);
+          let code = set.and_then(|set| set.arena_code).unwrap_or(printing.set);
+          section.push_str(&format!(" ({}) {}", code, number));
+        }
+        if landlord && !cc.modifiers.is_empty() {
+          section.push_str(&format!(" # {}", cc.modifiers));
+        }
+        section.push('\n');
+      }
+      sections.push(section);
+    }
+    sections.join("\n")
+  }
+
+  /// Returns the number of sideboard cards
+  pub fn sideboard_len(&self) -> usize {
+    self.sideboard.iter().map(|cc| cc.count).sum()
+  }
+
+  /// Returns every reason the deck is not legal in the format
+  ///
+  /// Cards without legality data are only checked against the copy limit,
+  /// for Standard whether their set is in Standard, and for commons only
+  /// formats their rarity
+  pub fn validate(&self, format: GameFormat) -> Result<(), Vec<DeckViolation>> {
+    let rules = format.rules();
+    let mut violations = Vec::new();
+    // The commander counts toward the deck size of Commander style formats
+    let len = self.len() + self.commander.iter().map(|cc| cc.count).sum::<usize>();
+    if len < rules.min_deck_size {
+      violations.push(DeckViolation::TooFewCards {
+        count: len,
+        min: rules.min_deck_size,
+      });
+    }
+    if let Some(max) = rules.max_deck_size {
+      if len > max {
+        violations.push(DeckViolation::TooManyCards { count: len, max });
+      }
+    }
+    let sideboard_len = self.sideboard_len();
+    if sideboard_len > rules.max_sideboard_size {
+      violations.push(DeckViolation::SideboardTooLarge {
+        count: sideboard_len,
+        max: rules.max_sideboard_size,
+      });
+    }
+    // Count copies across the main deck, sideboard and commander, in main deck order.
+    // The companion is listed in the sideboard as well, and maybeboard cards are not in the deck
+    let mut counts: Vec<(&Card, usize)> = Vec::new();
+    for cc in self
+      .cards
+      .iter()
+      .chain(self.sideboard.iter())
+      .chain(self.commander.iter())
+    {
+      match counts
+        .iter_mut()
+        .find(|(card, _)| card.name == cc.card.name)
+      {
+        Some((_, count)) => *count += cc.count,
+        None => counts.push((&cc.card, cc.count)),
+      }
+    }
+    for (card, count) in counts {
+      let name = card.name.clone();
+      match card.legality(format) {
+        Some(Legality::Banned) => violations.push(DeckViolation::Banned(name)),
+        Some(Legality::NotLegal) => violations.push(DeckViolation::NotLegal(name)),
+        Some(Legality::Restricted) if count > 1 => {
+          violations.push(DeckViolation::Restricted { name, count })
+        }
+        Some(_) => {}
+        None => {
+          if format == GameFormat::Standard
+            && !card.in_standard()
+            && card.kind != CardKind::BasicLand
+          {
+            violations.push(DeckViolation::NotLegal(name.clone()));
+          }
+          if rules.commons_only && card.rarity != Rarity::Common && card.kind != CardKind::BasicLand
+          {
+            violations.push(DeckViolation::NotCommon {
+              name: name.clone(),
+              rarity: card.rarity,
+            });
+          }
+        }
+      }
+      if card.kind != CardKind::BasicLand && count > rules.max_copies {
+        violations.push(DeckViolation::TooManyCopies {
+          name: card.name.clone(),
+          count,
+          max: rules.max_copies,
+        });
+      }
+    }
+    if violations.is_empty() {
+      Ok(())
+    } else {
+      Err(violations)
+    }
+  }
+
+  pub fn have_need(&self, collection: &Deck) -> (Deck, Deck) {
+    let mut have = DeckBuilder::new();
+    let mut need = DeckBuilder::new();
+    for need_cc in &self.cards {
+      let need_card = &need_cc.card;
+      let need_count = need_cc.count;
+      let need_name = &need_card.name;
+      let have_cc = collection.card_count_from_name(need_name);
+      let have_count = std::cmp::min(have_cc.map(|o| o.count).unwrap_or(0), need_count);
+      let diff_count = need_count - have_count;
+      if diff_count == 0 {
+        have = have.insert_count(need_card.clone(), need_count);
+      } else {
+        have = have.insert_count(need_card.clone(), have_count);
+        need = need.insert_count(need_card.clone(), diff_count);
+      }
+    }
+    (have.build(), need.build())
+  }
+}
+
+// Returns the card from a deck list card name, or the closest card names if there is no such card.
+// In lenient mode, the closest card is accepted if no other card is as close
+pub(crate) fn card_from_list_name(name: &str, lenient: bool) -> Result<&'static Card, Vec<String>> {
+  if let Some(card) = ALL_CARDS.card_from_name_normalized(name) {
+    return Ok(card);
+  }
+  let suggestions = ALL_CARDS.suggest_names(name, 3);
+  if lenient {
+    match suggestions.as_slice() {
+      [(card, _)] => return Ok(card),
+      [(card, best), (_, next), ..] if best < next => return Ok(card),
+      _ => {}
+    }
+  }
+  Err(
+    suggestions
+      .into_iter()
+      .map(|(card, _)| card.name.clone())
+      .collect(),
+  )
+}
+
+// Returns the problem with a deck list line that lists a card again with a different printing
+pub(crate) fn conflicting_printing_message(cc: &DeckCard, kept: &Printing, line: &str) -> String {
+  format!(
+    "{} is already listed as {}, cannot also list {} in deck list line: {}",
+    list_name(&cc.card),
+    kept,
+    cc.printing,
+    line
+  )
+}
+
+// Returns the card name for a deck list. Deck lists name split and adventure cards, which
+// decks hold by their left face, by their full name, e.g. "Fire // Ice"
+pub(crate) fn list_name(card: &Card) -> &str {
+  if !card.is_face {
+    return &card.name;
+  }
+  match ALL_CARDS.card_from_oracle_id(&card.oracle_id) {
+    Some(parent) if !parent.is_face => {
+      // Without a layout, split and adventure cards have a mana cost for each face
+      let split = match parent.layout.as_str() {
+        "" => parent.mana_cost_string.contains(" // "),
+        layout => layout == "split" || layout == "adventure",
+      };
+      if split {
+        &parent.name
+      } else {
+        &card.name
+      }
+    }
+    _ => &card.name,
+  }
+}
+
+// Returns the error for a card name missing from the collection, listing the closest card names
+pub(crate) fn unknown_card_error(
+  line: usize,
+  columns: Range<usize>,
+  name: &str,
+  suggestions: Vec<String>,
+) -> DeckListError {
+  let mut message = format!("Cannot find card named \"{}\" in collection", name);
+  if !suggestions.is_empty() {
+    let names: Vec<_> = suggestions.iter().map(|s| format!("\"{}\"", s)).collect();
+    message.push_str(&format!(". Did you mean {}?", names.join(", ")));
+  }
+  let mut error = DeckListError::new(line, columns, DeckListErrorKind::UnknownCard, message);
+  error.suggestions = suggestions;
+  error
+}
+
+// Returns the set code from a deck list set code or Arena set code, e.g. DOM for DAR,
+// or None if the code is not a valid set code. Codes missing from the set registry, such
+// as promo, Alchemy or newer sets, are kept as they are
+pub(crate) fn set_from_list_code(code: &str) -> Option<SetCode> {
+  let code = code.parse::<SetCode>().ok()?;
+  Some(ALL_SETS.resolve(code))
+}
+
+impl Deref for Deck {
+  type Target = [DeckCard];
+
+  fn deref(&self) -> &Self::Target {
+    &self.cards
+  }
+}
+
+#[macro_export]
+macro_rules! decklist {
+  ($list:expr) => {
+    $crate::deck::Deck::from_list($list).unwrap_or_else(|_| panic!("Bad deck list"))
+  };
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::deck::*;
+
+  #[test]
+  fn good_deckcode_0() {
+    let code = "
+        4 Legion's Landing (XLN) 22
+        4 Adanto Vanguard (XLN) 1
+        4 Skymarcher Aspirant (RIX) 21
+        4 Snubhorn Sentry (RIX) 23
+        4 Clifftop Retreat (DAR) 239
+        4 History of Benalia (DAR) 21
+        2 Ajani, Adversary of Tyrants (M19) 3
+        4 Heroic Reinforcements (M19) 217
+        4 Sacred Foundry (GRN) 254
+        2 Mountain (XLN) 272
+        12 Plains (XLN) 263
+        3 Hunted Witness (GRN) 15
+        4 Conclave Tribunal (GRN) 6
+        4 Venerated Loxodon (GRN) 30
+        1 Doom Whisperer (GRN) 30
+        ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 60);
+  }
+
+  #[test]
+  fn good_deckcode_1() {
+    let code = "
+        4 Legion's Landing
+        4 Adanto Vanguard
+        4 Skymarcher Aspirant
+        4 Snubhorn Sentry
+        4 Clifftop Retreat
+        4 History of Benalia
+        2 Ajani, Adversary of Tyrants
+        4 Heroic Reinforcements
+        4 Sacred Foundry
+        2 Mountain
+        12 Plains
+        3 Hunted Witness
+        4 Conclave Tribunal
+        4 Venerated Loxodon
+        1 Doom Whisperer
+        ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 60);
+  }
+
+  #[test]
+  fn good_deckcode_2() {
+    let code = "
+        # This is a comment
+        4 Legion's Landing
+        4 Adanto Vanguard
+        4 Skymarcher Aspirant
+        4 Snubhorn Sentry
+        4 Clifftop Retreat
+        # This is another comment
+        4 History of Benalia
+        2 Ajani, Adversary of Tyrants
+        4 Heroic Reinforcements
+        4 Sacred Foundry
+        2 Mountain
+        12 Plains
+        3 Hunted Witness
+        4 Conclave Tribunal
+        4 Venerated Loxodon
+        1 Doom Whisperer
+        # This is the last comment
+        ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 60);
+  }
+
+  #[test]
+  fn good_deckcode_3() {
+    let code = "
+        2 Carnage Tyrant (XLN) 179
+        3 Hydroid Krasis (RNA) 183
+        4 Jadelight Ranger (RIX) 136
+        4 Llanowar Elves (DAR) 168
+        4 Merfolk Branchwalker (XLN) 197
+        2 Midnight Reaper (GRN) 77
+        2 Ravenous Chupacabra (RIX) 82
+        1 Seekers' Squire (XLN) 121
+        4 Wildgrowth Walker (XLN) 216
+        3 Vivien Reid (M19) 208
+        4 Forest (XLN) 277
+        1 Island (XLN) 265
+        2 Swamp (XLN) 269
+        4 Breeding Pool (RNA) 246
+        1 Drowned Catacomb (XLN) 253
+        2 Memorial to Folly (DAR) 242
+        4 Overgrown Tomb (GRN) 253
+        2 Watery Grave (GRN) 259
+        4 Woodland Cemetery (DAR) 248
+        2 Cast Down (DAR) 81
+        2 Vraska's Contempt (XLN) 129
+        3 Find // Finality (GRN) 225
+
+        1 Tendershoot Dryad (RIX) 147
+        2 The Eldest Reborn (DAR) 90
+        1 Crushing Canopy (XLN) 183
+        1 Disdainful Stroke (GRN) 37
+        2 Negate (RIX) 44
+        1 Vraska's Contempt (XLN) 129
+        3 Cry of the Carnarium (RNA) 70
+        4 Duress (XLN) 105
+        ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 60);
+  }
+
+  #[test]
+  fn good_deckcode_4() {
+    let code = "
+        2 Carnage Tyrant (XLN) 179
+        3 Hydroid Krasis (RNA) 183
+        4 Jadelight Ranger (RIX) 136
+        4 Llanowar Elves (DAR) 168
+        4 Merfolk Branchwalker (XLN) 197
+        2 Midnight Reaper (GRN) 77
+        2 Ravenous Chupacabra (RIX) 82
+        1 Seekers' Squire (XLN) 121
+        4 Wildgrowth Walker (XLN) 216
+        3 Vivien Reid (M19) 208
+        4 Forest (XLN) 277
+        1 Island (XLN) 265
+        2 Swamp (XLN) 269
+        4 Breeding Pool (RNA) 246
+        1 Drowned Catacomb (XLN) 253
+        2 Memorial to Folly (DAR) 242
+        4 Overgrown Tomb (GRN) 253
+        2 Watery Grave (GRN) 259
+        4 Woodland Cemetery (DAR) 248
+        2 Cast Down (DAR) 81
+        2 Vraska's Contempt (XLN) 129
+        3 Find // Finality (GRN) 225
+
+        1 Tendershoot Dryad (RIX) 147
+        2 The Eldest Reborn (DAR) 90
+        1 Crushing Canopy (XLN) 183
+        1 Disdainful Stroke (GRN) 37
+        2 Negate (RIX) 44
+        1 Vraska's Contempt (XLN) 129
+        3 Cry of the Carnarium (RNA) 70
+        4 Duress (XLN) 105
+        ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 60);
+  }
+
+  #[test]
+  fn good_deckcode_5_set_x() {
+    let code = "
+        2 Carnage Tyrant (XLN) 179
+        3 Hydroid Krasis (RNA) 183 #X=4
+        4 Jadelight Ranger (RIX) 136
+        4 Llanowar Elves (DAR) 168
+        4 Merfolk Branchwalker (XLN) 197
+        2 Midnight Reaper (GRN) 77
+        2 Ravenous Chupacabra (RIX) 82
+        1 Seekers' Squire (XLN) 121
+        4 Wildgrowth Walker (XLN) 216
+        3 Vivien Reid (M19) 208
+        4 Forest (XLN) 277
+        1 Island (XLN) 265
+        2 Swamp (XLN) 269
+        4 Breeding Pool (RNA) 246
+        1 Drowned Catacomb (XLN) 253
+        2 Memorial to Folly (DAR) 242
+        4 Overgrown Tomb (GRN) 253
+        2 Watery Grave (GRN) 259
+        4 Woodland Cemetery (DAR) 248
+        2 Cast Down (DAR) 81
+        2 Vraska's Contempt (XLN) 129
+        3 Find // Finality (GRN) 225
+
+        1 Tendershoot Dryad (RIX) 147
+        2 The Eldest Reborn (DAR) 90
+        1 Crushing Canopy (XLN) 183
+        1 Disdainful Stroke (GRN) 37
+        2 Negate (RIX) 44
+        1 Vraska's Contempt (XLN) 129
+        3 Cry of the Carnarium (RNA) 70
+        4 Duress (XLN) 105
+        ";
+    let deck = decklist!(&code);
+    assert_eq!(deck.len(), 60);
+    let card = deck.card_from_name("Hydroid Krasis").unwrap();
+    assert_eq!(card.mana_cost.generic, 4);
+    assert_eq!(card.mana_cost_string, "{4}{G}{U}");
+    assert_eq!(card.mana_cost.to_string(), "{4}{U}{G}");
+  }
+
+  #[test]
+  fn good_deckcode_6_set_x() {
+    let code = "
+        2 Carnage Tyrant (XLN) 179
+        3 Hydroid Krasis (RNA) 183#x = 6
+        4 Jadelight Ranger (RIX) 136
+        4 Llanowar Elves (DAR) 168
+        4 Merfolk Branchwalker (XLN) 197
+        2 Midnight Reaper (GRN) 77
+        2 Ravenous Chupacabra (RIX) 82
+        1 Seekers' Squire (XLN) 121
+        4 Wildgrowth Walker (XLN) 216
+        3 Vivien Reid (M19) 208
+        4 Forest (XLN) 277
+        1 Island (XLN) 265
+        2 Swamp (XLN) 269
+        4 Breeding Pool (RNA) 246
+        1 Drowned Catacomb (XLN) 253
+        2 Memorial to Folly (DAR) 242
+        4 Overgrown Tomb (GRN) 253
+        2 Watery Grave (GRN) 259
+        4 Woodland Cemetery (DAR) 248
+        2 Cast Down (DAR) 81
+        2 Vraska's Contempt (XLN) 129
+        3 Find // Finality (GRN) 225
+
+        1 Tendershoot Dryad (RIX) 147
+        2 The Eldest Reborn (DAR) 90
+        1 Crushing Canopy (XLN) 183
+        1 Disdainful Stroke (GRN) 37
+        2 Negate (RIX) 44
+        1 Vraska's Contempt (XLN) 129
+        3 Cry of the Carnarium (RNA) 70
+        4 Duress (XLN) 105
+        ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 60);
+    let card = deck.card_from_name("Hydroid Krasis").unwrap();
+    assert_eq!(card.mana_cost.generic, 6);
+  }
+
+  #[test]
+  fn good_deckcode_7_set_x() {
+    let code = "
+        2 Carnage Tyrant (XLN) 179
+        3 Hydroid Krasis (RNA) 183#x = 6
+        4 Jadelight Ranger (RIX) 136
+        4 Llanowar Elves (DAR) 168
+        4 Merfolk Branchwalker (XLN) 197
+        2 Midnight Reaper (GRN) 77#x=3
+        2 Ravenous Chupacabra (RIX) 82
+        1 Seekers' Squire (XLN) 121
+        4 Wildgrowth Walker (XLN) 216
+        3 Vivien Reid (M19) 208
+        4 Forest (XLN) 277
+        1 Island (XLN) 265
+        2 Swamp (XLN) 269
+        4 Breeding Pool (RNA) 246
+        1 Drowned Catacomb (XLN) 253
+        2 Memorial to Folly (DAR) 242
+        4 Overgrown Tomb (GRN) 253
+        2 Watery Grave (GRN) 259
+        4 Woodland Cemetery (DAR) 248
+        2 Cast Down (DAR) 81
+        2 Vraska's Contempt (XLN) 129
+        3 Find // Finality (GRN) 225
+
+        1 Tendershoot Dryad (RIX) 147
+        2 The Eldest Reborn (DAR) 90
+        1 Crushing Canopy (XLN) 183
+        1 Disdainful Stroke (GRN) 37
+        2 Negate (RIX) 44
+        1 Vraska's Contempt (XLN) 129
+        3 Cry of the Carnarium (RNA) 70
+        4 Duress (XLN) 105
+        ";
+    let deck = decklist!(&code);
+    assert_eq!(deck.len(), 60);
+    let card = deck.card_from_name("Hydroid Krasis").unwrap();
+    assert_eq!(card.mana_cost.generic, 6);
+    // can't set x value of midnight reaper since it doesn't have {X} mana cost
+    let card = deck.card_from_name("Midnight Reaper").unwrap();
+    assert_eq!(card.mana_cost.generic, 2);
+  }
+
+  #[test]
+  fn good_deckcode_8_set_x() {
+    let code = "
+        # This is a comment
+        4 Legion's Landing
+        3 Hydroid Krasis#x=5
+        4 Adanto Vanguard
+        4 Skymarcher Aspirant
+        4 Snubhorn Sentry
+        4 Clifftop Retreat
+        # This is another comment
+        4 History of Benalia
+        2 Ajani, Adversary of Tyrants
+        4 Heroic Reinforcements
+        4 Sacred Foundry
+        2 Mountain
+        12 Plains
+        3 Hunted Witness
+        4 Conclave Tribunal
+        4 Venerated Loxodon
+        1 Doom Whisperer
+        # This is the last comment
+        ";
+    let deck = decklist!(&code);
+    assert_eq!(deck.len(), 63);
+    let card = deck.card_from_name("Hydroid Krasis").unwrap();
+    assert_eq!(card.mana_cost.generic, 5);
+  }
+
+  #[test]
+  fn good_deckcode_9_set_x() {
+    let code = "
+        # This is a comment
+        4 Legion's Landing
+        3 Hydroid Krasis # X=-5
+        4 Adanto Vanguard
+        4 Skymarcher Aspirant
+        4 Snubhorn Sentry
+        4 Clifftop Retreat
+        # This is another comment
+        4 History of Benalia
+        2 Ajani, Adversary of Tyrants
+        4 Heroic Reinforcements
+        4 Sacred Foundry
+        2 Mountain
+        12 Plains
+        3 Hunted Witness
+        4 Conclave Tribunal
+        4 Venerated Loxodon
+        1 Doom Whisperer
+        # This is the last comment
+        ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 63);
+    // Ignore negatives
+    let card = deck.card_from_name("Hydroid Krasis").unwrap();
+    assert_eq!(card.mana_cost.generic, 1);
+  }
+
+  #[test]
+  fn good_deckcode_set_x_multiple_x() {
+    let code = "
+        4 Crackle with Power #X=2
+        20 Mountain
+        ";
+    let deck = decklist!(code);
+    let card = deck.card_from_name("Crackle with Power").unwrap();
+    // Each {X} is worth the X value
+    assert_eq!(card.mana_cost.generic, 6);
+    assert_eq!(card.mana_cost.r, 2);
+    assert_eq!(card.turn, 8);
+  }
+
+  #[test]
+  #[should_panic]
+  fn bad_deckcode_0() {
+    // The last card, Doom Whisperer, is misspelled
+    let code = "
+        4 Legion's Landing (XLN) 22
+        4 Adanto Vanguard (XLN) 1
+        4 Skymarcher Aspirant (RIX) 21
+        4 Snubhorn Sentry (RIX) 23
+        4 Clifftop Retreat (DAR) 239
+        4 History of Benalia (DAR) 21
+        2 Ajani, Adversary of Tyrants (M19) 3
+        4 Heroic Reinforcements (M19) 217
+        4 Sacred Foundry (GRN) 254
+        2 Mountain (XLN) 272
+        13 Plains (XLN) 263
+        3 Hunted Witness (GRN) 15
+        4 Conclave Tribunal (GRN) 6
+        4 Venerated Loxodon (GRN) 30
+        1 Doo Whisperer (GRN) 30
+        ";
+    let _deck = decklist!(code);
+  }
+
+  #[test]
+  fn good_deckcode_with_0_0() {
+    let code = "
+        0 Island
+        4 Legion's Landing
+        4 Adanto Vanguard
+        4 Skymarcher Aspirant
+        4 Snubhorn Sentry
+        4 Clifftop Retreat
+        4 History of Benalia
+        2 Ajani, Adversary of Tyrants
+        4 Heroic Reinforcements
+        4 Sacred Foundry
+        2 Mountain
+        12 Plains
+        3 Hunted Witness
+        4 Conclave Tribunal
+        4 Venerated Loxodon
+        1 Doom Whisperer
+        ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 60);
+  }
+
+  #[test]
+  fn deck_aetherhub_45936() {
+    let code = "
+      4 Drowned Catacomb (XLN) 253
+      4 Glacial Fortress (XLN) 255
+      2 Search for Azcanta (XLN) 74
+      2 Vraska's Contempt (XLN) 129
+      1 Golden Demise (RIX) 73
+      3 Moment of Craving (RIX) 79
+      4 Isolated Chapel (DAR) 241
+      3 Teferi, Hero of Dominaria (DAR) 207
+      2 Cast Down (DAR) 81
+      1 Lyra Dawnbringer (DAR) 26
+      2 The Eldest Reborn (DAR) 90
+      1 Cleansing Nova (M19) 9
+      1 Chromium, the Mutable (M19) 214
+      3 Evolving Wilds (RIX) 186
+      3 Thought Erasure (GRN) 206
+      2 Disinformation Campaign (GRN) 167
+      4 Watery Grave (GRN) 259
+      4 Sinister Sabotage (GRN) 54
+      1 Chemister's Insight (GRN) 32
+      3 Ritual of Soot (GRN) 84
+      1 Price of Fame (GRN) 83
+      2 Syncopate (DAR) 67
+      2 Swamp (XLN) 268
+      2 Island (XLN) 264
+      3 Plains (XLN) 263
+
+      1 Ixalan's Binding (XLN) 17
+      1 Golden Demise (RIX) 73
+      1 The Eldest Reborn (DAR) 90
+      2 Fungal Infection (DAR) 94
+      2 Duress (XLN) 105
+      3 Thief of Sanity (GRN) 205
+      2 Unmoored Ego (GRN) 212
+      3 Blood Operative (GRN) 63
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 60);
+  }
+
+  #[test]
+  fn deck_aetherhub_50520() {
+    let code = "
+      4 Chart a Course (XLN) 48
+      3 Dive Down (XLN) 53
+      1 Drowned Catacomb (XLN) 253
+      2 Search for Azcanta (XLN) 74
+      3 Spell Pierce (XLN) 81
+      4 Sulfur Falls (DAR) 247
+      4 Opt (XLN) 65
+      3 Enigma Drake (M19) 217
+      3 Niv-Mizzet, Parun (GRN) 192
+      1 Beacon Bolt (GRN) 154
+      4 Crackling Drake (GRN) 163
+      4 Steam Vents (GRN) 257
+      5 Mountain (XLN) 272
+      4 Discovery // Dispersal (GRN) 223
+      4 Lava Coil (GRN) 108
+      7 Island (XLN) 264
+      4 Tormenting Voice (M19) 164
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 60);
+  }
+
+  #[test]
+  fn deck_aetherhub_50817() {
+    let code = "
+      4 Dragonskull Summit (XLN) 252
+      4 Drowned Catacomb (XLN) 253
+      2 Search for Azcanta (XLN) 74
+      3 Vraska's Contempt (XLN) 129
+      3 Angrath, the Flame-Chained (RIX) 152
+      5 Swamp (XLN) 268
+      1 Island (XLN) 264
+      3 Moment of Craving (RIX) 79
+      4 Sulfur Falls (DAR) 247
+      3 Cast Down (DAR) 81
+      2 The Eldest Reborn (DAR) 90
+      4 Thought Erasure (GRN) 206
+      4 Steam Vents (GRN) 257
+      4 Watery Grave (GRN) 259
+      2 Discovery // Dispersal (GRN) 223
+      4 Sinister Sabotage (GRN) 54
+      2 Chemister's Insight (GRN) 32
+      4 Ritual of Soot (GRN) 84
+      2 Ral, Izzet Viceroy (GRN) 195
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 60);
+  }
+
+  #[test]
+  fn empty_code() {
+    let code = "";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 0);
+  }
+
+  #[test]
+  fn code_contains_companion() {
+    let code = "
+      Companion
+      1 Lurrus of the Dream Den (IKO) 226
+
+      Deck
+      1 Island
+      1 Plains
+      1 Mountain
+      1 Forest
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 4);
+    assert_eq!(deck.companion[0].card.name, "Lurrus of the Dream-Den");
+  }
+
+  #[test]
+  fn code_contains_commander() {
+    let code = "
+      Commander
+      1 Lurrus of the Dream Den (IKO) 226
+
+      Deck
+      1 Island
+      1 Plains
+      1 Mountain
+      1 Forest
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 4);
+    assert_eq!(deck.zone(DeckZone::Commander).len(), 1);
+    assert!(deck.companion.is_empty());
+  }
+
+  #[test]
+  fn code_contains_deck() {
+    let code = "
+      Deck
+      1 Island
+      1 Plains
+      1 Mountain
+      1 Forest
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 4);
+  }
+
+  #[test]
+  fn code_contains_sideboard() {
+    let code = "
+      Deck
+      1 Island
+      1 Plains
+      1 Mountain
+      1 Forest
+
+      Sideboard
+      1 Forest
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 4);
+    assert_eq!(deck.sideboard_len(), 1);
+    assert_eq!(deck.sideboard[0].card.name, "Forest");
+  }
+
+  #[test]
+  fn code_contains_maybeboard() {
+    let code = "
+      Deck
+      1 Island
+      1 Plains
+      1 Mountain
+      1 Forest
+
+      Maybeboard
+      1 Forest
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 4);
+    assert_eq!(deck.sideboard_len(), 0);
+    assert_eq!(deck.maybeboard[0].card.name, "Forest");
+  }
+
+  #[test]
+  fn code_contains_every_zone() {
+    let code = "
+      About
+      Name Lurrus Blink
+
+      Commander
+      1 Lurrus of the Dream Den (IKO) 226
+
+      Companion
+      1 Jegantha, the Wellspring
+
+      Deck
+      30 Plains
+      20 Swamp
+
+      Sideboard
+      1 Jegantha, the Wellspring
+      2 Duress
+
+      Maybeboard
+      1 Negate
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.title.as_deref(), Some("Lurrus Blink"));
+    assert_eq!(deck.len(), 50);
+    assert_eq!(deck.sideboard_len(), 3);
+    assert_eq!(deck.commander[0].card.name, "Lurrus of the Dream-Den");
+    assert_eq!(deck.companion[0].card.name, "Jegantha, the Wellspring");
+    assert_eq!(deck.maybeboard[0].card.name, "Negate");
+    assert_eq!(deck.validate(GameFormat::Other), Ok(()));
+  }
+
+  #[test]
+  fn empty_line_starts_sideboard() {
+    let code = "
+      4 Island
+      4 Plains
+
+      2 Negate
+      1 Duress
+
+      4 Shock
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 8);
+    assert_eq!(deck.sideboard_len(), 3);
+    // The sideboard header starts the sideboard without an empty line
+    let code = "
+      4 Island
+      Sideboard
+      2 Negate
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 4);
+    assert_eq!(deck.sideboard_len(), 2);
+  }
+
+  #[test]
+  fn export_round_trip() {
+    let code = "
+      About
+      Name Simic Ramp
+
+      Companion
+      1 Jegantha, the Wellspring
+
+      Deck
+      3 Hydroid Krasis (RNA) 183 # X=4
+      4 Llanowar Elves (DAR) 168 # T=1
+      4 Growth Spiral # M={G}{U}
+      4 Find // Finality (GRN) 225
+      45 Forest
+
+      Sideboard
+      2 Negate (RIX) 44
+
+      Maybeboard
+      1 Duress
+    ";
+    let deck = decklist!(code);
+    let export = deck.to_export();
+    assert!(export.contains("3 Hydroid Krasis (RNA) 183 # X=4\n"));
+    // Llanowar Elves is from DOM, which Arena calls DAR
+    assert!(export.contains("4 Llanowar Elves (DAR) 168 # T=1\n"));
+    assert!(export.contains("4 Growth Spiral # M={G}{U}\n"));
+    let round_trip = Deck::from_list(&export).unwrap();
+    assert_eq!(round_trip.title, deck.title);
+    let details = |cards: &[DeckCard]| -> Vec<_> {
+      cards
+        .iter()
+        .map(|cc| {
+          (
+            cc.card.name.clone(),
+            cc.count,
+            cc.printing.clone(),
+            cc.modifiers.clone(),
+            cc.card.mana_cost,
+            cc.card.turn,
+            cc.card.kind,
+          )
+        })
+        .collect()
+    };
+    for zone in &[
+      DeckZone::Main,
+      DeckZone::Sideboard,
+      DeckZone::Companion,
+      DeckZone::Maybeboard,
+    ] {
+      assert_eq!(details(round_trip.zone(*zone)), details(deck.zone(*zone)));
+    }
+    let arena = deck.to_arena_export();
+    assert!(arena.contains("3 Hydroid Krasis (RNA) 183\n"));
+    assert!(arena.contains("4 Find // Finality (GRN) 225\n"));
+    assert!(!arena.contains('#'));
+    assert!(!arena.contains("Maybeboard"));
+    assert_eq!(Deck::from_list(&arena).unwrap().len(), 60);
+  }
+
+  fn with_legality(mut deck: Deck, name: &str, format: GameFormat, legality: Legality) -> Deck {
+    for cc in deck.cards.iter_mut().chain(deck.sideboard.iter_mut()) {
+      if cc.card.name == name {
+        cc.card.legalities.insert(format, legality);
+      }
+    }
+    deck
+  }
+
+  // Moves the cards to a Standard set, since no set of the card database is in Standard
+  fn in_standard_set(mut deck: Deck, names: &[&str]) -> Deck {
+    for cc in deck.cards.iter_mut().chain(deck.sideboard.iter_mut()) {
+      if names.contains(&cc.card.name.as_str()) {
+        cc.card.set = "dmu".parse().unwrap();
+      }
+    }
+    deck
+  }
+
+  #[test]
+  fn validate_standard() {
+    // The card database has no legality data, so Standard falls back to the card set
+    let code = "
+      4 Shock
+      52 Mountain
+      4 Lightning Bolt
+
+      4 Negate
+    ";
+    let deck = decklist!(code);
+    assert_eq!(
+      deck.validate(GameFormat::Standard),
+      Err(vec![
+        DeckViolation::NotLegal("Lightning Bolt".to_string()),
+        DeckViolation::NotLegal("Shock".to_string()),
+        DeckViolation::NotLegal("Negate".to_string()),
+      ])
+    );
+    // Basic lands are always legal
+    let deck = in_standard_set(deck, &["Shock", "Negate"]);
+    assert_eq!(
+      deck.validate(GameFormat::Standard),
+      Err(vec![DeckViolation::NotLegal("Lightning Bolt".to_string())])
+    );
+    let code = "
+      4 Shock
+      40 Mountain
+
+      1 Shock
+      4 Negate
+      11 Island
+    ";
+    let deck = in_standard_set(decklist!(code), &["Shock", "Negate"]);
+    assert_eq!(
+      deck.validate(GameFormat::Standard),
+      Err(vec![
+        DeckViolation::TooFewCards { count: 44, min: 60 },
+        DeckViolation::SideboardTooLarge { count: 16, max: 15 },
+        DeckViolation::TooManyCopies {
+          name: "Shock".to_string(),
+          count: 5,
+          max: 4
+        },
+      ])
+    );
+    let code = "
+      4 Shock
+      56 Mountain
+
+      4 Negate
+    ";
+    let deck = in_standard_set(decklist!(code), &["Shock", "Negate"]);
+    assert_eq!(deck.validate(GameFormat::Standard), Ok(()));
+  }
+
+  #[test]
+  fn validate_legalities() {
+    let code = "
+      2 Shock
+      2 Negate
+      56 Mountain
+    ";
+    let deck = decklist!(code);
+    let deck = with_legality(deck, "Shock", GameFormat::Vintage, Legality::Restricted);
+    let deck = with_legality(deck, "Negate", GameFormat::Vintage, Legality::Banned);
+    let deck = with_legality(deck, "Mountain", GameFormat::Vintage, Legality::Legal);
+    assert_eq!(
+      deck.validate(GameFormat::Vintage),
+      Err(vec![
+        DeckViolation::Banned("Negate".to_string()),
+        DeckViolation::Restricted {
+          name: "Shock".to_string(),
+          count: 2
+        },
+      ])
+    );
+    let deck = with_legality(deck, "Shock", GameFormat::Modern, Legality::NotLegal);
+    assert_eq!(
+      deck.validate(GameFormat::Modern),
+      Err(vec![DeckViolation::NotLegal("Shock".to_string())])
+    );
+  }
+
+  #[test]
+  fn validate_singleton() {
+    let code = "
+      2 Shock
+      1 Negate
+      96 Mountain
+
+      1 Duress
+    ";
+    let deck = decklist!(code);
+    assert_eq!(
+      deck.validate(GameFormat::Commander),
+      Err(vec![
+        DeckViolation::TooFewCards {
+          count: 99,
+          min: 100
+        },
+        DeckViolation::SideboardTooLarge { count: 1, max: 0 },
+        DeckViolation::TooManyCopies {
+          name: "Shock".to_string(),
+          count: 2,
+          max: 1
+        },
+      ])
+    );
+    assert_eq!(
+      deck.validate(GameFormat::Oathbreaker),
+      Err(vec![
+        DeckViolation::TooManyCards { count: 99, max: 60 },
+        DeckViolation::SideboardTooLarge { count: 1, max: 0 },
+        DeckViolation::TooManyCopies {
+          name: "Shock".to_string(),
+          count: 2,
+          max: 1
+        },
+      ])
+    );
+  }
+
+  #[test]
+  fn validate_pauper_rarity() {
+    let code = "
+      4 Shock
+      4 Vraska's Contempt
+      52 Swamp
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.card_from_name("Shock").unwrap().rarity, Rarity::Common);
+    assert_eq!(
+      deck.validate(GameFormat::Pauper),
+      Err(vec![DeckViolation::NotCommon {
+        name: "Vraska's Contempt".to_string(),
+        rarity: Rarity::Rare
+      }])
+    );
+    // Legality data takes precedence over the rarity of the printing
+    let deck = with_legality(
+      deck,
+      "Vraska's Contempt",
+      GameFormat::Pauper,
+      Legality::Legal,
+    );
+    assert_eq!(deck.validate(GameFormat::Pauper), Ok(()));
+  }
+
+  #[test]
+  fn fuzzy_card_names() {
+    let code = "
+      4 thrill of possibility
+      4 Lim-Dul’s Vault
+      4 A-Shock
+      48 Mountain
+    ";
+    let deck = decklist!(code);
+    assert_eq!(deck.len(), 60);
+    assert!(deck.card_from_name("Lim-Dûl's Vault").is_some());
+    assert!(deck.card_from_name("Shock").is_some());
+    let code = "
+      4 Thril of Posibility
+      56 Mountain
+    ";
+    let err = Deck::from_list(code).unwrap_err().to_string();
+    assert!(err.starts_with("Cannot find card named \"Thril of Posibility\" in collection. Did you mean \"Thrill of Possibility\""));
+    let deck = Deck::from_list_lenient(code).unwrap();
+    assert_eq!(
+      deck
+        .card_count_from_name("Thrill of Possibility")
+        .unwrap()
+        .count,
+      4
+    );
+    // No card name is close enough to suggest or accept
+    let code = "4 Zzzzqqqq Xxxxx";
+    assert_eq!(
+      Deck::from_list_lenient(code).unwrap_err().to_string(),
+      "Cannot find card named \"Zzzzqqqq Xxxxx\" in collection"
+    );
+  }
+
+  #[test]
+  fn every_deck_list_problem() {
+    let code = "
+      4 Shok
+      x Island
+      99999999999999999999999 Mountain
+      4 Shock (TOOLONG) 1
+      4 Hydroid Krasis # X=999
+      4 Opt # M={Q}
+      20 Island
+    ";
+    let errors = Deck::from_list(code).unwrap_err().0;
+    let kinds: Vec<_> = errors.iter().map(|e| (e.line, e.kind)).collect();
+    assert_eq!(
+      kinds,
+      vec![
+        (2, DeckListErrorKind::UnknownCard),
+        (3, DeckListErrorKind::BadLine),
+        (4, DeckListErrorKind::BadCount),
+        (5, DeckListErrorKind::BadSet),
+        (6, DeckListErrorKind::BadModifier),
+        (7, DeckListErrorKind::BadModifier),
+      ]
+    );
+    assert_eq!(errors[0].columns, 8..12);
+    assert_eq!(errors[0].suggestions[0], "Shock");
+    assert_eq!(errors[2].columns, 6..29);
+    assert_eq!(errors[3].columns, 15..22);
+    assert_eq!(errors[4].columns, 27..30);
+  }
+
+  #[test]
+  fn unregistered_sets_are_kept() {
+    // Promo, Alchemy, Secret Lair and other sets missing from the set registry
+    let code = "
+      4 Shock (SLD) 1
+      4 Opt (YMKM) 12
+      4 Negate (PLST) 44
+      48 Island (DAR) 250
+    ";
+    let deck = Deck::from_list(code).unwrap();
+    assert_eq!(printing_set(&deck, "Shock"), "SLD");
+    assert_eq!(printing_set(&deck, "Opt"), "YMKM");
+    assert_eq!(printing_set(&deck, "Negate"), "PLST");
+    // Registered Arena set codes still resolve
+    assert_eq!(printing_set(&deck, "Island"), "DOM");
+    let csv = "Count,Name,Set\n4,Shock,J21\n56,Island,ANA\n";
+    let deck = crate::deck::formats::import(csv).unwrap();
+    assert_eq!(printing_set(&deck, "Shock"), "J21");
+    assert_eq!(printing_set(&deck, "Island"), "ANA");
+  }
+
+  fn printing_set(deck: &Deck, name: &str) -> String {
+    deck
+      .card_count_from_name(name)
+      .unwrap()
+      .printing
+      .set
+      .to_string()
+  }
+
+  #[test]
+  fn deck_cards_keep_printings() {
+    let deck = Deck::from_list("4 Opt (XLN) 65\n4 Negate").unwrap();
+    let opt = deck.card_count_from_name("Opt").unwrap();
+    assert_eq!(opt.printing.set.to_string(), "XLN");
+    assert_eq!(opt.printing.collector_number.as_deref(), Some("65"));
+    // Cards keep the card database printing
+    assert_eq!(opt.card.set, ALL_CARDS.card_from_name("Opt").unwrap().set);
+    let negate = deck.card_count_from_name("Negate").unwrap();
+    assert_eq!(negate.printing, Printing::default());
+  }
+
+  #[test]
+  fn conflicting_printings() {
+    let code = "
+      2 Shock (M19) 156
+      2 Shock (M20) 160
+      2 Opt (XLN) 65
+      2 Opt (XLN) 65
+      2 Negate
+      2 Negate (M20) 69
+    ";
+    let errors = Deck::from_list(code).unwrap_err().0;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 3);
+    assert_eq!(errors[0].kind, DeckListErrorKind::ConflictingPrinting);
+    assert_eq!(errors[0].columns, 15..18);
+    assert!(errors[0]
+      .message
+      .starts_with("Shock is already listed as (M19) 156, cannot also list (M20) 160"));
+    // The same printing, or a printing after a line without a set, is the same card
+    let deck = Deck::from_list(&code.replace("(M20) 160", "(M19) 156")).unwrap();
+    let shock = deck.card_count_from_name("Shock").unwrap();
+    assert_eq!(
+      (shock.count, shock.printing.to_string()),
+      (4, "(M19) 156".to_string())
+    );
+    let opt = deck.card_count_from_name("Opt").unwrap();
+    assert_eq!(
+      (opt.count, opt.printing.to_string()),
+      (4, "(XLN) 65".to_string())
+    );
+    let negate = deck.card_count_from_name("Negate").unwrap();
+    assert_eq!(
+      (negate.count, negate.printing.to_string()),
+      (4, "(M20) 69".to_string())
+    );
+    // Every deck list format
+    let dck = "[Main]\n2 Shock|M19\n2 Shock|M20\n";
+    let errors = crate::deck::formats::import(dck).unwrap_err().0;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, 3);
+    assert_eq!(errors[0].kind, DeckListErrorKind::ConflictingPrinting);
+  }
+
+  #[test]
+  fn printings_match_the_card_database_printing() {
+    let xln: SetCode = "XLN".parse().unwrap();
+    let mut card = card!("Opt").clone();
+    card.set = xln;
+    card.collector_number = "65".to_string();
+    card.image_uri = "https://example.com/opt.jpg".to_string();
+    card.arena_id = 42;
+    let printing = Printing::new(&card, xln, Some("65".to_string()));
+    assert_eq!(printing, Printing::of_card(&card));
+    assert_eq!(
+      printing.image_uri.as_deref(),
+      Some("https://example.com/opt.jpg")
+    );
+    assert_eq!(printing.arena_id, Some(42));
+    // A deck list printing without a collector number takes the card database number
+    let printing = Printing::new(&card, xln, None);
+    assert_eq!(printing.collector_number.as_deref(), Some("65"));
+    assert_eq!(printing.arena_id, Some(42));
+    // A different set or collector number is a different printing
+    let different = [
+      Printing::new(&card, "DOM".parse().unwrap(), Some("65".to_string())),
+      Printing::new(&card, xln, Some("66".to_string())),
+    ];
+    for printing in &different {
+      assert_eq!(printing.image_uri, None);
+      assert_eq!(printing.arena_id, None);
+    }
+    assert_eq!(different[1].collector_number.as_deref(), Some("66"));
+    // An unknown card database collector number does not match the deck list number
+    card.collector_number.clear();
+    let printing = Printing::new(&card, xln, Some("65".to_string()));
+    assert_eq!(printing.set, xln);
+    assert_eq!(printing.image_uri, None);
+    assert_eq!(printing.arena_id, None);
+    // Deck cards without the printing image show the card image
+    let cc = DeckCard {
+      card: card.clone(),
+      count: 4,
+      printing,
+      modifiers: CardModifiers::default(),
+    };
+    assert_eq!(cc.image_uri(), "https://example.com/opt.jpg");
+  }
+}
@@ -1,14 +1,20 @@
 //! # Simulation hands and auto tap algorithm
 use crate::bipartite::maximum_bipartite_matching;
-use crate::card::{Card, CardKind, ManaCost};
+use crate::card::{Card, CardKind, ManaCost, Ramp};
 use crate::mulligan::Mulligan;
 use rand::prelude::*;
 
+/// The life paid for a [shock land](https://mtg.gamepedia.com/Shock_lands) to enter untapped
+pub const SHOCK_LAND_LIFE: u8 = 2;
+
 /// Hand represents the opening hand after the mulligan process, along with any cards drawn
 /// Note that the card draw is in order and represents the cards drawn during the draw step
 #[derive(Debug)]
 pub struct Hand {
   cards: Vec<SimCard>,
+  // The land cards remaining in the library after the opening hand and draws
+  library: Vec<SimCard>,
+  has_mana_producers: bool,
   pub starting_hand_size: usize,
   pub opening_hand_size: usize,
   pub mulligan_count: usize,
@@ -21,6 +27,16 @@ pub struct SimCard {
   pub hash: u64,
   pub kind: CardKind,
   pub mana_cost: ManaCost,
+  /// The turn to play the card
+  pub turn: u8,
+  /// The mana a nonland card produces once it is on the battlefield
+  pub produced_mana: Option<ManaCost>,
+  /// The amount of `produced_mana` the card adds each time it taps for mana
+  pub produced_mana_amount: u8,
+  /// The basic land types of a land card
+  pub land_types: ManaCost,
+  /// The lands a ramp spell or fetch land searches the library for
+  pub ramp: Option<Ramp>,
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
@@ -29,6 +45,18 @@ pub enum PlayOrder {
   Second,
 }
 
+/// LandEntry represents how lands enter the battlefield during the auto tap process
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LandEntry {
+  /// Lands follow their printed rules: tap lands enter tapped, check lands need a
+  /// basic land type, fast lands need two or fewer other lands, slow lands need two
+  /// or more other lands, and shock lands cost 2 life to enter untapped
+  Printed,
+  /// Every land enters untapped, as in the hypergeometric model
+  #[default]
+  Untapped,
+}
+
 /// AutoTapResult represents the result of the process that attempts
 /// to tap land cards for mana in order to pay some mana cost
 #[derive(Debug, Copy, Clone, Default)]
@@ -41,6 +69,8 @@ pub struct AutoTapResult {
   pub in_opening_hand: bool,
   /// True if the goal card is in the opening hand
   pub in_draw_hand: bool,
+  /// The life paid for shock lands to enter untapped
+  pub life_paid: u8,
 }
 
 impl AutoTapResult {
@@ -55,26 +85,59 @@ impl SimCard {
       kind: CardKind::Unknown,
       hash: 0,
       mana_cost: ManaCost::new(),
+      turn: 0,
+      produced_mana: None,
+      produced_mana_amount: 0,
+      land_types: ManaCost::new(),
+      ramp: None,
+    }
+  }
+}
+
+impl From<&Card> for SimCard {
+  fn from(card: &Card) -> Self {
+    Self {
+      hash: card.hash,
+      kind: card.kind,
+      mana_cost: card.mana_cost,
+      turn: card.turn,
+      produced_mana: card.produced_mana,
+      produced_mana_amount: card.produced_mana_amount,
+      land_types: card.land_types,
+      ramp: card.ramp,
     }
   }
 }
 
 // Scratch space for the bipartite matching algorithm
 // Used to reduce allocations at runtime
-pub struct Scratch<'a> {
-  lands: Vec<&'a SimCard>,
+pub struct Scratch {
+  sources: Vec<ManaCost>,
+  // The mana produced by nonland cards cast during the simulation,
+  // along with the turn they come online
+  online: Vec<(usize, ManaCost)>,
+  cast: Vec<bool>,
+  // The cards in hand and library that ramp spells have fetched
+  fetched: Vec<bool>,
+  // The lands in hand by the goal turn: whether they are played before the goal
+  // turn, along with their kind, mana and basic land types
+  lands: Vec<(bool, CardKind, ManaCost, ManaCost)>,
   edges: Vec<u8>,
   seen: Vec<bool>,
   matches: Vec<i32>,
 }
 
-impl<'a> Scratch<'a> {
+impl Scratch {
   /// Returns a new Scratch object based on the number of land cards in a deck
   /// and the maximum pip count of any one card. It's OK if you guess wrong for
   /// these numbers, there will simply be one additional allocation to make up
   /// the difference.
   pub fn new(max_land_count: usize, max_pip_count: usize) -> Self {
     Self {
+      sources: Vec::with_capacity(max_land_count),
+      online: Vec::new(),
+      cast: Vec::new(),
+      fetched: Vec::new(),
       lands: Vec::with_capacity(max_land_count),
       edges: vec![0; max_land_count * max_pip_count],
       seen: vec![false; max_land_count],
@@ -88,30 +151,40 @@ impl Hand {
   pub fn from_opening_and_draws(opening: &[&Card], draws: &[&Card]) -> Self {
     let mut cards: Vec<SimCard> = Vec::with_capacity(opening.len() + draws.len());
     for card in opening {
-      cards.push(SimCard {
-        hash: card.hash,
-        kind: card.kind,
-        mana_cost: card.mana_cost,
-      });
+      cards.push(SimCard::from(*card));
     }
     for card in draws {
-      cards.push(SimCard {
-        hash: card.hash,
-        kind: card.kind,
-        mana_cost: card.mana_cost,
-      });
+      cards.push(SimCard::from(*card));
     }
     // TODO: hard coded starting hand size is bad and potentially incorrect
     // since the mulligan process defines the starting hand size
     let starting_hand_size = 7;
     let opening_hand_size = opening.len();
+    let has_mana_producers = cards
+      .iter()
+      .any(|card| card.produced_mana.is_some() || card.ramp.is_some());
     Self {
       cards,
+      library: Vec::new(),
+      has_mana_producers,
       starting_hand_size,
       opening_hand_size,
       mulligan_count: starting_hand_size - opening_hand_size,
     }
   }
+  /// Returns the hand along with the cards remaining in the library after the
+  /// opening hand and draws, in library order. Only the land cards are kept,
+  /// and only if the hand contains a ramp spell that can search for them
+  pub fn with_library<'a>(mut self, library: impl Iterator<Item = &'a Card>) -> Self {
+    if self.cards.iter().any(|card| card.ramp.is_some()) {
+      self.library = library
+        .filter(|card| card.is_land())
+        .map(SimCard::from)
+        .collect();
+    }
+    self
+  }
+
   /// Returns a new random hand from `deck` using a mulligan strategy
   pub fn from_mulligan<T: Mulligan>(
     mulligan: &T,
@@ -134,14 +207,24 @@ impl Hand {
     goal: &Card,
     turn: usize,
     player_order: PlayOrder,
+  ) -> AutoTapResult {
+    self.auto_tap_by_turn_with_land_entry(goal, turn, player_order, LandEntry::default())
+  }
+
+  /// Returns the result of attempting to tap the `goal` card
+  /// with the land cards in hand (`self`) by the `turn` given the `player_order`,
+  /// where lands enter the battlefield according to `land_entry`.
+  /// See `auto_tap_by_turn`
+  pub fn auto_tap_by_turn_with_land_entry(
+    &self,
+    goal: &Card,
+    turn: usize,
+    player_order: PlayOrder,
+    land_entry: LandEntry,
   ) -> AutoTapResult {
     let mut scratch = Scratch::new(30, 8);
-    let goal = SimCard {
-      kind: goal.kind,
-      hash: goal.hash,
-      mana_cost: goal.mana_cost,
-    };
-    self.auto_tap_with_scratch(&goal, turn, player_order, &mut scratch)
+    let goal = SimCard::from(goal);
+    self.auto_tap_with_scratch(&goal, turn, player_order, land_entry, &mut scratch)
   }
 
   /// Returns the result of attempting to tap the `goal` card
@@ -211,29 +294,36 @@ impl Hand {
   /// The actual auto_tap implementation that exposes
   /// the scratch space data structure for performance purposes.
   /// The implementation constructs a bipartite graph between the
-  /// land cards in hand, and the mana pips of the goal card mana cost,
-  /// and then attempts to find the size of the maximum matching set,
-  /// see http://discrete.openmathbooks.org/dmoi2/sec_matchings.html.
+  /// mana sources (land cards and online mana producing cards), and the mana
+  /// pips of the goal card mana cost, and then attempts to find the size of the
+  /// maximum matching set, see http://discrete.openmathbooks.org/dmoi2/sec_matchings.html.
   /// If the size of the maximum matching set is equal to the number
-  /// of mana pips of the goal card mana cost, then the land cards in hand
+  /// of mana pips of the goal card mana cost, then the mana sources
   /// can successfully tap for the goal card.
   /// Kudos to user https://github.com/msg555 for the suggestion to model the
   /// problem as a bipartite matching problem (https://github.com/mtgoncurve/landlord/issues/16)
-  pub fn auto_tap_with_scratch<'a>(
-    &'a self,
+  pub fn auto_tap_with_scratch(
+    &self,
     goal: &SimCard,
-    turland_count: usize,
+    turn: usize,
     play_order: PlayOrder,
-    scratch: &mut Scratch<'a>,
+    land_entry: LandEntry,
+    scratch: &mut Scratch,
   ) -> AutoTapResult {
-    let draw_count = match play_order {
-      PlayOrder::First => turland_count - 1,
-      PlayOrder::Second => turland_count,
-    };
+    let draw_count = Self::draw_count_by_turn(turn, play_order);
     let opening_hand = self.opening();
     let draws = self.draws(draw_count);
 
-    // Populate scratch lands
+    // Cast any mana producing cards first, since this reuses scratch.sources
+    if self.has_mana_producers {
+      self.cast_mana_producers(goal, turn, play_order, scratch);
+    } else {
+      scratch.online.clear();
+    }
+
+    // Lands in hand before `turn` are played on earlier turns, and are untapped by `turn`.
+    // A land drawn on `turn`, or any land in the opening hand on turn 1, can only be
+    // played on `turn`, and only pays for the goal if it enters untapped
     scratch.lands.clear();
 
     // Iterate through opening_hand, add lands to scratch,
@@ -241,8 +331,10 @@ impl Hand {
     let in_opening_hand = {
       let mut found = false;
       for card in opening_hand {
-        if card.kind.is_land() {
-          scratch.lands.push(card);
+        if card.kind.is_land() && card.ramp.is_none() {
+          scratch
+            .lands
+            .push((turn > 1, card.kind, card.mana_cost, card.land_types));
         }
         if card.hash == goal.hash {
           found = true;
@@ -255,9 +347,12 @@ impl Hand {
     // and return if the goal is found in the drawn cards
     let in_draw_hand = {
       let mut found = false;
-      for card in draws {
-        if card.kind.is_land() {
-          scratch.lands.push(card);
+      for (i, card) in draws.iter().enumerate() {
+        if card.kind.is_land() && card.ramp.is_none() {
+          let played_before = Self::turn_drawn(i, play_order) < turn;
+          scratch
+            .lands
+            .push((played_before, card.kind, card.mana_cost, card.land_types));
         }
         if card.hash == goal.hash {
           found = true;
@@ -266,85 +361,409 @@ impl Hand {
       found
     };
 
-    let pip_count = goal.mana_cost.cmc() as usize; // rows (height)
-    let land_count = scratch.lands.len(); // columns (width)
-
-    // Exit early if there aren't enough lands
-    if land_count < pip_count {
+    // Exit early if there aren't enough mana sources, tapped or untapped
+    let online_count = scratch
+      .online
+      .iter()
+      .filter(|(online_turn, _)| *online_turn <= turn)
+      .count();
+    if scratch.lands.len() + online_count < goal.mana_cost.cmc() as usize {
       return AutoTapResult {
         paid: false,
         cmc: false,
         in_opening_hand,
         in_draw_hand,
+        life_paid: 0,
+      };
+    }
+    let life_paid = Self::pay_with_land_drop(goal, turn, land_entry, scratch);
+    AutoTapResult {
+      paid: life_paid.is_some(),
+      cmc: true,
+      in_opening_hand,
+      in_draw_hand,
+      life_paid: life_paid.unwrap_or(0),
+    }
+  }
+
+  // Returns the life paid for the lands in scratch to pay for the goal on `turn`, or
+  // None if they cannot pay. Every land played before `turn` pays, along with the land
+  // played on `turn` if it enters untapped. If there are more lands than land drops
+  // before `turn`, one of them can be held back and played on `turn` instead.
+  // Shock lands enter untapped for 2 life, which is only paid if needed
+  fn pay_with_land_drop(
+    goal: &SimCard,
+    turn: usize,
+    land_entry: LandEntry,
+    scratch: &mut Scratch,
+  ) -> Option<u8> {
+    if land_entry == LandEntry::Untapped {
+      scratch.sources.clear();
+      for (_, _, mana_cost, _) in &scratch.lands {
+        scratch.sources.push(*mana_cost);
+      }
+      Self::push_online_sources(turn, scratch);
+      return if Self::sources_can_pay_goal(&goal.mana_cost, scratch) {
+        Some(0)
+      } else {
+        None
+      };
+    }
+    let mut early_count = 0;
+    let mut early_types = 0;
+    for (played_before, _, _, land_types) in &scratch.lands {
+      if *played_before {
+        early_count += 1;
+        early_types |= land_types.bits;
+      }
+    }
+    let lands_before = std::cmp::min(early_count, turn - 1);
+    let held_back = early_count >= turn;
+    let mut free_drop = false;
+    let mut shock_drop = false;
+    for (played_before, kind, mana_cost, _) in &scratch.lands {
+      if *played_before && !held_back {
+        continue;
+      }
+      match Self::untapped_entry_life(*kind, mana_cost, lands_before, early_types) {
+        Some(0) => free_drop = true,
+        Some(_) => shock_drop = true,
+        None => {}
+      }
+    }
+    // If no land enters untapped on `turn` for free, a held back land enters tapped
+    let tapped_drop = if held_back && !free_drop {
+      scratch
+        .lands
+        .iter()
+        .enumerate()
+        .filter(|(_, (played_before, ..))| *played_before)
+        .min_by_key(|(_, (_, _, mana_cost, _))| goal.mana_cost.color_contribution(mana_cost))
+        .map(|(i, _)| i)
+    } else {
+      None
+    };
+    scratch.sources.clear();
+    for (i, (played_before, kind, mana_cost, _)) in scratch.lands.iter().enumerate() {
+      let pays = if *played_before {
+        Some(i) != tapped_drop
+      } else {
+        Self::untapped_entry_life(*kind, mana_cost, lands_before, early_types) == Some(0)
+      };
+      if pays {
+        scratch.sources.push(*mana_cost);
+      }
+    }
+    Self::push_online_sources(turn, scratch);
+    if Self::sources_can_pay_goal(&goal.mana_cost, scratch) {
+      return Some(0);
+    }
+    if !shock_drop {
+      return None;
+    }
+    // Pay life for a shock land to enter untapped on `turn`
+    scratch.sources.clear();
+    for (played_before, kind, mana_cost, _) in &scratch.lands {
+      if *played_before
+        || Self::untapped_entry_life(*kind, mana_cost, lands_before, early_types).is_some()
+      {
+        scratch.sources.push(*mana_cost);
+      }
+    }
+    Self::push_online_sources(turn, scratch);
+    if Self::sources_can_pay_goal(&goal.mana_cost, scratch) {
+      Some(SHOCK_LAND_LIFE)
+    } else {
+      None
+    }
+  }
+
+  // Returns the life paid for a land of `kind` to enter the battlefield untapped when
+  // played after `lands_before` other lands with the basic land types `land_types`,
+  // or None if it enters tapped
+  #[inline]
+  fn untapped_entry_life(
+    kind: CardKind,
+    mana_cost: &ManaCost,
+    lands_before: usize,
+    land_types: u8,
+  ) -> Option<u8> {
+    match kind {
+      CardKind::TapLand => None,
+      // Check lands look for the basic land types of their own colors
+      CardKind::CheckLand if mana_cost.bits & land_types == 0 => None,
+      CardKind::FastLand if lands_before > 2 => None,
+      CardKind::SlowLand if lands_before < 2 => None,
+      CardKind::ShockLand => Some(SHOCK_LAND_LIFE),
+      _ => Some(0),
+    }
+  }
+
+  // Adds the mana producing cards that are online by `turn` to scratch.sources
+  #[inline]
+  fn push_online_sources(turn: usize, scratch: &mut Scratch) {
+    for (online_turn, mana) in &scratch.online {
+      if *online_turn <= turn {
+        scratch.sources.push(*mana);
+      }
+    }
+  }
+
+  // Returns true if scratch.sources can pay for `mana_cost`, checking the source count first
+  #[inline]
+  fn sources_can_pay_goal(mana_cost: &ManaCost, scratch: &mut Scratch) -> bool {
+    scratch.sources.len() >= mana_cost.cmc() as usize && Self::sources_can_pay(mana_cost, scratch)
+  }
+
+  // Returns the number of cards drawn after the opening hand by `turn`
+  #[inline]
+  fn draw_count_by_turn(turn: usize, play_order: PlayOrder) -> usize {
+    match play_order {
+      PlayOrder::First => turn - 1,
+      PlayOrder::Second => turn,
+    }
+  }
+
+  // Returns the turn on which the draw at `draw_index` after the opening hand is drawn
+  #[inline]
+  fn turn_drawn(draw_index: usize, play_order: PlayOrder) -> usize {
+    match play_order {
+      PlayOrder::First => draw_index + 2,
+      PlayOrder::Second => draw_index + 1,
+    }
+  }
+
+  // Populates scratch.sources with the mana of the lands in hand after `draw_count` draws,
+  // and the mana producing cards that are online by `turn`. Fetch lands do not
+  // produce mana themselves, see `cast_mana_producers`
+  fn populate_sources(&self, turn: usize, draw_count: usize, scratch: &mut Scratch) {
+    scratch.sources.clear();
+    for card in self.opening_with_draws(draw_count) {
+      if card.kind.is_land() && card.ramp.is_none() {
+        scratch.sources.push(card.mana_cost);
+      }
+    }
+    for (online_turn, mana) in &scratch.online {
+      if *online_turn <= turn {
+        scratch.sources.push(*mana);
+      }
+    }
+  }
+
+  // Casts the mana producing nonland cards in hand, such as mana dorks, mana rocks
+  // and ramp spells, so that they can tap for mana by `turn`. A card is cast on the
+  // earliest turn on or after its own turn where it is in hand and the sources on the
+  // battlefield can pay for it, and the mana spent is not available to the cards cast
+  // after it that turn. Mana producers come online the following turn, and ramp
+  // spells fetch their lands from the library right away. Copies of the goal card
+  // are never cast this way. Fetch lands are played first, one land drop per turn
+  // from the turn they are drawn, cracked right away, and produce no mana if the
+  // library has no land left for them to find
+  fn cast_mana_producers(
+    &self,
+    goal: &SimCard,
+    turn: usize,
+    play_order: PlayOrder,
+    scratch: &mut Scratch,
+  ) {
+    scratch.online.clear();
+    let is_candidate = |card: &SimCard| {
+      (card.produced_mana.is_some() || card.ramp.is_some())
+        && !card.kind.is_land()
+        && card.hash != goal.hash
+    };
+    scratch.cast.clear();
+    scratch.cast.resize(self.cards.len(), false);
+    scratch.fetched.clear();
+    scratch
+      .fetched
+      .resize(self.cards.len() + self.library.len(), false);
+    // Cards drawn after the goal turn are still in the library when ramp spells search it
+    let library_start = std::cmp::min(
+      self.opening_hand_size + Self::draw_count_by_turn(turn, play_order),
+      self.cards.len(),
+    );
+    // Play the fetch lands in hand by `turn`, one per turn, and crack them right away
+    let mut next_land_drop = 1;
+    for (i, card) in self.cards[..library_start].iter().enumerate() {
+      if let (true, Some(ramp)) = (card.kind.is_land(), card.ramp) {
+        let drawn_turn = if i < self.opening_hand_size {
+          1
+        } else {
+          Self::turn_drawn(i - self.opening_hand_size, play_order)
+        };
+        let played_turn = std::cmp::max(drawn_turn, next_land_drop);
+        if played_turn > turn {
+          break;
+        }
+        next_land_drop = played_turn + 1;
+        self.fetch_lands(goal, &ramp, played_turn, library_start, scratch);
+      }
+    }
+    for cast_turn in 1..turn {
+      let draw_count = Self::draw_count_by_turn(cast_turn, play_order);
+      self.populate_sources(cast_turn, draw_count, scratch);
+      let in_hand = self.opening_with_draws(draw_count);
+      for (i, card) in in_hand.iter().enumerate() {
+        if scratch.cast[i] || !is_candidate(card) || card.turn as usize > cast_turn {
+          continue;
+        }
+        if scratch.sources.len() < card.mana_cost.cmc() as usize
+          || !Self::sources_can_pay(&card.mana_cost, scratch)
+        {
+          continue;
+        }
+        scratch.cast[i] = true;
+        // Tap the sources that paid for the card
+        let mut n = 0;
+        let matches = &scratch.matches;
+        scratch.sources.retain(|_| {
+          let tapped = matches[n] >= 0;
+          n += 1;
+          !tapped
+        });
+        // Every mana the card adds is a separate source, at least one
+        if let Some(mana) = card.produced_mana {
+          for _ in 0..std::cmp::max(1, card.produced_mana_amount) {
+            scratch.online.push((cast_turn + 1, mana));
+          }
+        }
+        if let Some(ramp) = card.ramp {
+          self.fetch_lands(goal, &ramp, cast_turn, library_start, scratch);
+        }
+      }
+    }
+  }
+
+  // Searches the library, starting at card index `library_start`, for the lands
+  // a ramp spell cast or fetch land cracked on `cast_turn` finds. Lands that contribute the most colors
+  // to the goal are fetched first. Lands put onto the battlefield untapped can tap
+  // for mana right away, while tapped lands and lands put into hand come online
+  // the following turn
+  fn fetch_lands(
+    &self,
+    goal: &SimCard,
+    ramp: &Ramp,
+    cast_turn: usize,
+    library_start: usize,
+    scratch: &mut Scratch,
+  ) {
+    let card_at = |i: usize| {
+      if i < self.cards.len() {
+        &self.cards[i]
+      } else {
+        &self.library[i - self.cards.len()]
+      }
+    };
+    let land_count = ramp.battlefield_count + ramp.hand_count;
+    for n in 0..land_count {
+      let mut best: Option<(usize, u32)> = None;
+      for i in library_start..(self.cards.len() + self.library.len()) {
+        let card = card_at(i);
+        if scratch.fetched[i] || !card.kind.is_land() || !ramp.can_find(card.kind, &card.land_types)
+        {
+          continue;
+        }
+        let contribution = goal.mana_cost.color_contribution(&card.mana_cost);
+        let is_better = match best {
+          Some((_, best_contribution)) => contribution > best_contribution,
+          None => true,
+        };
+        if is_better {
+          best = Some((i, contribution));
+        }
+      }
+      let i = match best {
+        Some((i, _)) => i,
+        None => return,
       };
+      scratch.fetched[i] = true;
+      let mana = card_at(i).mana_cost;
+      if n < ramp.battlefield_count && !ramp.tapped {
+        scratch.online.push((cast_turn, mana));
+        scratch.sources.push(mana);
+      } else {
+        scratch.online.push((cast_turn + 1, mana));
+      }
     }
+  }
+
+  // Returns true if the mana sources in scratch can pay for `mana_cost`
+  fn sources_can_pay(mana_cost: &ManaCost, scratch: &mut Scratch) -> bool {
+    let pip_count = mana_cost.cmc() as usize; // rows (height)
+    let source_count = scratch.sources.len(); // columns (width)
+    assert!(pip_count <= source_count);
 
     // Resize the scratch space data structures required
     // for the maximum bipartite matching algorithm
-    scratch.edges.resize(pip_count * land_count, 0);
-    scratch.seen.resize(land_count, false);
-    scratch.matches.resize(land_count, -1);
+    scratch.edges.resize(pip_count * source_count, 0);
+    scratch.seen.resize(source_count, false);
+    scratch.matches.resize(source_count, -1);
     // Build the adjaceny matrix representing the bipartite
-    // graph between land cards and the goal card mana cost pips
-    let r_pips = goal.mana_cost.r as usize;
-    let g_pips = goal.mana_cost.g as usize;
-    let b_pips = goal.mana_cost.b as usize;
-    let u_pips = goal.mana_cost.u as usize;
-    let w_pips = goal.mana_cost.w as usize;
-    let c_pips = goal.mana_cost.c as usize;
+    // graph between mana sources and the goal card mana cost pips
+    let r_pips = mana_cost.r as usize;
+    let g_pips = mana_cost.g as usize;
+    let b_pips = mana_cost.b as usize;
+    let u_pips = mana_cost.u as usize;
+    let w_pips = mana_cost.w as usize;
+    let c_pips = mana_cost.c as usize;
+    let generic_pips = mana_cost.generic as usize;
     let r_range = 0..r_pips;
     let g_range = r_range.end..(r_range.end + g_pips);
     let b_range = g_range.end..(g_range.end + b_pips);
     let u_range = b_range.end..(b_range.end + u_pips);
     let w_range = u_range.end..(u_range.end + w_pips);
     let c_range = w_range.end..(w_range.end + c_pips);
+    let generic_range = c_range.end..(c_range.end + generic_pips);
     for m in r_range {
-      for (n, land) in scratch.lands.iter().enumerate() {
-        scratch.edges[land_count * m + n] = land.mana_cost.r;
+      for (n, source) in scratch.sources.iter().enumerate() {
+        scratch.edges[source_count * m + n] = source.r;
       }
     }
     for m in g_range {
-      for (n, land) in scratch.lands.iter().enumerate() {
-        scratch.edges[land_count * m + n] = land.mana_cost.g;
+      for (n, source) in scratch.sources.iter().enumerate() {
+        scratch.edges[source_count * m + n] = source.g;
       }
     }
     for m in b_range {
-      for (n, land) in scratch.lands.iter().enumerate() {
-        scratch.edges[land_count * m + n] = land.mana_cost.b;
+      for (n, source) in scratch.sources.iter().enumerate() {
+        scratch.edges[source_count * m + n] = source.b;
       }
     }
     for m in u_range {
-      for (n, land) in scratch.lands.iter().enumerate() {
-        scratch.edges[land_count * m + n] = land.mana_cost.u;
+      for (n, source) in scratch.sources.iter().enumerate() {
+        scratch.edges[source_count * m + n] = source.u;
       }
     }
     for m in w_range {
-      for (n, land) in scratch.lands.iter().enumerate() {
-        scratch.edges[land_count * m + n] = land.mana_cost.w;
+      for (n, source) in scratch.sources.iter().enumerate() {
+        scratch.edges[source_count * m + n] = source.w;
       }
     }
+    // Colorless-specific {C} pips require a source that produces colorless mana
     for m in c_range {
-      for (n, _) in scratch.lands.iter().enumerate() {
-        scratch.edges[land_count * m + n] = 1;
+      for (n, source) in scratch.sources.iter().enumerate() {
+        scratch.edges[source_count * m + n] = source.c;
+      }
+    }
+    // Any source can pay for a generic pip
+    for m in generic_range {
+      for n in 0..source_count {
+        scratch.edges[source_count * m + n] = 1;
       }
     }
     // Find the size of the maximum bipartite matching for
     // the graph. This corresponds to the number
-    // of pips we can sucessfully pay with lands in hand
+    // of pips we can sucessfully pay with the mana sources
     let pips_paid = maximum_bipartite_matching(
       &scratch.edges,
       pip_count,
-      land_count,
+      source_count,
       &mut scratch.seen,
       &mut scratch.matches,
     );
     assert!(pips_paid <= pip_count);
-    AutoTapResult {
-      paid: pips_paid == pip_count,
-      cmc: true,
-      in_opening_hand,
-      in_draw_hand,
-    }
+    pips_paid == pip_count
   }
 }
 
@@ -352,6 +771,93 @@ impl Hand {
 mod tests {
   use crate::card::*;
   use crate::hand::*;
+  use helpers::*;
+
+  // Card fixtures shared by the tests below
+  mod helpers {
+    use crate::card::*;
+    use crate::hand::*;
+
+    pub fn test_card(hash: u64, kind: CardKind, mana_cost: ManaCost) -> Card {
+      Card {
+        hash,
+        mana_cost,
+        all_mana_costs: vec![mana_cost],
+        kind,
+        turn: mana_cost.cmc(),
+        ..Default::default()
+      }
+    }
+
+    pub fn creature(mana_cost: ManaCost) -> Card {
+      test_card(2, CardKind::Creature, mana_cost)
+    }
+
+    pub fn mana_producer(hash: u64, mana_cost: ManaCost, produced_mana: ManaCost) -> Card {
+      Card {
+        produced_mana: Some(produced_mana),
+        ..test_card(hash, CardKind::Creature, mana_cost)
+      }
+    }
+
+    pub fn ramp_spell(hash: u64, mana_cost: ManaCost, ramp: Ramp) -> Card {
+      Card {
+        ramp: Some(ramp),
+        ..test_card(hash, CardKind::Sorcery, mana_cost)
+      }
+    }
+
+    pub fn fetch_land(hash: u64, mana_cost: ManaCost, ramp: Ramp) -> Card {
+      Card {
+        turn: 1,
+        ramp: Some(ramp),
+        ..test_card(hash, CardKind::OtherLand, mana_cost)
+      }
+    }
+
+    // Rampant Growth
+    pub fn basic_land_ramp() -> Ramp {
+      Ramp {
+        land_types: ManaCost::new(),
+        basic_only: true,
+        battlefield_count: 1,
+        tapped: true,
+        hand_count: 0,
+      }
+    }
+
+    // Arid Mesa
+    pub fn arid_mesa() -> Card {
+      let land_types = ManaCost::from_rgbuwc(1, 0, 0, 0, 1, 0);
+      fetch_land(
+        10,
+        land_types,
+        Ramp {
+          land_types,
+          basic_only: false,
+          battlefield_count: 1,
+          tapped: false,
+          hand_count: 0,
+        },
+      )
+    }
+
+    // Evolving Wilds
+    pub fn evolving_wilds() -> Card {
+      fetch_land(
+        11,
+        ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0),
+        basic_land_ramp(),
+      )
+    }
+
+    // Returns the auto tap result for `goal` by its turn when playing first,
+    // with lands following their printed entry rules
+    pub fn play_printed(hand: &Hand, goal: &Card) -> AutoTapResult {
+      let turn = std::cmp::max(1, goal.turn) as usize;
+      hand.auto_tap_by_turn_with_land_entry(goal, turn, PlayOrder::First, LandEntry::Printed)
+    }
+  }
 
   #[test]
   fn cards_can_pay_0() {
@@ -1119,6 +1625,422 @@ mod tests {
     assert_eq!(result.cmc, true);
   }
 
+  #[test]
+  fn cards_can_pay_colorless_0() {
+    // {C} pips require a land that produces colorless mana
+    let card = card!("Matter Reshaper");
+    let opening = vec![card!("Forest"), card!("Forest"), card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &[]);
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, false);
+  }
+
+  #[test]
+  fn cards_can_pay_colorless_1() {
+    let card = card!("Matter Reshaper");
+    let opening = vec![card!("Forest"), card!("Wastes"), card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &[]);
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, true);
+  }
+
+  #[test]
+  fn mana_dork_pays_from_next_turn() {
+    let card = card!("Jadelight Ranger"); // {1}{G}{G}
+    let dork = mana_producer(
+      1,
+      ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+      ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+    );
+    let opening = vec![card!("Forest"), &dork, card!("Island")];
+    let draws = vec![card!("Opt"), card!("Opt")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, true);
+  }
+
+  #[test]
+  fn mana_dork_drawn_late_does_not_pay() {
+    let card = card!("Jadelight Ranger"); // {1}{G}{G}
+    let dork = mana_producer(
+      1,
+      ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+      ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+    );
+    let opening = vec![card!("Forest"), card!("Island")];
+    let draws = vec![card!("Opt"), &dork];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    let result = hand.play_cmc_auto_tap(&card);
+    assert_eq!(result.cmc, false);
+    assert_eq!(result.paid, false);
+  }
+
+  #[test]
+  fn mana_rock_adds_every_mana() {
+    let card = Card {
+      turn: 2,
+      ..creature(ManaCost::from_rgbuwc_generic(0, 1, 0, 0, 0, 0, 3)) // {3}{G}
+    };
+    let mut rock = mana_producer(
+      1,
+      ManaCost::from_rgbuwc_generic(0, 0, 0, 0, 0, 0, 1),
+      ManaCost::from_rgbuwc(0, 0, 0, 0, 0, 1),
+    );
+    rock.kind = CardKind::Artifact;
+    let opening = vec![card!("Forest"), &rock, card!("Forest")];
+    let draws = vec![card!("Opt")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, false);
+    rock.produced_mana_amount = 2;
+    let opening = vec![card!("Forest"), &rock, card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, true);
+  }
+
+  #[test]
+  fn mana_rock_pays_for_colors() {
+    let card = creature(ManaCost::from_rgbuwc_generic(0, 1, 1, 0, 0, 0, 1)); // {1}{G}{B}
+    let signet = mana_producer(
+      1,
+      ManaCost::from_rgbuwc_generic(0, 0, 0, 0, 0, 0, 2),
+      ManaCost::from_rgbuwc(0, 1, 1, 0, 0, 0),
+    );
+    let opening = vec![card!("Swamp"), &signet, card!("Island")];
+    let draws = vec![card!("Opt"), card!("Opt")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, true);
+    // Without the second land, the rock is never cast
+    let opening = vec![card!("Swamp"), &signet, card!("Opt")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, false);
+  }
+
+  #[test]
+  fn ramp_spell_fetches_land_for_colors() {
+    let card = creature(ManaCost::from_rgbuwc_generic(1, 1, 0, 0, 0, 0, 2)); // {2}{R}{G}
+    let growth = ramp_spell(
+      1,
+      ManaCost::from_rgbuwc_generic(0, 1, 0, 0, 0, 0, 1),
+      basic_land_ramp(),
+    );
+    let opening = vec![card!("Forest"), &growth, card!("Island")];
+    let draws = vec![card!("Opt"), card!("Forest"), card!("Opt")];
+    let library = vec![card!("Plains"), card!("Mountain"), card!("Opt")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, false);
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, true);
+  }
+
+  #[test]
+  fn ramp_spell_respects_land_types() {
+    let card = creature(ManaCost::from_rgbuwc_generic(1, 1, 0, 0, 0, 0, 2)); // {2}{R}{G}
+                                                                             // Farseek
+    let farseek = ramp_spell(
+      1,
+      ManaCost::from_rgbuwc_generic(0, 1, 0, 0, 0, 0, 1),
+      Ramp {
+        land_types: ManaCost::from_rgbuwc(1, 0, 1, 1, 1, 0),
+        basic_only: false,
+        ..basic_land_ramp()
+      },
+    );
+    let opening = vec![card!("Forest"), &farseek, card!("Island")];
+    let draws = vec![card!("Opt"), card!("Forest"), card!("Opt")];
+    let library = vec![card!("Forest"), card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, false);
+    // Finds a nonbasic land with the Mountain type
+    let taiga = Card {
+      hash: 3,
+      kind: CardKind::OtherLand,
+      mana_cost: ManaCost::from_rgbuwc(1, 1, 0, 0, 0, 0),
+      land_types: ManaCost::from_rgbuwc(1, 1, 0, 0, 0, 0),
+      ..Default::default()
+    };
+    let library = vec![card!("Forest"), &taiga];
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, true);
+  }
+
+  #[test]
+  fn ramp_spell_untapped_land_pays_same_turn() {
+    // A {2}{G} creature on turn 2
+    let card = Card {
+      hash: 3,
+      turn: 2,
+      ..creature(ManaCost::from_rgbuwc_generic(0, 1, 0, 0, 0, 0, 2))
+    };
+    let dork = mana_producer(
+      2,
+      ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+      ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+    );
+    // Three Visits, if it cost {G}
+    let visits = ramp_spell(
+      1,
+      ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+      Ramp {
+        land_types: ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+        basic_only: false,
+        tapped: false,
+        ..basic_land_ramp()
+      },
+    );
+    // The untapped Forest casts the dork on turn 1
+    let opening = vec![card!("Forest"), &visits, &dork];
+    let draws = vec![card!("Opt")];
+    let library = vec![card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, true);
+    // A tapped Forest does not
+    let growth = ramp_spell(
+      1,
+      ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+      basic_land_ramp(),
+    );
+    let opening = vec![card!("Forest"), &growth, &dork];
+    let library = vec![card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, false);
+  }
+
+  #[test]
+  fn fetch_land_finds_land_in_library() {
+    let card = creature(ManaCost::from_rgbuwc_generic(1, 0, 0, 0, 1, 0, 1)); // {1}{R}{W}
+    let mesa = arid_mesa();
+    let opening = vec![&mesa, card!("Plains"), card!("Opt")];
+    let draws = vec![card!("Forest"), card!("Opt")];
+    let library = vec![card!("Island"), card!("Mountain")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    assert_eq!(hand.play_cmc_auto_tap(&card).paid, true);
+    // Fails when no valid target remains in the library
+    let library = vec![card!("Island"), card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    let result = hand.play_cmc_auto_tap(&card);
+    assert_eq!(result.cmc, false);
+    assert_eq!(result.paid, false);
+  }
+
+  #[test]
+  fn fetch_land_enters_tapped() {
+    let card = card!("Llanowar Elves"); // {G}
+    let wilds = evolving_wilds();
+    let opening = vec![&wilds, card!("Opt")];
+    let draws = vec![card!("Opt")];
+    let library = vec![card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    assert_eq!(hand.auto_tap_by_turn(card, 1, PlayOrder::First).paid, false);
+    assert_eq!(hand.auto_tap_by_turn(card, 2, PlayOrder::First).paid, true);
+  }
+
+  #[test]
+  fn mulligan_hands_and_libraries_hold_the_deck_once() {
+    use crate::mulligan::{London, Never, Vancouver};
+    // Distinct fetch lands, so that every hand keeps its library
+    let cards: Vec<_> = (0..60)
+      .map(|hash| fetch_land(hash, ManaCost::new(), basic_land_ramp()))
+      .collect();
+    let deck: Vec<_> = cards.iter().collect();
+    // Hands of seven lands are mulliganed down to five
+    let mut london = London::never();
+    london.mulligan_on_lands = vec![7, 6].into_iter().collect();
+    london.mulligan_down_to = 5;
+    let mut vancouver = Vancouver::never();
+    vancouver.mulligan_on_lands = london.mulligan_on_lands.clone();
+    vancouver.mulligan_down_to = 5;
+    let mut rng = StdRng::seed_from_u64(0);
+    let deck_hashes: Vec<u64> = (0..60).collect();
+    for draws in &[0, 10] {
+      let hands = vec![
+        Hand::from_mulligan(&Never::new(), &mut rng, &deck, *draws),
+        Hand::from_mulligan(&london, &mut rng, &deck, *draws),
+        Hand::from_mulligan(&vancouver, &mut rng, &deck, *draws),
+      ];
+      for hand in hands {
+        let mut hashes: Vec<_> = hand
+          .cards
+          .iter()
+          .chain(hand.library.iter())
+          .map(|card| card.hash)
+          .collect();
+        hashes.sort_unstable();
+        assert_eq!(hashes, deck_hashes);
+      }
+    }
+  }
+
+  #[test]
+  fn fetch_lands_take_one_land_drop_per_turn() {
+    // A {R}{W} creature
+    let card = Card {
+      turn: 1,
+      ..creature(ManaCost::from_rgbuwc(1, 0, 0, 0, 1, 0))
+    };
+    let mesa = arid_mesa();
+    let opening = vec![&mesa, &mesa, card!("Opt")];
+    let draws = vec![card!("Opt"), card!("Opt")];
+    let library = vec![card!("Mountain"), card!("Plains")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    assert_eq!(
+      hand.auto_tap_by_turn(&card, 1, PlayOrder::First).paid,
+      false
+    );
+    assert_eq!(hand.auto_tap_by_turn(&card, 2, PlayOrder::First).paid, true);
+    // The second Evolving Wilds is played on turn 2, and its land enters tapped
+    let card = creature(ManaCost::from_rgbuwc_generic(0, 1, 0, 0, 0, 0, 1)); // {1}{G}
+    let wilds = evolving_wilds();
+    let opening = vec![&wilds, &wilds, card!("Opt")];
+    let library = vec![card!("Forest"), card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    assert_eq!(
+      hand.auto_tap_by_turn(&card, 2, PlayOrder::First).paid,
+      false
+    );
+    assert_eq!(hand.auto_tap_by_turn(&card, 3, PlayOrder::First).paid, true);
+  }
+
+  #[test]
+  fn fetch_lands_thin_the_library() {
+    let card = card!("Jadelight Ranger"); // {1}{G}{G}
+    let wilds = evolving_wilds();
+    let opening = vec![&wilds, &wilds, card!("Forest")];
+    let draws = vec![card!("Opt"), card!("Opt")];
+    // Both fetch lands cannot find the same Forest
+    let library = vec![card!("Forest"), card!("Opt")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    assert_eq!(hand.play_cmc_auto_tap(card).paid, false);
+    let library = vec![card!("Forest"), card!("Opt"), card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws).with_library(library.into_iter());
+    assert_eq!(hand.play_cmc_auto_tap(card).paid, true);
+  }
+
+  #[test]
+  fn tap_land_drawn_on_turn_does_not_pay() {
+    let card = creature(ManaCost::from_rgbuwc_generic(0, 1, 0, 0, 0, 0, 1)); // {1}{G}
+    let tap_land = Card {
+      hash: 3,
+      kind: CardKind::TapLand,
+      mana_cost: ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+      ..Default::default()
+    };
+    let opening = vec![card!("Forest"), card!("Opt")];
+    let draws = vec![&tap_land];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    let result = play_printed(&hand, &card);
+    assert_eq!(result.cmc, true);
+    assert_eq!(result.paid, false);
+    // Played on turn 1, the tap land is untapped by turn 2
+    let opening = vec![&tap_land, card!("Opt")];
+    let draws = vec![card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(play_printed(&hand, &card).paid, true);
+    // With two tap lands, one of them enters tapped on turn 2
+    let opening = vec![&tap_land, &tap_land];
+    let draws = vec![card!("Opt")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(play_printed(&hand, &card).paid, false);
+    // The tap land does not pay on turn 1
+    let card = creature(ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0));
+    let opening = vec![&tap_land, card!("Opt")];
+    let hand = Hand::from_opening_and_draws(&opening, &[]);
+    assert_eq!(play_printed(&hand, &card).paid, false);
+    let opening = vec![&tap_land, card!("Forest")];
+    let hand = Hand::from_opening_and_draws(&opening, &[]);
+    assert_eq!(play_printed(&hand, &card).paid, true);
+  }
+
+  #[test]
+  fn check_land_needs_basic_land_type() {
+    let card = creature(ManaCost::from_rgbuwc(0, 0, 0, 1, 1, 0)); // {W}{U}
+    let opening = vec![card!("Island"), card!("Opt")];
+    let draws = vec![card!("Glacial Fortress")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(play_printed(&hand, &card).paid, true);
+    let card = creature(ManaCost::from_rgbuwc_generic(0, 0, 0, 0, 1, 0, 1)); // {1}{W}
+    let opening = vec![card!("Swamp"), card!("Opt")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(play_printed(&hand, &card).paid, false);
+  }
+
+  #[test]
+  fn check_lands_without_basic_land_types_enter_tapped() {
+    let card = card!("Niv-Mizzet, Parun");
+    let lands = vec![
+      card!("Sulfur Falls"),
+      card!("Sulfur Falls"),
+      card!("Sulfur Falls"),
+    ];
+    let draws = vec![
+      card!("Sulfur Falls"),
+      card!("Sulfur Falls"),
+      card!("Sulfur Falls"),
+    ];
+    // Without an Island or a Mountain, the sixth check land enters tapped
+    let hand = Hand::from_opening_and_draws(&lands, &draws);
+    assert_eq!(play_printed(&hand, card).paid, false);
+    let draws = vec![
+      card!("Sulfur Falls"),
+      card!("Sulfur Falls"),
+      card!("Island"),
+    ];
+    let hand = Hand::from_opening_and_draws(&lands, &draws);
+    assert_eq!(play_printed(&hand, card).paid, true);
+    let card = card!("The Immortal Sun");
+    let land = card!("Sulfur Falls");
+    let lands = vec![land, land, land, land, land, land];
+    let hand = Hand::from_opening_and_draws(&[], &lands);
+    let turn = card.turn as usize;
+    let result =
+      hand.auto_tap_by_turn_with_land_entry(card, turn, PlayOrder::Second, LandEntry::Printed);
+    assert_eq!(result.paid, false);
+    assert_eq!(result.cmc, true);
+  }
+
+  #[test]
+  fn fast_land_enters_untapped_early() {
+    let card = creature(ManaCost::from_rgbuwc_generic(1, 0, 0, 0, 0, 0, 1)); // {1}{R}
+    let opening = vec![card!("Island"), card!("Opt")];
+    let draws = vec![card!("Spirebluff Canal")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(play_printed(&hand, &card).paid, true);
+    let card = creature(ManaCost::from_rgbuwc_generic(1, 0, 0, 0, 0, 0, 3)); // {3}{R}
+    let opening = vec![card!("Island"), card!("Island"), card!("Island")];
+    let draws = vec![card!("Opt"), card!("Opt"), card!("Spirebluff Canal")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(play_printed(&hand, &card).paid, false);
+  }
+
+  #[test]
+  fn slow_land_enters_untapped_late() {
+    let card = creature(ManaCost::from_rgbuwc_generic(0, 0, 0, 0, 1, 0, 1)); // {1}{W}
+    let opening = vec![card!("Island"), card!("Opt")];
+    let draws = vec![card!("Deserted Beach")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(play_printed(&hand, &card).paid, false);
+    let card = creature(ManaCost::from_rgbuwc_generic(0, 0, 0, 0, 1, 0, 2)); // {2}{W}
+    let opening = vec![card!("Island"), card!("Island")];
+    let draws = vec![card!("Opt"), card!("Deserted Beach")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    assert_eq!(play_printed(&hand, &card).paid, true);
+  }
+
+  #[test]
+  fn shock_land_pays_life_if_needed() {
+    let card = creature(ManaCost::from_rgbuwc_generic(0, 0, 1, 0, 0, 0, 1)); // {1}{B}
+    let opening = vec![card!("Island"), card!("Opt")];
+    let draws = vec![card!("Watery Grave")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    let result = play_printed(&hand, &card);
+    assert_eq!(result.paid, true);
+    assert_eq!(result.life_paid, SHOCK_LAND_LIFE);
+    // An untapped land can be played on turn 2 instead, and
+    // the shock land enters tapped for free on turn 1
+    let opening = vec![card!("Watery Grave"), card!("Swamp")];
+    let draws = vec![card!("Opt")];
+    let hand = Hand::from_opening_and_draws(&opening, &draws);
+    let result = play_printed(&hand, &card);
+    assert_eq!(result.paid, true);
+    assert_eq!(result.life_paid, 0);
+  }
+
   #[test]
   fn test_issue_16() {
     let mana_cost = ManaCost::from_rgbuwc(1, 1, 1, 2, 1, 0);
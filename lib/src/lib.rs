@@ -21,6 +21,7 @@ extern crate wasm_bindgen;
 pub mod card;
 #[macro_use]
 pub mod deck;
+pub mod arena;
 mod bipartite;
 pub mod collection;
 pub mod data;
@@ -28,6 +29,8 @@ pub mod hand;
 pub mod mulligan;
 pub mod prelude;
 pub mod scryfall;
+pub mod search;
+pub mod set;
 pub mod simulation;
 
 // mtgoncurve.com
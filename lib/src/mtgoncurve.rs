@@ -1,11 +1,12 @@
 //! # https://mtgoncurve.com interface
 //!
 //! Defines the interface between landlord and [https://mtgoncurve.com](https://mtgoncurve.com)
-use crate::card::{Card, CardKind, ManaColorCount, ManaCost};
+use crate::card::{CardKind, ManaColorCount, ManaCost};
 use crate::data::ALL_CARDS;
-use crate::deck::Deck;
-use crate::mulligan::London;
-use crate::simulation::{Observations, Simulation, SimulationConfig};
+use crate::deck::{Deck, DeckCard, DeckListError};
+use crate::hand::LandEntry;
+use crate::mulligan::{London, Mulligan};
+use crate::simulation::{LifePolicy, Observations, Simulation, SimulationConfig};
 
 use std::collections::HashSet;
 use wasm_bindgen::prelude::*;
@@ -13,7 +14,8 @@ use wasm_bindgen::JsValue;
 
 #[derive(Debug, Serialize, Deserialize)]
 enum Error {
-    BadDeckcode(String),
+    /// Every problem with the deck list, with its line, columns, kind and suggestions
+    BadDeckcode(Vec<DeckListError>),
     BadCardNameInRow(usize, String),
     EmptyDeckcode,
 }
@@ -33,6 +35,12 @@ struct Input {
     pub mulligan_on_lands: HashSet<usize>,
     #[doc(hidden)]
     pub acceptable_hand_list: Vec<Vec<String>>,
+    /// How lands enter the battlefield, defaults to every land entering untapped
+    #[serde(default)]
+    pub land_entry: LandEntry,
+    /// When to pay life rather than mana, defaults to paying life only if needed
+    #[serde(default)]
+    pub life_policy: LifePolicy,
 }
 
 /// Output format expected by https://mtgoncurve.com
@@ -60,6 +68,8 @@ struct CardObservation {
     cmc: u8,
     card_count: usize,
     observations: Observations,
+    /// The expected life paid to cast the card, given that we could pay for it
+    expected_life_paid: f64,
 }
 
 #[derive(Default, Debug, Serialize, Deserialize)]
@@ -80,12 +90,14 @@ struct MtgOnCurveCard {
     pub mana_cost: ManaCost,
 }
 
-impl From<&Card> for MtgOnCurveCard {
-    fn from(card: &Card) -> Self {
+impl From<&DeckCard> for MtgOnCurveCard {
+    fn from(cc: &DeckCard) -> Self {
+        let card = &cc.card;
         Self {
             name: card.name.clone(),
             mana_cost_string: card.mana_cost_string.clone(),
-            image_uri: card.image_uri.clone(),
+            // The image of the printing in the deck list
+            image_uri: cc.image_uri().to_string(),
             kind: card.kind,
             hash: card.hash,
             turn: card.turn,
@@ -95,7 +107,8 @@ impl From<&Card> for MtgOnCurveCard {
 }
 
 /// Runs a simulation given input
-/// Assumes that input deserializes into a valid `Input`, and returns a serialized `Output`
+/// Assumes that input deserializes into a valid `Input`, and returns a serialized `Output`,
+/// or a serialized `Error` if the simulation cannot run, e.g. `{"BadDeckcode": [...]}`
 /// # Example
 ///
 ///  ```js
@@ -112,12 +125,10 @@ pub fn mtgoncurve_run(input: &JsValue) -> JsValue {
         Ok(v) => v,
     };
     let result = match run_impl(&input) {
-        Err(e) => {
-            return JsValue::from_str(&format!("Error running simulation for input: {:#?}", e));
-        }
-        Ok(v) => v,
+        Err(e) => serde_json::to_value(&e),
+        Ok(v) => serde_json::to_value(&v),
     };
-    JsValue::from_serde(&result).expect("this can't fail")
+    JsValue::from_serde(&result.expect("this can't fail")).expect("this can't fail")
 }
 
 fn run_impl(input: &Input) -> Result<Output, Error> {
@@ -128,12 +139,16 @@ fn run_impl(input: &Input) -> Result<Output, Error> {
     if deck.is_empty() {
         return Err(Error::EmptyDeckcode);
     }
-    let highest_turn = deck
-        .iter()
-        .fold(0, |max, c| std::cmp::max(max, c.card.turn as usize));
     let mut mulligan = London::never();
     mulligan.mulligan_down_to = input.mulligan_down_to;
     mulligan.mulligan_on_lands = input.mulligan_on_lands.clone();
+    mulligan.acceptable_hand_list = acceptable_hand_list(input)?;
+    run_with_mulligan(input, &deck, &mulligan)
+}
+
+/// Returns the acceptable hand list from the input, with each card represented by its hash
+fn acceptable_hand_list(input: &Input) -> Result<Vec<HashSet<u64>>, Error> {
+    let mut acceptable_hand_list = Vec::new();
     for (i, acceptable_hand) in input.acceptable_hand_list.iter().enumerate() {
         let mut keep_cards = HashSet::new();
         for card_name in acceptable_hand {
@@ -144,15 +159,28 @@ fn run_impl(input: &Input) -> Result<Output, Error> {
             }
         }
         if !keep_cards.is_empty() {
-            mulligan.acceptable_hand_list.push(keep_cards);
+            acceptable_hand_list.push(keep_cards);
         }
     }
+    Ok(acceptable_hand_list)
+}
+
+fn run_with_mulligan<M: Mulligan>(
+    input: &Input,
+    deck: &Deck,
+    mulligan: &M,
+) -> Result<Output, Error> {
+    let highest_turn = deck
+        .iter()
+        .fold(0, |max, c| std::cmp::max(max, c.card.turn as usize));
     let sim = Simulation::from_config(&SimulationConfig {
         run_count: input.runs,
         draw_count: highest_turn,
-        mulligan: &mulligan,
-        deck: &deck,
+        mulligan,
+        deck,
         on_the_play: input.on_the_play,
+        life_policy: input.life_policy,
+        land_entry: input.land_entry,
     });
     let mut outputs = Output::new();
     outputs.accumulated_opening_hand_size = sim.accumulated_opening_hand_size;
@@ -167,9 +195,10 @@ fn run_impl(input: &Input) -> Result<Output, Error> {
             let o = sim.observations_for_card_by_turn(&card, card.turn as usize);
             let cmc = card.mana_cost.cmc();
             CardObservation {
-                card: card.into(),
+                card: c.into(),
                 cmc,
                 card_count: count,
+                expected_life_paid: o.expected_life_paid(),
                 observations: o,
             }
         })
@@ -188,10 +217,11 @@ fn run_impl(input: &Input) -> Result<Output, Error> {
         .map(|c| {
             let cmc = c.card.mana_cost.cmc();
             CardObservation {
-                card: (&c.card).into(),
+                card: c.into(),
                 cmc,
                 card_count: c.count,
                 observations: Observations::new(),
+                expected_life_paid: 0.0,
             }
         })
         .collect();
@@ -231,7 +261,9 @@ fn run_impl(input: &Input) -> Result<Output, Error> {
                 CardKind::CheckLand => outputs.check_land_counts.count(&card.mana_cost),
                 CardKind::TapLand => outputs.tap_land_counts.count(&card.mana_cost),
                 CardKind::ShockLand => outputs.shock_land_counts.count(&card.mana_cost),
-                CardKind::OtherLand => outputs.other_land_counts.count(&card.mana_cost),
+                CardKind::OtherLand | CardKind::FastLand | CardKind::SlowLand => {
+                    outputs.other_land_counts.count(&card.mana_cost)
+                }
                 _ => outputs.non_land_counts.count(&card.mana_cost),
             }
         }
@@ -275,6 +307,7 @@ impl Default for Output {
 #[cfg(test)]
 mod tests {
     use crate::mtgoncurve::*;
+    use crate::mulligan::Vancouver;
 
     // The following tests confirm numbers from the tables in the article
     // https://www.channelfireball.com/articles/how-many-colored-mana-sources-do-you-need-to-consistently-cast-your-spells-a-guilds-of-ravnica-update/
@@ -335,6 +368,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands,
             acceptable_hand_list: Vec::new(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         run_impl(&input).expect("simulation ok");
     }
@@ -387,6 +422,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands,
             acceptable_hand_list: Vec::new(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         run_impl(&input).expect("simulation ok");
     }
@@ -438,6 +475,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands,
             acceptable_hand_list,
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         run_impl(&input).expect("simulation ok");
     }
@@ -458,6 +497,8 @@ mod tests {
             mulligan_down_to: 7,
             mulligan_on_lands: Default::default(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations[0];
@@ -467,6 +508,61 @@ mod tests {
         assert_eq!(obs.observations.play, n);
     }
 
+    #[test]
+    fn life_policy_and_life_paid() {
+        let code = "
+        4 Opt
+        20 Watery Grave
+        ";
+        let n = 100;
+        let mut input = Input {
+            code: code.to_string(),
+            runs: n,
+            on_the_play: true,
+            mulligan_down_to: 7,
+            mulligan_on_lands: Default::default(),
+            acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::Printed,
+            life_policy: LifePolicy::default(),
+        };
+        let results = run_impl(&input).expect("simulation ok");
+        let obs = &results.card_observations[0];
+        assert_eq!(obs.observations.mana, n);
+        assert_eq!(obs.expected_life_paid, 2.0);
+        let json = serde_json::to_value(&results).expect("serializes");
+        assert_eq!(json["card_observations"][0]["expected_life_paid"], 2.0);
+        input.life_policy = LifePolicy::Never;
+        let results = run_impl(&input).expect("simulation ok");
+        let obs = &results.card_observations[0];
+        assert_eq!(obs.observations.mana, 0);
+        assert_eq!(obs.expected_life_paid, 0.0);
+    }
+
+    #[test]
+    fn bad_deckcode_lists_every_problem() {
+        let input = Input {
+            code: "4 Shok\nx Island\n20 Island".to_string(),
+            runs: 1,
+            on_the_play: true,
+            mulligan_down_to: 7,
+            mulligan_on_lands: Default::default(),
+            acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
+        };
+        let err = run_impl(&input).expect_err("bad deck list");
+        let json = serde_json::to_value(&err).expect("serializes");
+        let errors = json["BadDeckcode"].as_array().expect("deck list errors");
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0]["line"], 1);
+        assert_eq!(errors[0]["columns"]["start"], 2);
+        assert_eq!(errors[0]["columns"]["end"], 6);
+        assert_eq!(errors[0]["kind"], "UnknownCard");
+        assert_eq!(errors[0]["suggestions"][0], "Shock");
+        assert_eq!(errors[1]["line"], 2);
+        assert_eq!(errors[1]["kind"], "BadLine");
+    }
+
     // 60 card deck, 24 lands, Sources 8
     // table: https://227rsi2stdr53e3wto2skssd7xe-wpengine.netdna-ssl.com/wp-content/uploads/2018/10/How-many-sources-60-cards-768x209.png
     #[test]
@@ -500,6 +596,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations;
@@ -559,6 +657,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations;
@@ -618,6 +718,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations;
@@ -677,6 +779,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations;
@@ -703,6 +807,165 @@ mod tests {
         karsten_check!(obs, "Cosmic Horror", 0.476);
     }
 
+    // Runs the simulation with the Vancouver mulligan
+    fn vancouver_run_impl(input: &Input) -> Result<Output, Error> {
+        let deck = Deck::from_list(&input.code).map_err(|e| Error::BadDeckcode(e.0))?;
+        let mut mulligan = Vancouver::never();
+        mulligan.mulligan_down_to = input.mulligan_down_to;
+        mulligan.mulligan_on_lands = input.mulligan_on_lands.clone();
+        mulligan.acceptable_hand_list = acceptable_hand_list(input)?;
+        run_with_mulligan(input, &deck, &mulligan)
+    }
+
+    // Returns the deck list used to verify a row in the tables from the article above
+    fn karsten_table_code(sources: usize, lands: usize, deck_size: usize) -> String {
+        format!(
+            "
+            1 Appetite for Brains
+            1 Abnormal Endurance
+            1 Bloodghast
+            1 Ammit Eternal
+            1 Blood Operative
+            1 Doomsday
+            1 Ancient Craving
+            1 Akuta, Born of Ash
+            1 Grave Pact
+            1 Anointed Deacon
+            1 Phyrexian Obliterator
+            1 Aku Djinn
+            1 Hellfire
+            1 Bogstomper
+            1 Acid-Spewer Dragon
+            1 Cosmic Horror
+            {} Swamp
+            {} Detection Tower
+            {} Darksteel Colossus
+        ",
+            sources,
+            lands - sources,
+            deck_size - lands - 16
+        )
+    }
+
+    // The Vancouver tests below expect the results of a 1000000 run Vancouver
+    // simulation of each table row, and run fewer simulations than the tests above
+    fn karsten_table_input(code: String) -> Input {
+        Input {
+            code,
+            runs: 50000,
+            on_the_play: true,
+            mulligan_down_to: 5,
+            mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
+            acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
+        }
+    }
+
+    #[test]
+    fn karsten_vancouver_test_24_60_8() {
+        let input = karsten_table_input(karsten_table_code(8, 24, 60));
+        let results = vancouver_run_impl(&input).expect("simulation ok");
+        let obs = &results.card_observations;
+        karsten_check!(obs, "Appetite for Brains", 0.702);
+        karsten_check!(obs, "Abnormal Endurance", 0.753);
+        karsten_check!(obs, "Bloodghast", 0.320);
+
+        karsten_check!(obs, "Ammit Eternal", 0.818);
+        karsten_check!(obs, "Blood Operative", 0.412);
+        karsten_check!(obs, "Doomsday", 0.110);
+
+        karsten_check!(obs, "Ancient Craving", 0.877);
+        karsten_check!(obs, "Akuta, Born of Ash", 0.521);
+        karsten_check!(obs, "Grave Pact", 0.174);
+
+        karsten_check!(obs, "Anointed Deacon", 0.921);
+        karsten_check!(obs, "Aku Djinn", 0.629);
+        karsten_check!(obs, "Hellfire", 0.261);
+
+        karsten_check!(obs, "Acid-Spewer Dragon", 0.952);
+        karsten_check!(obs, "Bogstomper", 0.728);
+        karsten_check!(obs, "Cosmic Horror", 0.364);
+    }
+
+    #[test]
+    fn karsten_vancouver_test_24_60_14() {
+        let input = karsten_table_input(karsten_table_code(14, 24, 60));
+        let results = vancouver_run_impl(&input).expect("simulation ok");
+        let obs = &results.card_observations;
+        karsten_check!(obs, "Appetite for Brains", 0.915);
+        karsten_check!(obs, "Abnormal Endurance", 0.942);
+        karsten_check!(obs, "Bloodghast", 0.678);
+
+        karsten_check!(obs, "Ammit Eternal", 0.972);
+        karsten_check!(obs, "Blood Operative", 0.794);
+        karsten_check!(obs, "Doomsday", 0.438);
+
+        karsten_check!(obs, "Ancient Craving", 0.989);
+        karsten_check!(obs, "Akuta, Born of Ash", 0.890);
+        karsten_check!(obs, "Grave Pact", 0.604);
+
+        karsten_check!(obs, "Anointed Deacon", 0.996);
+        karsten_check!(obs, "Aku Djinn", 0.950);
+        karsten_check!(obs, "Hellfire", 0.758);
+
+        karsten_check!(obs, "Acid-Spewer Dragon", 0.999);
+        karsten_check!(obs, "Bogstomper", 0.981);
+        karsten_check!(obs, "Cosmic Horror", 0.872);
+    }
+
+    #[test]
+    fn karsten_vancouver_test_17_40_5() {
+        let input = karsten_table_input(karsten_table_code(5, 17, 40));
+        let results = vancouver_run_impl(&input).expect("simulation ok");
+        let obs = &results.card_observations;
+        karsten_check!(obs, "Appetite for Brains", 0.673);
+        karsten_check!(obs, "Abnormal Endurance", 0.727);
+        karsten_check!(obs, "Bloodghast", 0.273);
+
+        karsten_check!(obs, "Ammit Eternal", 0.792);
+        karsten_check!(obs, "Blood Operative", 0.352);
+        karsten_check!(obs, "Doomsday", 0.074);
+
+        karsten_check!(obs, "Ancient Craving", 0.854);
+        karsten_check!(obs, "Akuta, Born of Ash", 0.451);
+        karsten_check!(obs, "Grave Pact", 0.116);
+
+        karsten_check!(obs, "Anointed Deacon", 0.904);
+        karsten_check!(obs, "Aku Djinn", 0.557);
+        karsten_check!(obs, "Hellfire", 0.179);
+
+        karsten_check!(obs, "Acid-Spewer Dragon", 0.942);
+        karsten_check!(obs, "Bogstomper", 0.663);
+        karsten_check!(obs, "Cosmic Horror", 0.262);
+    }
+
+    #[test]
+    fn karsten_vancouver_test_40_99_15() {
+        let input = karsten_table_input(karsten_table_code(15, 40, 99));
+        let results = vancouver_run_impl(&input).expect("simulation ok");
+        let obs = &results.card_observations;
+        karsten_check!(obs, "Appetite for Brains", 0.748);
+        karsten_check!(obs, "Abnormal Endurance", 0.795);
+        karsten_check!(obs, "Bloodghast", 0.390);
+
+        karsten_check!(obs, "Ammit Eternal", 0.854);
+        karsten_check!(obs, "Blood Operative", 0.490);
+        karsten_check!(obs, "Doomsday", 0.165);
+
+        karsten_check!(obs, "Ancient Craving", 0.904);
+        karsten_check!(obs, "Akuta, Born of Ash", 0.600);
+        karsten_check!(obs, "Grave Pact", 0.251);
+
+        karsten_check!(obs, "Anointed Deacon", 0.940);
+        karsten_check!(obs, "Aku Djinn", 0.704);
+        karsten_check!(obs, "Hellfire", 0.356);
+
+        karsten_check!(obs, "Acid-Spewer Dragon", 0.964);
+        karsten_check!(obs, "Bogstomper", 0.791);
+        karsten_check!(obs, "Cosmic Horror", 0.472);
+    }
+
     #[test]
     fn karsten_new_mana_is_great_devarti_verify_2x_hydroid() {
         /*
@@ -745,6 +1008,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations;
@@ -793,6 +1058,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations;
@@ -839,6 +1106,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations;
@@ -878,6 +1147,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations;
@@ -919,6 +1190,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations;
@@ -964,6 +1237,8 @@ mod tests {
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations;
@@ -1004,6 +1279,8 @@ Deck
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         let results = run_impl(&input).expect("simulation ok");
         let obs = &results.card_observations;
@@ -1028,6 +1305,8 @@ Deck
             mulligan_down_to: 5,
             mulligan_on_lands: vec![0, 1, 6, 7].into_iter().collect(),
             acceptable_hand_list: Default::default(),
+            land_entry: LandEntry::default(),
+            life_policy: LifePolicy::default(),
         };
         run_impl(&input).expect("simulation ok");
     }
@@ -172,6 +172,12 @@ impl Mulligan for London {
         return Hand::from_opening_and_draws(
           &shuffled_deck[..opening_hand_size],
           &shuffled_deck[opening_hand_size..],
+        )
+        // partial_shuffle moves the drawn cards to the end of index_range
+        .with_library(
+          index_range[..deck_size - cards_to_draw]
+            .iter()
+            .map(|i| deck[*i]),
         );
       }
     }
@@ -210,6 +216,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       for hand in sim.hands {
         assert_eq!(hand.opening_hand_size, 7);
@@ -253,6 +261,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       for hand in sim.hands {
         let hand_contains_card = hand
@@ -301,6 +311,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       for hand in sim.hands {
         let hand_contains_cards = hand
@@ -349,6 +361,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       for hand in sim.hands {
         let hand_contains_cards = hand
@@ -397,6 +411,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       for hand in sim.hands {
         let hand_contains_cards = hand
@@ -432,6 +448,8 @@ mod tests {
       mulligan: &mulligan,
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     for hand in sim.hands {
       assert_eq!(hand.opening_hand_size, 0);
@@ -475,6 +493,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let obs = sim.observations_for_card(card);
       let p = obs.in_opening_hand as f64 / runs as f64;
@@ -489,6 +509,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let obs = sim.observations_for_card(card);
       let p = obs.in_opening_hand as f64 / runs as f64;
@@ -503,6 +525,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let obs = sim.observations_for_card(card);
       let p = obs.in_opening_hand as f64 / runs as f64;
@@ -517,6 +541,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let obs = sim.observations_for_card(card);
       let p = obs.in_opening_hand as f64 / runs as f64;
@@ -531,6 +557,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let obs = sim.observations_for_card(card);
       let p = obs.in_opening_hand as f64 / runs as f64;
@@ -545,6 +573,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let obs = sim.observations_for_card(card);
       let p = obs.in_opening_hand as f64 / runs as f64;
@@ -559,6 +589,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let obs = sim.observations_for_card(card);
       let p = obs.in_opening_hand as f64 / runs as f64;
@@ -624,6 +656,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands, 0);
       let p = good_hands as f64 / runs as f64;
@@ -638,6 +672,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands, 0);
       let p = good_hands as f64 / runs as f64;
@@ -652,6 +688,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands, 0);
       let p = good_hands as f64 / runs as f64;
@@ -666,6 +704,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands, 0);
       let p = good_hands as f64 / runs as f64;
@@ -734,6 +774,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands);
       let p = good_hands as f64 / runs as f64;
@@ -748,6 +790,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands);
       let p = good_hands as f64 / runs as f64;
@@ -763,6 +807,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands);
       let p = good_hands as f64 / runs as f64;
@@ -777,6 +823,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands);
       let p = good_hands as f64 / runs as f64;
@@ -831,6 +879,8 @@ mod tests {
       mulligan: &mulligan,
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let good_hands = good_hand_count(&sim.hands, 0);
     let p = good_hands as f64 / runs as f64;
@@ -843,6 +893,8 @@ mod tests {
       mulligan: &mulligan,
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let good_hands = good_hand_count(&sim.hands, 1);
     let p = good_hands as f64 / runs as f64;
@@ -898,6 +950,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands, 0);
       let p = good_hands as f64 / runs as f64;
@@ -912,6 +966,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands, 1);
       let p = good_hands as f64 / runs as f64;
@@ -967,6 +1023,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands, 0);
       let p = good_hands as f64 / runs as f64;
@@ -981,6 +1039,8 @@ mod tests {
         mulligan: &mulligan,
         deck: &deck,
         on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
       });
       let good_hands = good_hand_count(&sim.hands, 1);
       let p = good_hands as f64 / runs as f64;
@@ -1035,6 +1095,8 @@ mod tests {
       mulligan: &mulligan,
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let good_hands = good_hand_count(&sim.hands, 0);
     let p = good_hands as f64 / runs as f64;
@@ -1060,6 +1122,8 @@ mod tests {
       mulligan: &mulligan,
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     for hand in sim.hands {
       assert_eq!(hand.opening_hand_size, 2);
@@ -1084,6 +1148,8 @@ mod tests {
       mulligan: &mulligan,
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     for hand in sim.hands {
       assert_eq!(hand.opening_hand_size, 0);
@@ -47,6 +47,12 @@ impl Mulligan for Never {
     return Hand::from_opening_and_draws(
       &shuffled_deck[..starting_hand_size],
       &shuffled_deck[starting_hand_size..],
+    )
+    // partial_shuffle moves the drawn cards to the end of index_range
+    .with_library(
+      index_range[..deck_len - cards_to_draw]
+        .iter()
+        .map(|i| deck[*i]),
     );
   }
 }
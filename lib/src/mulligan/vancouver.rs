@@ -2,29 +2,300 @@ use crate::card::Card;
 use crate::hand::Hand;
 use crate::mulligan::Mulligan;
 use rand::prelude::*;
+use std::collections::HashSet;
 
-const _STARTING_HAND_SIZE: usize = 7;
+const STARTING_HAND_SIZE: usize = 7;
 
 /// Vancouver represents a mulligan strategy that adheres to the
 /// [Vancouver mulligan rule](https://mtg.gamepedia.com/Mulligan#Vancouver_mulligan)
-///
-/// TODO This strategy is currently unimplemented
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Vancouver {
   pub starting_hand_size: usize,
+  pub mulligan_down_to: usize,
+  /// A set of land counts on which to always mulligan
+  pub mulligan_on_lands: HashSet<usize>,
+  /// A list of card sets that represent keepable hands
+  /// The card is represented by it's `u64` hash value
+  pub acceptable_hand_list: Vec<HashSet<u64>>,
 }
 
 impl Vancouver {
+  /// Returns a Vancouver mulligan strategy that never mulligans
   pub fn never() -> Self {
-    unimplemented!()
+    Self {
+      starting_hand_size: STARTING_HAND_SIZE,
+      mulligan_down_to: STARTING_HAND_SIZE,
+      mulligan_on_lands: HashSet::new(),
+      acceptable_hand_list: Default::default(),
+    }
   }
-  pub fn always(_down_to: usize) -> Self {
-    unimplemented!()
+
+  /// Returns a Vancouver mulligan strategy that always mulligans
+  /// down to `down_to` card count
+  pub fn always(down_to: usize) -> Self {
+    let mut mulligan_on_lands = HashSet::new();
+    for i in 0..=STARTING_HAND_SIZE {
+      mulligan_on_lands.insert(i);
+    }
+    Self {
+      starting_hand_size: STARTING_HAND_SIZE,
+      mulligan_down_to: down_to,
+      mulligan_on_lands,
+      acceptable_hand_list: Default::default(),
+    }
+  }
+}
+
+impl Vancouver {
+  // Returns true if the scry keeps the top card of the library on top of a kept hand of
+  // hand_size cards with land_count lands. The hand is flooded once every larger land count
+  // is a land count this strategy mulligans on, so the scry keeps a land on top while the
+  // hand is not flooded, and keeps a nonland on top once it is
+  fn scry_keeps_on_top(&self, top_is_land: bool, land_count: usize, hand_size: usize) -> bool {
+    let flooded = (land_count + 1..=hand_size).all(|n| self.mulligan_on_lands.contains(&n));
+    top_is_land != flooded
   }
 }
 
 impl Mulligan for Vancouver {
-  fn simulate_hand(&self, mut _rng: &mut impl Rng, _deck: &[&Card], _draws: usize) -> Hand {
-    unimplemented!();
+  fn simulate_hand(&self, mut rng: &mut impl Rng, deck: &[&Card], draws: usize) -> Hand {
+    let deck_size = deck.len();
+
+    // The number of cards to draw for the starting hand, capped by deck_size
+    let starting_hand_size = std::cmp::min(self.starting_hand_size, deck_size);
+    // The number of cards to mulligan down to, capped by starting_hand_size
+    let mulligan_down_to = std::cmp::min(self.mulligan_down_to, starting_hand_size);
+    assert!(starting_hand_size >= mulligan_down_to);
+    // The maximum number of mulligan rounds to consider
+    let max_mulligan_rounds = starting_hand_size - mulligan_down_to + 1;
+    assert!(max_mulligan_rounds > 0);
+
+    // Need to draw starting_hand_size cards plus the number of draws specified, capped by deck_size
+    // We add one more card to act as the back of the deck for the scry process
+    let cards_to_draw = std::cmp::min(starting_hand_size + draws + 1, deck_size);
+
+    // Create an index range to shuffle on rather than shuffling the immutable `deck` slice
+    let mut index_range: Vec<_> = (0..deck_size).collect();
+
+    // Iterate through the mulligan rounds. Note that round == 0 is considered the first starting hand draw
+    for round in 0..max_mulligan_rounds {
+      // Each mulligan round draws one fewer card than the previous round
+      let opening_hand_size = starting_hand_size - round;
+      // Rather than shuffle the entire deck, only consider cards_to_draw
+      let mut shuffled_deck: Vec<_> = index_range
+        .partial_shuffle(&mut rng, cards_to_draw)
+        .0
+        .iter()
+        .map(|i| deck[*i])
+        .collect();
+      let opening_hand = &shuffled_deck[..opening_hand_size];
+
+      // Have to keep the hand if this is the last round
+      let is_last_round = round == max_mulligan_rounds - 1;
+
+      // Do we have a sufficient number of lands in our opening hand according to
+      // the mulligan strategy?
+      let land_count = opening_hand
+        .iter()
+        .fold(0, |accum, c| if c.is_land() { accum + 1 } else { accum });
+      let sufficient_land_count = !self.mulligan_on_lands.contains(&land_count);
+      // Is this not the last round? Not enough lands? Great -- onto the next round
+      if !is_last_round && !sufficient_land_count {
+        continue;
+      }
+
+      // Check if our opening hand contains all cards of
+      // one of the sets specified in the mulligan strategy
+      let found_acceptable_hand = self.acceptable_hand_list.iter().any(|acceptable_hand| {
+        acceptable_hand
+          .iter()
+          .all(|hash| opening_hand.iter().any(|card| card.hash == *hash))
+      });
+
+      // Can we keep the hand?
+      let disregard_found_acceptable_hand = self.acceptable_hand_list.is_empty();
+      let keep = is_last_round
+        || (sufficient_land_count && (disregard_found_acceptable_hand || found_acceptable_hand));
+      if !keep {
+        continue;
+      }
+
+      // Any hand kept after a mulligan gets to scry 1
+      let can_scry = round > 0 && opening_hand_size < shuffled_deck.len();
+      if can_scry {
+        let top = shuffled_deck[opening_hand_size];
+        if !self.scry_keeps_on_top(top.is_land(), land_count, opening_hand_size) {
+          // Move the top card to the back of our drawn cards. We drew one extra card
+          // in the cards_to_draw calculation above so that this card is not drawn
+          let top = shuffled_deck.remove(opening_hand_size);
+          shuffled_deck.push(top);
+        }
+      }
+      return Hand::from_opening_and_draws(
+        &shuffled_deck[..opening_hand_size],
+        &shuffled_deck[opening_hand_size..],
+      )
+      // partial_shuffle moves the drawn cards to the end of index_range
+      .with_library(
+        index_range[..deck_size - cards_to_draw]
+          .iter()
+          .map(|i| deck[*i]),
+      );
+    }
+    unreachable!();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::hand::LandEntry;
+  use crate::mulligan::vancouver::*;
+  use crate::simulation::*;
+  use std::collections::HashSet;
+
+  #[test]
+  fn mulligan_test_never() {
+    let code = "
+        1 Cleansing Nova (M19) 9
+        1 Vraska, Relic Seeker (XLN) 232
+        1 Sinister Sabotage (GRN) 54
+        1 Opt (XLN) 65
+        1 Vraska's Contempt (XLN) 129
+        1 Thought Erasure
+        1 Cry of the Carnarium (RNA) 70
+        ";
+    let deck = decklist!(code);
+    let runs = 100;
+    let draws = 0;
+    let mulligan = Vancouver::never();
+    let sim = Simulation::from_config(&SimulationConfig {
+      run_count: runs,
+      draw_count: draws,
+      mulligan: &mulligan,
+      deck: &deck,
+      on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
+    });
+    for hand in sim.hands {
+      assert_eq!(hand.opening_hand_size, 7);
+      assert_eq!(hand.mulligan_count, 0);
+    }
+  }
+
+  #[test]
+  fn mulligan_test_always() {
+    let code = "
+        30 Island
+        30 Opt
+        ";
+    let deck = decklist!(code);
+    let runs = 100;
+    let draws = 5;
+    for down_to in 0..=7 {
+      let mulligan = Vancouver::always(down_to);
+      let sim = Simulation::from_config(&SimulationConfig {
+        run_count: runs,
+        draw_count: draws,
+        mulligan: &mulligan,
+        deck: &deck,
+        on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry: LandEntry::default(),
+      });
+      for hand in sim.hands {
+        assert_eq!(hand.opening_hand_size, down_to);
+        assert_eq!(hand.mulligan_count, 7 - down_to);
+        // The scry never changes the number of cards drawn
+        assert_eq!(hand.opening_with_draws(draws).len(), down_to + draws);
+      }
+    }
+  }
+
+  #[test]
+  fn mulligan_test_acceptable_hand() {
+    let code = "
+        1 Cleansing Nova (M19) 9
+        1 Vraska, Relic Seeker (XLN) 232
+        1 Sinister Sabotage (GRN) 54
+        1 Opt (XLN) 65
+        1 Vraska's Contempt (XLN) 129
+        1 Thought Erasure
+        1 Cry of the Carnarium (RNA) 70
+        1 Island
+        ";
+    let deck = decklist!(code);
+    let look_for = {
+      let mut hs = HashSet::new();
+      hs.insert(card!("Island").hash);
+      vec![hs]
+    };
+    let runs = 100;
+    let draws = 0;
+    let mut mulligan = Vancouver::never();
+    mulligan.mulligan_down_to = 1;
+    mulligan.acceptable_hand_list = look_for;
+    let island = card!("Island");
+    let sim = Simulation::from_config(&SimulationConfig {
+      run_count: runs,
+      draw_count: draws,
+      mulligan: &mulligan,
+      deck: &deck,
+      on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
+    });
+    for hand in sim.hands {
+      let kept_island = hand.opening().iter().any(|c| c.hash == island.hash);
+      // Either we found the island, or we were forced to keep a single card hand
+      assert!(kept_island || hand.opening_hand_size == 1);
+    }
+  }
+
+  #[test]
+  fn scry_finds_spell_for_land_heavy_hand() {
+    let code = "
+        7 Island
+        1 Opt
+        ";
+    let deck = decklist!(code);
+    let opt = card!("Opt");
+    let runs = 100;
+    let draws = 1;
+    let mulligan = Vancouver::always(6);
+    let sim = Simulation::from_config(&SimulationConfig {
+      run_count: runs,
+      draw_count: draws,
+      mulligan: &mulligan,
+      deck: &deck,
+      on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
+    });
+    for hand in sim.hands {
+      assert_eq!(hand.opening_hand_size, 6);
+      // A hand with enough lands scrys lands to the bottom, so we
+      // either kept Opt or draw it with our first draw
+      let found_opt = hand.any_in_opening_with_draws(draws, |c| c.hash == opt.hash);
+      assert!(found_opt);
+    }
+  }
+
+  #[test]
+  fn scry_keeps_lands_until_flooded() {
+    let mut mulligan = Vancouver::never();
+    mulligan.mulligan_on_lands = vec![0, 1, 6, 7].into_iter().collect();
+    // A 6 card hand with 0 to 4 lands needs another land, and a hand with 5 lands does not
+    for land_count in 0..=4 {
+      assert!(mulligan.scry_keeps_on_top(true, land_count, 6));
+      assert!(!mulligan.scry_keeps_on_top(false, land_count, 6));
+    }
+    assert!(!mulligan.scry_keeps_on_top(true, 5, 6));
+    assert!(mulligan.scry_keeps_on_top(false, 5, 6));
+    // A strategy that accepts any land count wants lands until the hand is all lands
+    mulligan.mulligan_on_lands.clear();
+    assert!(mulligan.scry_keeps_on_top(true, 5, 6));
+    assert!(!mulligan.scry_keeps_on_top(false, 5, 6));
+    assert!(mulligan.scry_keeps_on_top(false, 6, 6));
   }
 }
@@ -1,9 +1,12 @@
 use crate::card::*;
+use crate::set::{Set, SetType};
 use chrono::NaiveDate;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
+use std::io::Write;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScryfallCard {
@@ -21,15 +24,22 @@ pub struct ScryfallCard {
     #[serde(default)]
     pub type_line: String,
     #[serde(default)]
+    pub colors: HashSet<ManaColor>,
+    #[serde(default)]
     pub color_identity: HashSet<ManaColor>,
     #[serde(default)]
-    pub legalities: HashMap<String, Legality>,
+    pub produced_mana: HashSet<ManaColor>,
+    #[serde(default)]
+    pub legalities: HashMap<GameFormat, Legality>,
     #[serde(default)]
     pub image_uris: HashMap<String, String>,
     #[serde(default)]
     pub cmc: f32,
     #[serde(default)]
     pub arena_id: u64,
+    /// The Arena ids of the other printings of the card, collected by `PrintingDedup`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub other_arena_ids: Vec<u64>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub card_faces: Vec<ScryfallCard>,
     #[serde(default)]
@@ -45,9 +55,34 @@ pub struct ScryfallCard {
     pub lang: Option<String>,
     #[serde(default)]
     pub promo: bool,
+    #[serde(default)]
+    pub games: Vec<String>,
+    #[serde(default)]
+    pub layout: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialOrd, PartialEq)]
+/// ScryfallSet represents a [Scryfall set object](https://scryfall.com/docs/api/sets)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScryfallSet {
+    pub code: SetCode,
+    pub name: String,
+    #[serde(default)]
+    pub set_type: SetType,
+    #[serde(with = "scryfall_date_format")]
+    #[serde(default = "scryfall_default_date")]
+    pub released_at: NaiveDate,
+    #[serde(default)]
+    pub arena_code: Option<SetCode>,
+}
+
+/// ScryfallSetList represents the list returned by the Scryfall /sets endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScryfallSetList {
+    pub data: Vec<ScryfallSet>,
+}
+
+/// Legality represents the legality of a card in a [GameFormat](enum.GameFormat.html)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialOrd, PartialEq, Eq, Ord, Hash)]
 pub enum Legality {
     #[serde(rename = "legal")]
     Legal,
@@ -71,8 +106,10 @@ pub enum Object {
     Other,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialOrd, PartialEq, Eq, Hash)]
-#[serde(rename = "lowercase")]
+/// GameFormat represents a [format](https://mtg.gamepedia.com/Format), named
+/// after the keys of the Scryfall card legalities
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialOrd, PartialEq, Eq, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
 pub enum GameFormat {
     Future,
     Pioneer,
@@ -87,6 +124,15 @@ pub enum GameFormat {
     Standard,
     Modern,
     Legacy,
+    Explorer,
+    Timeless,
+    Alchemy,
+    Gladiator,
+    Oathbreaker,
+    StandardBrawl,
+    PauperCommander,
+    Predh,
+    Premodern,
     #[serde(other)]
     Other,
 }
@@ -102,105 +148,6 @@ pub enum Rarity {
     Unknown,
 }
 
-/// Set codes
-/// See [https://mtg.gamepedia.com/Template:List_of_Magic_sets](https://mtg.gamepedia.com/Template:List_of_Magic_sets)
-/// This listing only covers core and expansion sets from ~2015
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialOrd, PartialEq, Eq, Ord, Hash)]
-#[serde(rename_all = "lowercase")]
-pub enum SetCode {
-    // DAR is MTGAA's code for DOM
-    // https://www.reddit.com/r/MagicArena/comments/8f72hf/mtga_calls_dominara_dar_instead_of_dom_why/
-    // TODO: consider making a separate arena set code
-    IKO,
-    DAR,
-    ORI,
-    BFZ,
-    OGW,
-    SOI,
-    EMN,
-    KLD,
-    AER,
-    AKH,
-    HOU,
-    XLN,
-    RIX,
-    DOM,
-    M19,
-    GRN,
-    RNA,
-    WAR,
-    M20,
-    ELD,
-    THB,
-    M21,
-    #[serde(other)]
-    Unknown,
-}
-
-impl std::str::FromStr for SetCode {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, ()> {
-        let r = match s {
-            // DAR is MTGAA's code for DOM
-            // https://www.reddit.com/r/MagicArena/comments/8f72hf/mtga_calls_dominara_dar_instead_of_dom_why/
-            // TODO: consider making a separate arena set code
-            "IKO" => Self::IKO,
-            "DAR" => Self::DOM,
-            "ORI" => Self::ORI,
-            "BFZ" => Self::BFZ,
-            "OGW" => Self::OGW,
-            "SOI" => Self::SOI,
-            "EMN" => Self::EMN,
-            "KLD" => Self::KLD,
-            "AER" => Self::AER,
-            "AKH" => Self::AKH,
-            "HOU" => Self::HOU,
-            "XLN" => Self::XLN,
-            "RIX" => Self::RIX,
-            "DOM" => Self::DOM,
-            "M19" => Self::M19,
-            "GRN" => Self::GRN,
-            "RNA" => Self::RNA,
-            "WAR" => Self::WAR,
-            "M20" => Self::M20,
-            "ELD" => Self::ELD,
-            "THB" => Self::THB,
-            "M21" => Self::M21,
-            _ => Self::Unknown,
-        };
-        Ok(r)
-    }
-}
-
-impl std::fmt::Display for SetCode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
-    }
-}
-
-impl SetCode {
-    pub fn in_standard(&self) -> bool {
-        match self {
-            Self::IKO => true,
-            Self::GRN => true,
-            Self::RNA => true,
-            Self::WAR => true,
-            Self::M20 => true,
-            Self::ELD => true,
-            Self::THB => true,
-            Self::M21 => false,
-            _ => false,
-        }
-    }
-}
-
-impl Default for SetCode {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 impl Default for Rarity {
     fn default() -> Self {
         Self::Unknown
@@ -249,262 +196,16 @@ mod scryfall_date_format {
     }
 }
 
-// We use Scryfall's color_identity attribute to determine the color sources
-// of a land card. In some cases, this is incorrect. Rather than parse the
-// the oracle text, we simply keep a map of land cards and the mana cost
-// we wish them to represent
+// The mana a land card produces is parsed from its oracle text, see
+// `land_mana_from_oracle_text`. The few lands whose mana abilities cannot be
+// parsed are kept in a map of land cards and the mana cost we wish them to represent
 lazy_static! {
     static ref SPECIAL_LANDS: HashMap<&'static str, ManaCost> = [
+        // "Add one mana of any type that a land you control could produce."
         (
-            "Slayers' Stronghold",
-            ManaCost::from_rgbuwc(0, 0, 0, 0, 0, 1)
-        ),
-        (
-            "Alchemist's Refuge",
-            ManaCost::from_rgbuwc(0, 0, 0, 0, 0, 1)
-        ),
-        (
-            "Desolate Lighthouse",
-            ManaCost::from_rgbuwc(0, 0, 0, 0, 0, 1)
-        ),
-        (
-            "Command Tower",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Opal Palace",
+            "Reflecting Pool",
             ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 1)
         ),
-        (
-            "Path of Ancestry",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        // fetch lands
-        (
-            "Arid Mesa",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Bloodstained Mire",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Flooded Strand",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Marsh Flats",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Misty Rainforest",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Polluted Delta",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Scalding Tarn",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Verdant Catacombs",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Windswept Heath",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Wooded Foothills",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Fabled Passage",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Evolving Wilds",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        // KHM Uncommon Lands
-        // https://scryfall.com/search?as=grid&order=name&q=type%3Aland+set%3Akhm+rarity%3Au
-        (
-            "Axgard Armory",
-            ManaCost::from_rgbuwc(0, 0, 0, 0, 1, 0)
-        ),
-        (
-            "Bretagard Stronghold",
-            ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0)
-        ),
-        (
-            "Gates of Istfell",
-            ManaCost::from_rgbuwc(0, 0, 0, 0, 1, 0)
-        ),
-        (
-            "Gnottvold Slumbermound",
-            ManaCost::from_rgbuwc(1, 0, 0, 0, 0, 0)
-        ),
-        (
-            "Great Hall of Starnheim",
-            ManaCost::from_rgbuwc(0, 0, 1, 0, 0, 0)
-        ),
-        (
-            "Immersturm Skullcairn",
-            ManaCost::from_rgbuwc(0, 0, 1, 0, 0, 0)
-        ),
-        (
-            "Littjara Mirrorlake",
-            ManaCost::from_rgbuwc(0, 0, 0, 1, 0, 0)
-        ),
-        (
-            "Port of Karfell",
-            ManaCost::from_rgbuwc(0, 0, 0, 1, 0, 0)
-        ),
-        (
-            "Skemfar Elderhall",
-            ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0)
-        ),
-        (
-            "Surtland Frostpyre",
-            ManaCost::from_rgbuwc(1, 0, 0, 0, 0, 0)
-        ),
-        (
-            "Terramorphic Expanse",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        (
-            "Prismatic Vista",
-            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
-        ),
-        // Pathways
-        // https://scryfall.com/search?q=pathway&as=text
-        (
-          "Barkchannel Pathway // Tidechannel Pathway",
-          ManaCost::from_rgbuwc(0, 1, 0, 1, 0, 0)
-        ),
-        (
-          "Barkchannel Pathway",
-          ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0)
-        ),
-        (
-          "Tidechannel Pathway",
-          ManaCost::from_rgbuwc(0, 0, 0, 1, 0, 0)
-        ),
-        (
-          "Blightstep Pathway // Searstep Pathway",
-          ManaCost::from_rgbuwc(1, 0, 1, 0, 0, 0)
-        ),
-        (
-          "Blightstep Pathway",
-          ManaCost::from_rgbuwc(0, 0, 1, 0, 0, 0)
-        ),
-        (
-          "Searstep Pathway",
-          ManaCost::from_rgbuwc(0, 0, 0, 1, 0, 0)
-        ),
-        (
-          "Branchloft Pathway // Boulderloft Pathway",
-          ManaCost::from_rgbuwc(0, 1, 0, 0, 1, 0)
-        ),
-        (
-          "Branchloft Pathway",
-          ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0)
-        ),
-        (
-          "Boulderloft Pathway",
-          ManaCost::from_rgbuwc(0, 0, 0, 0, 1, 0)
-        ),
-        (
-          "Brightclimb Pathway // Grimclimb Pathway",
-          ManaCost::from_rgbuwc(0, 0, 1, 0, 1, 0)
-        ),
-        (
-          "Brightclimb Pathway",
-          ManaCost::from_rgbuwc(0, 0, 0, 0, 1, 0)
-        ),
-        (
-          "Grimclimb Pathway",
-          ManaCost::from_rgbuwc(0, 0, 1, 0, 0, 0)
-        ),
-        (
-          "Clearwater Pathway // Murkwater Pathway",
-          ManaCost::from_rgbuwc(0, 0, 1, 1, 0, 0)
-        ),
-        (
-          "Clearwater Pathway",
-          ManaCost::from_rgbuwc(0, 0, 0, 1, 0, 0)
-        ),
-        (
-          "Murkwater Pathway",
-          ManaCost::from_rgbuwc(0, 0, 1, 0, 0, 0)
-        ),
-        (
-          "Cragcrown Pathway // Timbercrown Pathway",
-          ManaCost::from_rgbuwc(1, 1, 0, 0, 0, 0)
-        ),
-        (
-          "Cragcrown Pathway",
-          ManaCost::from_rgbuwc(1, 0, 0, 0, 0, 0)
-        ),
-        (
-          "Timbercrown Pathway",
-          ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0)
-        ),
-        (
-          "Darkbore Pathway // Slitherbore Pathway",
-          ManaCost::from_rgbuwc(0, 1, 1, 0, 0, 0)
-        ),
-        (
-          "Darkbore Pathway",
-          ManaCost::from_rgbuwc(0, 0, 1, 0, 0, 0)
-        ),
-        (
-          "Slitherbore Pathway",
-          ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0)
-        ),
-        (
-          "Hengegate Pathway // Mistgate Pathway",
-          ManaCost::from_rgbuwc(0, 0, 0, 1, 1, 0)
-        ),
-        (
-          "Hengegate Pathway",
-          ManaCost::from_rgbuwc(0, 0, 0, 0, 1, 0)
-        ),
-        (
-          "Mistgate Pathway",
-          ManaCost::from_rgbuwc(0, 0, 0, 1, 0, 0)
-        ),
-        (
-          "Needleverge Pathway // Pillarverge Pathway",
-          ManaCost::from_rgbuwc(0, 0, 0, 0, 1, 0)
-        ),
-        (
-          "Needleverge Pathway",
-          ManaCost::from_rgbuwc(1, 0, 0, 0, 0, 0)
-        ),
-        (
-          "Pillarverge Pathway",
-          ManaCost::from_rgbuwc(0, 0, 0, 0, 1, 0)
-        ),
-        (
-          "Riverglide Pathway // Lavaglide Pathway",
-          ManaCost::from_rgbuwc(1, 0, 0, 1, 0, 0)
-        ),
-        (
-          "Riverglide Pathway",
-          ManaCost::from_rgbuwc(0, 0, 0, 1, 0, 0)
-        ),
-        (
-          "Lavaglide Pathway",
-          ManaCost::from_rgbuwc(1, 0, 0, 0, 0, 0)
-        ),
-        // https://github.com/mtgoncurve/mtgoncurve.com/issues/78
-        (
-            "Kor Haven",
-            ManaCost::from_rgbuwc(0, 0, 0, 0, 0, 1)
-          )
     ]
     .iter()
     .copied()
@@ -513,6 +214,16 @@ lazy_static! {
 
 impl Into<Card> for ScryfallCard {
     fn into(self) -> Card {
+        // The oracle text of a card with multiple faces, such as a modal double faced land, is on its faces
+        let oracle_text = if self.oracle_text.is_empty() {
+            self.card_faces
+                .iter()
+                .map(|face| face.oracle_text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            self.oracle_text.clone()
+        };
         let kind;
         let mana_cost;
         let all_mana_costs;
@@ -531,9 +242,13 @@ impl Into<Card> for ScryfallCard {
                     0
                 }
             }
+            let parsed_mana = land_mana_from_oracle_text(&oracle_text);
             mana_cost = if let Some(cost) = SPECIAL_LANDS.get::<str>(&self.name) {
                 *cost
+            } else if parsed_mana.bits != 0 {
+                parsed_mana
             } else {
+                // Fall back to the color identity for lands without a mana ability
                 ManaCost::from_rgbuwc(
                     is_color_01(&self, ManaColor::Red),
                     is_color_01(&self, ManaColor::Green),
@@ -543,18 +258,24 @@ impl Into<Card> for ScryfallCard {
                     is_color_01(&self, ManaColor::Colorless),
                 )
             };
-            let is_check = self
-                .oracle_text
-                .contains("enters the battlefield tapped unless you control a");
-            let is_shock = self
-                .oracle_text
-                .contains("enters the battlefield, you may pay 2 life.");
-            let is_tap = self.oracle_text.contains("enters the battlefield tapped.");
+            // Newer oracle text says "enters tapped" rather than "enters the battlefield tapped"
+            let entry_text = self.oracle_text.replace("enters the battlefield", "enters");
+            let is_check = entry_text.contains("enters tapped unless you control a");
+            let is_shock = entry_text.contains("enters, you may pay 2 life.");
+            let is_fast =
+                entry_text.contains("enters tapped unless you control two or fewer other lands.");
+            let is_slow =
+                entry_text.contains("enters tapped unless you control two or more other lands.");
+            let is_tap = entry_text.contains("enters tapped.");
             let is_basic = self.type_line.contains("Basic Land");
             if is_shock {
                 kind = CardKind::ShockLand;
             } else if is_check {
                 kind = CardKind::CheckLand;
+            } else if is_fast {
+                kind = CardKind::FastLand;
+            } else if is_slow {
+                kind = CardKind::SlowLand;
             } else if is_tap {
                 kind = CardKind::TapLand;
             } else if is_basic {
@@ -565,16 +286,72 @@ impl Into<Card> for ScryfallCard {
             all_mana_costs = vec![mana_cost];
         } else {
             kind = CardKind::Unknown;
-            all_mana_costs = mana_costs_from_str(&self.mana_cost).into_iter().collect();
-            mana_cost = ManaCost::from_rgbuwc(
-                all_mana_costs[0].r,
-                all_mana_costs[0].g,
-                all_mana_costs[0].b,
-                all_mana_costs[0].u,
-                all_mana_costs[0].w,
-                all_mana_costs[0].c,
-            );
+            // Mana costs with unknown symbols, such as the un-set {HW}, are
+            // treated as free rather than guessed at
+            all_mana_costs = mana_costs_from_str(&self.mana_cost).unwrap_or_else(|e| {
+                log::warn!(
+                    "{} ({}) has an unparsable mana cost \"{}\", treating it as free: {}",
+                    self.name,
+                    self.set,
+                    self.mana_cost,
+                    e
+                );
+                vec![ManaCost::new()]
+            });
+            mana_cost = all_mana_costs[0];
         }
+        // Nonland permanents that tap for mana, such as mana dorks and mana rocks
+        let produced_mana_amount = if !is_land
+            && !self.produced_mana.is_empty()
+            && (self.type_line.contains("Creature") || self.type_line.contains("Artifact"))
+        {
+            mana_ability_amount_from_oracle_text(&oracle_text).unwrap_or(0)
+        } else {
+            0
+        };
+        let produced_mana = if produced_mana_amount > 0 {
+            let produces = |color| {
+                if self.produced_mana.contains(&color) {
+                    1
+                } else {
+                    0
+                }
+            };
+            Some(ManaCost::from_rgbuwc(
+                produces(ManaColor::Red),
+                produces(ManaColor::Green),
+                produces(ManaColor::Black),
+                produces(ManaColor::Blue),
+                produces(ManaColor::White),
+                produces(ManaColor::Colorless),
+            ))
+        } else {
+            None
+        };
+        let land_types = if is_land {
+            land_types_from_str(&self.type_line)
+        } else {
+            ManaCost::new()
+        };
+        // Ramp spells, and fetch lands that search for a land instead of producing mana
+        let ramp = if is_land && self.oracle_text.contains("Add ") {
+            None
+        } else {
+            ramp_from_oracle_text(&self.oracle_text)
+        };
+        // Formats that Scryfall added after this GameFormat listing are dropped
+        let legalities = self
+            .legalities
+            .into_iter()
+            .filter(|(format, _)| *format != GameFormat::Other)
+            .collect();
+        // The colors of a card with multiple faces are on its faces
+        let colors = color_set(
+            self.colors
+                .iter()
+                .chain(self.card_faces.iter().flat_map(|face| face.colors.iter())),
+        );
+        let color_identity = color_set(self.color_identity.iter());
         let name = self.name;
         let image_uri = match self.image_uris.get("normal") {
             None => {
@@ -594,12 +371,17 @@ impl Into<Card> for ScryfallCard {
         .to_string();
         // Calculate the earliest turn to play the card. By default, turn corresponds
         // to the CMC of the card (0 cost cards are played on t1)
-        let turn =
-            mana_cost.r + mana_cost.g + mana_cost.b + mana_cost.u + mana_cost.w + mana_cost.c;
-        let turn = std::cmp::max(1, turn);
+        let turn = std::cmp::max(1, mana_cost.cmc());
         let mut s = DefaultHasher::new();
         name.hash(&mut s);
         let hash = s.finish();
+        let mut arena_ids: Vec<_> = Some(self.arena_id)
+            .filter(|id| *id != 0)
+            .into_iter()
+            .chain(self.other_arena_ids)
+            .collect();
+        arena_ids.sort_unstable();
+        arena_ids.dedup();
         Card {
             name,
             oracle_id: self.oracle_id,
@@ -611,9 +393,1037 @@ impl Into<Card> for ScryfallCard {
             mana_cost,
             all_mana_costs,
             arena_id: self.arena_id,
+            arena_ids,
             set: self.set,
+            collector_number: self.collector_number,
             rarity: self.rarity,
             is_face: self.object == Object::CardFace,
+            produced_mana,
+            produced_mana_amount,
+            land_types,
+            ramp,
+            legalities,
+            type_line: self.type_line,
+            oracle_text,
+            colors,
+            color_identity,
+            layout: self.layout,
+        }
+    }
+}
+
+// Returns the colors as a ManaCost with one of each color, ignoring colorless
+fn color_set<'a, I: Iterator<Item = &'a ManaColor>>(colors: I) -> ManaCost {
+    let mut set = ManaCost::new();
+    for color in colors {
+        match color {
+            ManaColor::Red => set.r = 1,
+            ManaColor::Green => set.g = 1,
+            ManaColor::Black => set.b = 1,
+            ManaColor::Blue => set.u = 1,
+            ManaColor::White => set.w = 1,
+            ManaColor::Colorless => {}
+        }
+    }
+    set.update_bits()
+}
+
+impl From<ScryfallSet> for Set {
+    fn from(set: ScryfallSet) -> Self {
+        // Only keep Arena codes that differ from the set code, e.g. DAR for DOM
+        let arena_code = set.arena_code.filter(|arena_code| *arena_code != set.code);
+        Set {
+            code: set.code,
+            name: set.name,
+            set_type: set.set_type,
+            released_at: set.released_at,
+            arena_code,
+        }
+    }
+}
+
+/// PrintingPreference represents the printing of a card to keep when
+/// deduplicating the Scryfall default cards
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrintingPreference {
+    /// The most recently released printing
+    #[default]
+    Newest,
+    /// The first released printing that is not a promo
+    OldestNonPromo,
+    /// The most recently released printing available on MTG Arena, or the newest
+    /// printing if the card is not on MTG Arena
+    Arena,
+}
+
+impl std::str::FromStr for PrintingPreference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "newest" => Ok(Self::Newest),
+            "oldest" => Ok(Self::OldestNonPromo),
+            "arena" => Ok(Self::Arena),
+            _ => Err(format!(
+                "Unknown printing preference \"{}\", expected newest, oldest or arena",
+                s
+            )),
+        }
+    }
+}
+
+/// DropReason represents why a Scryfall card was left out of the card database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DropReason {
+    /// The card is not legal in any format, e.g. a token
+    NotLegal,
+    /// The printing is not in English
+    NotEnglish,
+    /// The printing is a promo
+    Promo,
+    /// The printing is from a funny set, e.g. Unglued
+    Funny,
+    /// Another printing of the card was kept
+    DuplicatePrinting,
+}
+
+/// DropReport counts the Scryfall cards left out of the card database by reason,
+/// and writes a row for every dropped card to the writer, if any, as it is dropped
+///
+/// Each row is the tab separated reason, name, set and collector number of the card
+#[derive(Debug)]
+pub struct DropReport<W = std::io::Sink> {
+    counts: BTreeMap<DropReason, usize>,
+    writer: Option<W>,
+    // The first error writing a row, returned by finish
+    error: Option<std::io::Error>,
+}
+
+impl Default for DropReport {
+    fn default() -> Self {
+        Self {
+            counts: BTreeMap::new(),
+            writer: None,
+            error: None,
+        }
+    }
+}
+
+impl<W: Write> DropReport<W> {
+    /// Returns a report that writes a row for every dropped card to the writer
+    pub fn with_writer(writer: W) -> Self {
+        Self {
+            counts: BTreeMap::new(),
+            writer: Some(writer),
+            error: None,
+        }
+    }
+
+    /// Returns the number of dropped cards for each reason
+    pub fn counts(&self) -> &BTreeMap<DropReason, usize> {
+        &self.counts
+    }
+
+    /// Adds the card to the report
+    pub fn push(&mut self, card: ScryfallCard, reason: DropReason) {
+        *self.counts.entry(reason).or_insert(0) += 1;
+        if self.error.is_some() {
+            return;
+        }
+        if let Some(writer) = self.writer.as_mut() {
+            if let Err(e) = writeln!(
+                writer,
+                "{:?}\t{}\t{}\t{}",
+                reason, card.name, card.set, card.collector_number
+            ) {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// Flushes and returns the writer, or the first error writing to it
+    pub fn finish(mut self) -> std::io::Result<Option<W>> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        if let Some(writer) = self.writer.as_mut() {
+            writer.flush()?;
+        }
+        Ok(self.writer)
+    }
+}
+
+impl ScryfallCard {
+    // Returns the oracle id shared by every printing of the card. Reversible
+    // cards only have an oracle id on their faces
+    fn printing_key(&self) -> &str {
+        if !self.oracle_id.is_empty() {
+            return &self.oracle_id;
+        }
+        match self.card_faces.first() {
+            Some(face) if !face.oracle_id.is_empty() => &face.oracle_id,
+            _ => &self.name,
+        }
+    }
+
+    /// Returns false if the card is not legal in any format, e.g. a token
+    pub fn is_legal_anywhere(&self) -> bool {
+        !self.legalities.values().all(|l| l == &Legality::NotLegal)
+    }
+
+    fn is_on_arena(&self) -> bool {
+        self.arena_id != 0 || self.games.iter().any(|g| g == "arena")
+    }
+}
+
+/// Removes the cards that are not legal in any format, which filters out any tokens
+///
+/// See https://github.com/mtgoncurve/landlord/issues/4
+pub fn filter_not_legal<W: Write>(
+    cards: Vec<ScryfallCard>,
+    report: &mut DropReport<W>,
+) -> Vec<ScryfallCard> {
+    let mut kept = Vec::with_capacity(cards.len());
+    for card in cards {
+        if card.is_legal_anywhere() {
+            kept.push(card);
+        } else {
+            report.push(card, DropReason::NotLegal);
+        }
+    }
+    kept
+}
+
+/// Turns the Scryfall default cards, which list every printing of a card, into
+/// oracle style cards with a single printing per card, see [PrintingDedup](struct.PrintingDedup.html)
+pub fn dedup_default_cards<W: Write>(
+    cards: Vec<ScryfallCard>,
+    preference: PrintingPreference,
+    report: &mut DropReport<W>,
+) -> Vec<ScryfallCard> {
+    let mut dedup = PrintingDedup::new(preference);
+    for card in cards {
+        dedup.insert(card, report);
+    }
+    dedup.into_cards()
+}
+
+/// PrintingDedup keeps a single printing of every card inserted, one card at a time
+///
+/// Non-English printings, promos and funny sets are dropped, and one printing of
+/// every card is kept according to the preference. This is useful when the
+/// oracle cards are missing cards from upcoming sets
+#[derive(Debug, Clone)]
+pub struct PrintingDedup {
+    preference: PrintingPreference,
+    kept: HashMap<String, ScryfallCard>,
+}
+
+impl PrintingDedup {
+    pub fn new(preference: PrintingPreference) -> Self {
+        Self {
+            preference,
+            kept: HashMap::new(),
+        }
+    }
+
+    /// Keeps the card if it is the preferred printing so far, and reports the dropped printing
+    pub fn insert<W: Write>(&mut self, card: ScryfallCard, report: &mut DropReport<W>) {
+        if card.lang.as_deref() != Some("en") {
+            return report.push(card, DropReason::NotEnglish);
+        }
+        if card.promo {
+            return report.push(card, DropReason::Promo);
+        }
+        if card.set_type == "funny" {
+            return report.push(card, DropReason::Funny);
+        }
+        let key = card.printing_key().to_string();
+        let (mut kept, dropped) = match self.kept.remove(&key) {
+            Some(kept) if self.is_preferred(&kept, &card) => (kept, card),
+            Some(kept) => (card, kept),
+            None => {
+                self.kept.insert(key, card);
+                return;
+            }
+        };
+        // Arena identifies every printing by its own Arena id
+        kept.other_arena_ids
+            .extend(Some(dropped.arena_id).filter(|id| *id != 0));
+        kept.other_arena_ids
+            .extend(dropped.other_arena_ids.iter().copied());
+        self.kept.insert(key, kept);
+        report.push(dropped, DropReason::DuplicatePrinting);
+    }
+
+    /// Returns the kept printings, ordered by oracle id
+    pub fn into_cards(self) -> Vec<ScryfallCard> {
+        let mut cards: Vec<_> = self.kept.into_iter().collect();
+        cards.sort_by(|a, b| a.0.cmp(&b.0));
+        cards.into_iter().map(|(_, card)| card).collect()
+    }
+
+    // Returns true if printing a is preferred over printing b
+    fn is_preferred(&self, a: &ScryfallCard, b: &ScryfallCard) -> bool {
+        let order = match self.preference {
+            PrintingPreference::Newest => b.released_at.cmp(&a.released_at),
+            PrintingPreference::OldestNonPromo => a.released_at.cmp(&b.released_at),
+            PrintingPreference::Arena => {
+                (b.is_on_arena(), b.released_at).cmp(&(a.is_on_arena(), a.released_at))
+            }
+        };
+        order
+            .then_with(|| a.set.cmp(&b.set))
+            .then_with(|| a.collector_number.cmp(&b.collector_number))
+            != std::cmp::Ordering::Greater
+    }
+}
+
+struct ScryfallCardVisitor<F>(F);
+
+impl<'de, F> serde::de::Visitor<'de> for ScryfallCardVisitor<F>
+where
+    F: FnMut(ScryfallCard),
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "an array of Scryfall cards")
+    }
+
+    fn visit_seq<A>(mut self, mut seq: A) -> Result<(), A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        while let Some(card) = seq.next_element::<ScryfallCard>()? {
+            (self.0)(card);
+        }
+        Ok(())
+    }
+}
+
+/// Calls f with every card of a Scryfall bulk data JSON array, such as the oracle cards,
+/// as the card is read. Only a single card is kept in memory at a time, so even the
+/// all cards bulk data can be read with constant memory
+pub fn scryfall_cards_from_reader<R, F>(reader: R, f: F) -> Result<(), serde_json::Error>
+where
+    R: std::io::Read,
+    F: FnMut(ScryfallCard),
+{
+    use serde::Deserializer;
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    (&mut deserializer).deserialize_seq(ScryfallCardVisitor(f))?;
+    deserializer.end()
+}
+
+// Returns the mana a land card produces, parsed from the mana abilities and
+// land searches in its oracle text, e.g. "{T}: Add {G} or {U}.",
+// "{T}: Add one mana of any color." or "Search your library for a Forest or
+// Island card". Returns an empty mana cost if the text has neither
+fn land_mana_from_oracle_text(oracle_text: &str) -> ManaCost {
+    let all_colors = ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0);
+    let mut mana = ManaCost::new();
+    let mut add = |other: ManaCost| {
+        mana.r |= other.r;
+        mana.g |= other.g;
+        mana.b |= other.b;
+        mana.u |= other.u;
+        mana.w |= other.w;
+        mana.c |= other.c;
+    };
+    for (i, _) in oracle_text.match_indices("Add ") {
+        let rest = &oracle_text[i + "Add ".len()..];
+        let effect = &rest[..rest.find('.').unwrap_or(rest.len())];
+        if effect.starts_with("one mana of any color")
+            || effect.starts_with("two mana in any combination of colors")
+        {
+            // Mana that can only be spent on some spells is not counted
+            if !rest[effect.len()..].starts_with(". Spend this mana only") {
+                add(all_colors);
+            }
+            continue;
+        }
+        let mut symbols = effect;
+        while let Some(start) = symbols.find('{') {
+            let end = match symbols[start..].find('}') {
+                Some(end) => start + end,
+                None => break,
+            };
+            let color = |c| if &symbols[start + 1..end] == c { 1 } else { 0 };
+            add(ManaCost::from_rgbuwc(
+                color("R"),
+                color("G"),
+                color("B"),
+                color("U"),
+                color("W"),
+                color("C"),
+            ));
+            symbols = &symbols[end + 1..];
+        }
+    }
+    const SEARCH: &str = "earch your library for ";
+    for (i, _) in oracle_text.match_indices(SEARCH) {
+        let rest = &oracle_text[i + SEARCH.len()..];
+        let lands = match rest.find(" card") {
+            Some(end) => &rest[..end],
+            None => continue,
+        };
+        let land_types = land_types_from_str(lands);
+        if land_types.bits != 0 {
+            add(land_types);
+        } else if lands.contains("basic land") {
+            add(all_colors);
         }
     }
+    mana.update_bits()
+}
+
+// Returns the net amount of mana a nonland permanent adds each time it taps for
+// mana, parsed from the mana abilities in its oracle text, e.g. 1 for "{T}: Add {G}.",
+// 2 for "{T}: Add {C}{C}." and 1 for "{1}, {T}: Add {W}{U}.", where the mana paid
+// to activate the ability is subtracted from the mana it adds. Abilities with costs
+// other than tapping and mana, such as sacrificing the permanent, are ignored.
+// Returns None if the text has no mana ability that adds more mana than it costs
+fn mana_ability_amount_from_oracle_text(oracle_text: &str) -> Option<u8> {
+    const ADD: &str = ": Add ";
+    let mut best = 0;
+    for line in oracle_text.lines() {
+        let i = match line.find(ADD) {
+            Some(i) => i,
+            None => continue,
+        };
+        let cost = line[..i].trim_start_matches('(');
+        let mut taps = false;
+        let mut paid = 0u8;
+        let mut other_cost = false;
+        for part in cost.split(", ") {
+            if part == "{T}" {
+                taps = true;
+                continue;
+            }
+            match mana_costs_from_str(part) {
+                Ok(costs) if part.starts_with('{') && part.ends_with('}') => {
+                    paid = paid.saturating_add(costs[0].cmc())
+                }
+                _ => other_cost = true,
+            }
+        }
+        if !taps || other_cost {
+            continue;
+        }
+        let rest = &line[i + ADD.len()..];
+        let effect = &rest[..rest.find('.').unwrap_or(rest.len())];
+        let added = match effect.split(' ').next() {
+            Some("one") => 1,
+            Some("two") => 2,
+            Some("three") => 3,
+            Some("four") => 4,
+            Some("five") => 5,
+            // Choices such as "{G} or {U}" add the mana of a single choice
+            _ => effect
+                .split(" or ")
+                .next()
+                .map_or(0, |choice| choice.matches('{').count() as u8),
+        };
+        best = std::cmp::max(best, added.saturating_sub(paid));
+    }
+    if best > 0 {
+        Some(best)
+    } else {
+        None
+    }
+}
+
+// Returns the basic land types named in `s`, e.g. "Land — Forest Island"
+fn land_types_from_str(s: &str) -> ManaCost {
+    let has_type = |land_type| {
+        if s.split(|c: char| !c.is_alphabetic())
+            .any(|word| word == land_type)
+        {
+            1
+        } else {
+            0
+        }
+    };
+    ManaCost::from_rgbuwc(
+        has_type("Mountain"),
+        has_type("Forest"),
+        has_type("Swamp"),
+        has_type("Island"),
+        has_type("Plains"),
+        0,
+    )
+}
+
+// Returns the ramp effect of a spell that searches the library for land cards
+// and puts them onto the battlefield, e.g.
+// "Search your library for up to two basic land cards, reveal those cards,
+// put one onto the battlefield tapped and the other into your hand, then shuffle."
+fn ramp_from_oracle_text(oracle_text: &str) -> Option<Ramp> {
+    const SEARCH: &str = "earch your library for ";
+    let rest = &oracle_text[oracle_text.find(SEARCH)? + SEARCH.len()..];
+    let lands = &rest[..rest.find(" card")?];
+    let effect = &rest[..rest.find('.').unwrap_or(rest.len())];
+    let land_types = land_types_from_str(lands);
+    if (!lands.contains("land") && land_types.bits == 0) || !effect.contains("onto the battlefield")
+    {
+        return None;
+    }
+    let count = if lands.starts_with("up to two") || lands.starts_with("two") {
+        2
+    } else {
+        1
+    };
+    let hand_count = if count > 1 && effect.contains("into your hand") {
+        1
+    } else {
+        0
+    };
+    Some(Ramp {
+        land_types,
+        basic_only: lands.contains("basic"),
+        battlefield_count: count - hand_count,
+        tapped: effect.contains("onto the battlefield tapped"),
+        hand_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scryfall::*;
+
+    #[test]
+    fn mana_dork_produced_mana() {
+        let json = r#"{
+            "object": "card",
+            "name": "Llanowar Elves",
+            "mana_cost": "{G}",
+            "type_line": "Creature — Elf Druid",
+            "oracle_text": "{T}: Add {G}.",
+            "produced_mana": ["G"],
+            "lang": "en"
+        }"#;
+        let card: Card = serde_json::from_str::<ScryfallCard>(json).unwrap().into();
+        assert_eq!(
+            card.produced_mana,
+            Some(ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn mana_abilities_keep_their_amount() {
+        let card = |type_line: &str, oracle_text: &str, produced_mana: &str| -> Card {
+            let json = format!(
+                r#"{{
+                    "object": "card",
+                    "name": "Mana Rock",
+                    "mana_cost": "{{1}}",
+                    "type_line": "{}",
+                    "oracle_text": "{}",
+                    "produced_mana": {},
+                    "lang": "en"
+                }}"#,
+                type_line, oracle_text, produced_mana
+            );
+            serde_json::from_str::<ScryfallCard>(&json).unwrap().into()
+        };
+        let sol_ring = card("Artifact", "{T}: Add {C}{C}.", r#"["C"]"#);
+        assert_eq!(
+            sol_ring.produced_mana,
+            Some(ManaCost::from_rgbuwc(0, 0, 0, 0, 0, 1))
+        );
+        assert_eq!(sol_ring.produced_mana_amount, 2);
+        let signet = card("Artifact", "{1}, {T}: Add {W}{U}.", r#"["W", "U"]"#);
+        assert_eq!(
+            signet.produced_mana,
+            Some(ManaCost::from_rgbuwc(0, 0, 0, 1, 1, 0))
+        );
+        assert_eq!(signet.produced_mana_amount, 1);
+        let lotus = card(
+            "Artifact",
+            "{T}: Add three mana of any one color.",
+            r#"["W", "U", "B", "R", "G"]"#,
+        );
+        assert_eq!(lotus.produced_mana_amount, 3);
+        let birds = card(
+            "Creature — Bird",
+            "Flying\\n{T}: Add one mana of any color.",
+            r#"["W", "U", "B", "R", "G"]"#,
+        );
+        assert_eq!(birds.produced_mana_amount, 1);
+        let dork = card("Creature — Elf", "{T}: Add {G} or {U}.", r#"["G", "U"]"#);
+        assert_eq!(dork.produced_mana_amount, 1);
+        // One shot and filtering abilities are not mana sources
+        let petal = card(
+            "Artifact",
+            "{T}, Sacrifice Lotus Petal: Add one mana of any color.",
+            r#"["W", "U", "B", "R", "G"]"#,
+        );
+        assert_eq!(petal.produced_mana, None);
+        assert_eq!(petal.produced_mana_amount, 0);
+        let filter = card("Artifact", "{1}, {T}: Add {R}.", r#"["R"]"#);
+        assert_eq!(filter.produced_mana, None);
+    }
+
+    #[test]
+    fn spell_without_mana_ability_produces_no_mana() {
+        let json = r#"{
+            "object": "card",
+            "name": "Dark Ritual",
+            "mana_cost": "{B}",
+            "type_line": "Instant",
+            "oracle_text": "Add {B}{B}{B}.",
+            "produced_mana": ["B"],
+            "lang": "en"
+        }"#;
+        let card: Card = serde_json::from_str::<ScryfallCard>(json).unwrap().into();
+        assert_eq!(card.produced_mana, None);
+    }
+
+    // Returns a printing of the card, where extra overrides fields of the printing
+    fn printing(name: &str, set: &str, released_at: &str, extra: &str) -> ScryfallCard {
+        let mut json = serde_json::json!({
+            "object": "card",
+            "name": name,
+            "oracle_id": format!("{}-oracle", name),
+            "set": set,
+            "collector_number": "1",
+            "released_at": released_at,
+            "legalities": { "legacy": "legal" },
+            "lang": "en",
+        });
+        let extra: serde_json::Value = serde_json::from_str(&format!("{{{}}}", extra)).unwrap();
+        for (key, value) in extra.as_object().unwrap() {
+            json[key] = value.clone();
+        }
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn names_and_sets(cards: &[ScryfallCard]) -> Vec<(String, String)> {
+        cards
+            .iter()
+            .map(|c| (c.name.clone(), c.set.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn default_cards_filters() {
+        let cards = vec![
+            printing("Shock", "m19", "2018-07-13", ""),
+            printing("Shock", "m20", "2019-07-12", r#""lang": "ja""#),
+            printing("Shock", "pm20", "2019-07-12", r#""promo": true"#),
+            printing("Goblin", "unh", "2004-11-19", r#""set_type": "funny""#),
+            printing(
+                "Goblin Token",
+                "tm19",
+                "2018-07-13",
+                r#""legalities": { "legacy": "not_legal" }"#,
+            ),
+        ];
+        let mut report = DropReport::with_writer(Vec::new());
+        let cards = filter_not_legal(cards, &mut report);
+        let cards = dedup_default_cards(cards, PrintingPreference::Newest, &mut report);
+        assert_eq!(
+            names_and_sets(&cards),
+            vec![("Shock".to_string(), "M19".to_string())]
+        );
+        let counts = report.counts();
+        assert_eq!(counts[&DropReason::NotLegal], 1);
+        assert_eq!(counts[&DropReason::NotEnglish], 1);
+        assert_eq!(counts[&DropReason::Promo], 1);
+        assert_eq!(counts[&DropReason::Funny], 1);
+        assert_eq!(counts.get(&DropReason::DuplicatePrinting), None);
+        let rows = String::from_utf8(report.finish().unwrap().unwrap()).unwrap();
+        assert_eq!(rows.lines().count(), 4);
+        assert_eq!(rows.lines().nth(1), Some("NotEnglish\tShock\tM20\t1"));
+    }
+
+    #[test]
+    fn stream_cards_from_reader() {
+        let cards = vec![
+            printing("Shock", "m19", "2018-07-13", ""),
+            printing("Negate", "m19", "2018-07-13", ""),
+        ];
+        let json = serde_json::to_vec(&cards).unwrap();
+        let mut names = Vec::new();
+        scryfall_cards_from_reader(&json[..], |card| names.push(card.name)).unwrap();
+        assert_eq!(names, vec!["Shock", "Negate"]);
+        assert!(scryfall_cards_from_reader(&b"{}"[..], |_| {}).is_err());
+        assert!(scryfall_cards_from_reader(&b"[] []"[..], |_| {}).is_err());
+        let truncated = &json[..json.len() - 1];
+        assert!(scryfall_cards_from_reader(truncated, |_| {}).is_err());
+    }
+
+    #[test]
+    fn default_cards_printing_preference() {
+        let cards = || {
+            vec![
+                printing(
+                    "Shock",
+                    "m19",
+                    "2018-07-13",
+                    r#""games": ["paper", "arena"]"#,
+                ),
+                printing("Shock", "sta", "2021-04-23", r#""games": ["paper"]"#),
+                printing("Shock", "7ed", "2001-04-11", r#""games": ["paper"]"#),
+                printing("Negate", "m19", "2018-07-13", ""),
+            ]
+        };
+        let dedup = |preference| {
+            let mut report = DropReport::default();
+            let cards = dedup_default_cards(cards(), preference, &mut report);
+            assert_eq!(report.counts()[&DropReason::DuplicatePrinting], 2);
+            names_and_sets(&cards)
+        };
+        let expected = |set: &str| {
+            vec![
+                ("Negate".to_string(), "M19".to_string()),
+                ("Shock".to_string(), set.to_string()),
+            ]
+        };
+        assert_eq!(dedup(PrintingPreference::Newest), expected("STA"));
+        assert_eq!(dedup(PrintingPreference::OldestNonPromo), expected("7ED"));
+        assert_eq!(dedup(PrintingPreference::Arena), expected("M19"));
+        assert_eq!("oldest".parse(), Ok(PrintingPreference::OldestNonPromo));
+        assert!("cheapest".parse::<PrintingPreference>().is_err());
+    }
+
+    #[test]
+    fn default_cards_keep_every_arena_id() {
+        let cards = vec![
+            printing("Shock", "m19", "2018-07-13", r#""arena_id": 68519"#),
+            printing("Shock", "m20", "2019-07-12", r#""arena_id": 69644"#),
+            printing("Shock", "7ed", "2001-04-11", ""),
+            printing("Shock", "sta", "2021-04-23", r#""arena_id": 73156"#),
+        ];
+        let mut report = DropReport::default();
+        let cards = dedup_default_cards(cards, PrintingPreference::Arena, &mut report);
+        assert_eq!(cards.len(), 1);
+        let card: Card = cards.into_iter().next().unwrap().into();
+        assert_eq!(card.set.to_string(), "STA");
+        assert_eq!(card.arena_id, 73156);
+        assert_eq!(card.arena_ids, vec![68519, 69644, 73156]);
+        assert_eq!(
+            card.all_arena_ids().collect::<Vec<_>>(),
+            vec![73156, 68519, 69644]
+        );
+    }
+
+    #[test]
+    fn legalities() {
+        let json = r#"{
+            "object": "card",
+            "name": "Lightning Bolt",
+            "mana_cost": "{R}",
+            "type_line": "Instant",
+            "legalities": {
+                "standard": "not_legal",
+                "modern": "legal",
+                "vintage": "legal",
+                "paupercommander": "legal",
+                "predh": "legal",
+                "some_new_format": "legal"
+            },
+            "lang": "en"
+        }"#;
+        let card: Card = serde_json::from_str::<ScryfallCard>(json).unwrap().into();
+        assert_eq!(card.legalities.len(), 5);
+        assert_eq!(
+            card.legality(GameFormat::Standard),
+            Some(Legality::NotLegal)
+        );
+        assert_eq!(card.legality(GameFormat::Modern), Some(Legality::Legal));
+        assert_eq!(
+            card.legality(GameFormat::PauperCommander),
+            Some(Legality::Legal)
+        );
+        assert_eq!(card.legality(GameFormat::Predh), Some(Legality::Legal));
+        assert_eq!(card.legality(GameFormat::Explorer), None);
+        assert_eq!(card.legality(GameFormat::Other), None);
+    }
+
+    #[test]
+    fn ramp_spells() {
+        let ramp = |oracle_text: &str| {
+            let json = format!(
+                r#"{{
+                    "object": "card",
+                    "name": "Ramp",
+                    "mana_cost": "{{2}}{{G}}",
+                    "type_line": "Sorcery",
+                    "oracle_text": "{}",
+                    "lang": "en"
+                }}"#,
+                oracle_text
+            );
+            let card: Card = serde_json::from_str::<ScryfallCard>(&json).unwrap().into();
+            card.ramp
+        };
+        // Rampant Growth
+        assert_eq!(
+            ramp("Search your library for a basic land card, put that card onto the battlefield tapped, then shuffle."),
+            Some(Ramp {
+                land_types: ManaCost::new(),
+                basic_only: true,
+                battlefield_count: 1,
+                tapped: true,
+                hand_count: 0,
+            })
+        );
+        // Cultivate
+        assert_eq!(
+            ramp("Search your library for up to two basic land cards, reveal those cards, put one onto the battlefield tapped and the other into your hand, then shuffle."),
+            Some(Ramp {
+                land_types: ManaCost::new(),
+                basic_only: true,
+                battlefield_count: 1,
+                tapped: true,
+                hand_count: 1,
+            })
+        );
+        // Farseek
+        assert_eq!(
+            ramp("Search your library for a Plains, Island, Swamp, or Mountain card and put it onto the battlefield tapped, then shuffle."),
+            Some(Ramp {
+                land_types: ManaCost::from_rgbuwc(1, 0, 1, 1, 1, 0),
+                basic_only: false,
+                battlefield_count: 1,
+                tapped: true,
+                hand_count: 0,
+            })
+        );
+        // Three Visits
+        assert_eq!(
+            ramp(
+                "Search your library for a Forest card, put it onto the battlefield, then shuffle."
+            ),
+            Some(Ramp {
+                land_types: ManaCost::from_rgbuwc(0, 1, 0, 0, 0, 0),
+                basic_only: false,
+                battlefield_count: 1,
+                tapped: false,
+                hand_count: 0,
+            })
+        );
+        // Tutors and land searches that put the land into hand are not ramp
+        assert_eq!(
+            ramp("Search your library for a card, put that card into your hand, then shuffle."),
+            None
+        );
+        assert_eq!(ramp("Search your library for a basic land card, reveal it, put it into your hand, then shuffle."), None);
+    }
+
+    fn land_mana(oracle_text: &str, color_identity: &str) -> ManaCost {
+        let json = format!(
+            r#"{{
+                "object": "card",
+                "name": "Land",
+                "type_line": "Land",
+                "oracle_text": "{}",
+                "color_identity": [{}],
+                "lang": "en"
+            }}"#,
+            oracle_text, color_identity
+        );
+        let card: Card = serde_json::from_str::<ScryfallCard>(&json).unwrap().into();
+        card.mana_cost
+    }
+
+    #[test]
+    fn land_mana_abilities() {
+        // Kor Haven
+        assert_eq!(
+            land_mana(
+                "{T}: Add {C}.\\n{1}{W}, {T}: Prevent all combat damage that would be dealt by target attacking creature this turn.",
+                r#""W""#
+            ),
+            ManaCost::from_rgbuwc(0, 0, 0, 0, 0, 1)
+        );
+        // Zagoth Triome
+        assert_eq!(
+            land_mana(
+                "({T}: Add {B}, {R}, or {G}.)\\nZagoth Triome enters the battlefield tapped.\\nCycling {3}",
+                r#""B", "R", "G""#
+            ),
+            ManaCost::from_rgbuwc(1, 1, 1, 0, 0, 0)
+        );
+        // City of Brass
+        assert_eq!(
+            land_mana(
+                "Whenever City of Brass becomes tapped, it deals 1 damage to you.\\n{T}: Add one mana of any color.",
+                ""
+            ),
+            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
+        );
+        // Unclaimed Territory
+        assert_eq!(
+            land_mana(
+                "As Unclaimed Territory enters the battlefield, choose a creature type.\\n{T}: Add {C}.\\n{T}: Add one mana of any color. Spend this mana only to cast a creature spell of the chosen type.",
+                ""
+            ),
+            ManaCost::from_rgbuwc(0, 0, 0, 0, 0, 1)
+        );
+        // Arid Mesa
+        assert_eq!(
+            land_mana(
+                "{T}, Pay 1 life, Sacrifice Arid Mesa: Search your library for a Mountain or Plains card, put it onto the battlefield, then shuffle.",
+                ""
+            ),
+            ManaCost::from_rgbuwc(1, 0, 0, 0, 1, 0)
+        );
+        // Evolving Wilds
+        assert_eq!(
+            land_mana(
+                "{T}, Sacrifice Evolving Wilds: Search your library for a basic land card, put it onto the battlefield tapped, then shuffle.",
+                ""
+            ),
+            ManaCost::from_rgbuwc(1, 1, 1, 1, 1, 0)
+        );
+        // Lands without a mana ability fall back to their color identity
+        assert_eq!(
+            land_mana(
+                "Dark Depths enters the battlefield with ten ice counters on it.",
+                ""
+            ),
+            ManaCost::from_rgbuwc(0, 0, 0, 0, 0, 1)
+        );
+    }
+
+    #[test]
+    fn land_kinds() {
+        let kind = |oracle_text: &str| {
+            let json = format!(
+                r#"{{
+                    "object": "card",
+                    "name": "Land",
+                    "type_line": "Land",
+                    "oracle_text": "{}",
+                    "lang": "en"
+                }}"#,
+                oracle_text
+            );
+            let card: Card = serde_json::from_str::<ScryfallCard>(&json).unwrap().into();
+            card.kind
+        };
+        assert_eq!(
+            kind("Temple of Deceit enters the battlefield tapped.\\nWhen Temple of Deceit enters the battlefield, scry 1.\\n{T}: Add {U} or {B}."),
+            CardKind::TapLand
+        );
+        assert_eq!(
+            kind(
+                "This land enters tapped.\\nWhen this land enters, scry 1.\\n{T}: Add {U} or {B}."
+            ),
+            CardKind::TapLand
+        );
+        assert_eq!(
+            kind("Glacial Fortress enters the battlefield tapped unless you control a Plains or an Island.\\n{T}: Add {W} or {U}."),
+            CardKind::CheckLand
+        );
+        assert_eq!(
+            kind("({T}: Add {U} or {B}.)\\nAs Watery Grave enters, you may pay 2 life. If you don't, it enters tapped."),
+            CardKind::ShockLand
+        );
+        assert_eq!(
+            kind("This land enters tapped unless you control two or fewer other lands.\\n{T}: Add {U} or {R}."),
+            CardKind::FastLand
+        );
+        assert_eq!(
+            kind("Deserted Beach enters the battlefield tapped unless you control two or more other lands.\\n{T}: Add {W} or {U}."),
+            CardKind::SlowLand
+        );
+    }
+
+    #[test]
+    fn fetch_lands() {
+        let fetch = |oracle_text: &str| {
+            let json = format!(
+                r#"{{
+                    "object": "card",
+                    "name": "Fetch Land",
+                    "type_line": "Land",
+                    "oracle_text": "{}",
+                    "lang": "en"
+                }}"#,
+                oracle_text
+            );
+            let card: Card = serde_json::from_str::<ScryfallCard>(&json).unwrap().into();
+            card.ramp
+        };
+        // Arid Mesa
+        assert_eq!(
+            fetch("{T}, Pay 1 life, Sacrifice Arid Mesa: Search your library for a Mountain or Plains card, put it onto the battlefield, then shuffle."),
+            Some(Ramp {
+                land_types: ManaCost::from_rgbuwc(1, 0, 0, 0, 1, 0),
+                basic_only: false,
+                battlefield_count: 1,
+                tapped: false,
+                hand_count: 0,
+            })
+        );
+        // Evolving Wilds
+        assert_eq!(
+            fetch("{T}, Sacrifice Evolving Wilds: Search your library for a basic land card, put it onto the battlefield tapped, then shuffle."),
+            Some(Ramp {
+                land_types: ManaCost::new(),
+                basic_only: true,
+                battlefield_count: 1,
+                tapped: true,
+                hand_count: 0,
+            })
+        );
+        // Lands with a mana ability of their own are not fetch lands
+        assert_eq!(
+            fetch("{T}: Add {C}.\\n{2}, {T}, Sacrifice Myriad Landscape: Search your library for up to two basic land cards that share a land type, put them onto the battlefield tapped, then shuffle."),
+            None
+        );
+    }
+
+    #[test]
+    fn modal_double_faced_land_mana() {
+        let json = r#"{
+            "object": "card",
+            "name": "Needleverge Pathway // Pillarverge Pathway",
+            "type_line": "Land // Land",
+            "color_identity": ["R", "W"],
+            "card_faces": [
+                {
+                    "object": "card_face",
+                    "name": "Needleverge Pathway",
+                    "type_line": "Land",
+                    "image_uris": { "normal": "needleverge.jpg" },
+                    "oracle_text": "{T}: Add {R}.",
+                    "lang": "en"
+                },
+                {
+                    "object": "card_face",
+                    "name": "Pillarverge Pathway",
+                    "type_line": "Land",
+                    "image_uris": { "normal": "pillarverge.jpg" },
+                    "oracle_text": "{T}: Add {W}.",
+                    "lang": "en"
+                }
+            ],
+            "lang": "en"
+        }"#;
+        let card: ScryfallCard = serde_json::from_str(json).unwrap();
+        let face: Card = card.card_faces[1].clone().into();
+        assert_eq!(face.mana_cost, ManaCost::from_rgbuwc(0, 0, 0, 0, 1, 0));
+        let card: Card = card.into();
+        assert_eq!(card.mana_cost, ManaCost::from_rgbuwc(1, 0, 0, 0, 1, 0));
+    }
+
+    #[test]
+    fn land_types_from_type_line() {
+        let json = r#"{
+            "object": "card",
+            "name": "Breeding Pool",
+            "type_line": "Land — Forest Island",
+            "oracle_text": "({T}: Add {G} or {U}.)",
+            "color_identity": ["G", "U"],
+            "lang": "en"
+        }"#;
+        let card: Card = serde_json::from_str::<ScryfallCard>(json).unwrap().into();
+        assert_eq!(card.land_types, ManaCost::from_rgbuwc(0, 1, 0, 1, 0, 0));
+        assert_eq!(card.ramp, None);
+    }
 }
@@ -0,0 +1,754 @@
+//! # Card search
+//!
+//! Supports a subset of the [Scryfall search syntax](https://scryfall.com/docs/syntax)
+//! for [`Collection::search`](../collection/struct.Collection.html#method.search):
+//!
+//! - `t:land` or `type:creature`, the type line contains the text
+//! - `o:"enters tapped"` or `oracle:draw`, the oracle text contains the text
+//! - `c:rg`, `c=rg`, `c<=wubrg`, `c:m` or `c:colorless`, the card colors.
+//!   `c:` matches cards with at least the given colors
+//! - `id<=wubrg` or `id:rg`, the card color identity. `id:` matches cards whose
+//!   identity fits within the given colors
+//! - `mv<=3` or `cmc=2`, the mana value
+//! - `r:mythic` or `r>=rare`, the rarity
+//! - `s:dom` or `set:dar`, the set, accepting Arena set codes
+//! - `f:standard` or `legal:pioneer`, the card is legal in the format
+//! - `is:dfc`, `is:mdfc`, `is:transform`, `is:split`, `is:adventure`, `is:permanent` or `is:spell`
+//! - Any other word, or quoted words, match the card name
+//!
+//! Terms next to each other must all match, or use `or` to match either term. Prefix a
+//! term with `-` to negate it, and group terms with parentheses, e.g. `t:land -(t:basic or c:c)`.
+//! Matching is case insensitive
+//!
+//! Cards from a card database without oracle data, such as an older `all_cards.landlord`,
+//! are searched by what the card kind, name, mana cost and set tell: lands match `t:land`,
+//! tap, check, shock, fast and slow lands match `o:"enters tapped"`, colors come from the
+//! mana cost, and `f:standard` falls back to the set being in Standard
+use std::borrow::Cow;
+
+use crate::card::{Card, CardKind, GameFormat, Legality, ManaCost, Rarity, SetCode};
+use crate::data::ALL_SETS;
+
+/// Query represents a parsed search query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    /// Matches if every query matches
+    And(Vec<Query>),
+    /// Matches if any query matches
+    Or(Vec<Query>),
+    /// Matches if the query does not match
+    Not(Box<Query>),
+    /// Matches a single search term
+    Filter(Filter),
+}
+
+/// Filter represents a single search term, such as `t:land` or `mv<=3`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    /// The card name contains the lowercase text
+    Name(String),
+    /// The type line contains the lowercase text
+    Type(String),
+    /// The oracle text contains the lowercase text
+    Oracle(String),
+    /// The card colors compare to the colors
+    Colors(Comparison, ColorValue),
+    /// The card color identity compares to the colors
+    Identity(Comparison, ColorValue),
+    /// The mana value compares to the value
+    ManaValue(Comparison, u8),
+    /// The rarity compares to the rarity
+    Rarity(Comparison, Rarity),
+    /// The card is from the set
+    Set(SetCode),
+    /// The card is legal in the format
+    Format(GameFormat),
+    /// The card has the property, such as "dfc"
+    Is(IsFilter),
+}
+
+/// Comparison represents a search term operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessEq,
+    Eq,
+    NotEq,
+    GreaterEq,
+    Greater,
+}
+
+/// ColorValue represents the colors of a color search term
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorValue {
+    /// A set of colors, in the representation of `ManaCost::bits`
+    Colors(u8),
+    /// Two or more colors
+    Multicolor,
+}
+
+/// IsFilter represents the properties `is:` searches for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsFilter {
+    /// A double faced card, either transforming or modal
+    Dfc,
+    /// A modal double faced card
+    Mdfc,
+    /// A transforming double faced card
+    Transform,
+    Split,
+    Adventure,
+    /// A card that stays on the battlefield
+    Permanent,
+    /// An instant or sorcery
+    Spell,
+}
+
+/// SearchError represents a query that cannot be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchError {
+    /// The query has no terms, such as "" or "()"
+    Empty,
+    /// A parenthesis or quote is missing its pair
+    Unbalanced,
+    /// The keyword before the operator is not supported, e.g. "pow" in "pow>3"
+    UnknownKeyword(String),
+    /// The value after the operator is not valid for the keyword, e.g. "r:shiny"
+    InvalidValue { keyword: String, value: String },
+    /// The keyword does not support the operator, e.g. "t>land"
+    InvalidOperator { keyword: String },
+}
+
+impl std::fmt::Display for SearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Empty search query"),
+            Self::Unbalanced => write!(f, "Unbalanced parenthesis or quote in search query"),
+            Self::UnknownKeyword(keyword) => write!(f, "Unknown search keyword \"{}\"", keyword),
+            Self::InvalidValue { keyword, value } => {
+                write!(
+                    f,
+                    "Invalid value \"{}\" for search keyword \"{}\"",
+                    value, keyword
+                )
+            }
+            Self::InvalidOperator { keyword } => {
+                write!(f, "Invalid operator for search keyword \"{}\"", keyword)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+impl Query {
+    /// Returns true if the card matches the query
+    pub fn matches(&self, card: &Card) -> bool {
+        match self {
+            Self::And(queries) => queries.iter().all(|q| q.matches(card)),
+            Self::Or(queries) => queries.iter().any(|q| q.matches(card)),
+            Self::Not(query) => !query.matches(card),
+            Self::Filter(filter) => filter.matches(card),
+        }
+    }
+}
+
+impl std::str::FromStr for Query {
+    type Err = SearchError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let query = parser.or()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(SearchError::Unbalanced);
+        }
+        Ok(query)
+    }
+}
+
+impl Filter {
+    /// Returns true if the card matches the search term
+    pub fn matches(&self, card: &Card) -> bool {
+        match self {
+            Self::Name(text) => card.name.to_lowercase().contains(text),
+            Self::Type(text) => type_line(card).to_lowercase().contains(text),
+            Self::Oracle(text) => oracle_text(card).to_lowercase().contains(text),
+            Self::Colors(op, value) => compare_colors(colors(card), *op, *value),
+            Self::Identity(op, value) => compare_colors(color_identity(card), *op, *value),
+            Self::ManaValue(op, value) => {
+                // The mana cost of a land represents the mana it produces
+                let mv = if card.is_land() { 0 } else { card.cmc() };
+                compare(mv, *op, *value)
+            }
+            Self::Rarity(op, rarity) => {
+                card.rarity != Rarity::Unknown && compare(card.rarity, *op, *rarity)
+            }
+            Self::Set(code) => card.set == *code || ALL_SETS.resolve(card.set) == *code,
+            Self::Format(format) => match card.legality(*format) {
+                Some(legality) => legality == Legality::Legal,
+                None => *format == GameFormat::Standard && card.in_standard(),
+            },
+            Self::Is(is) => is.matches(card),
+        }
+    }
+}
+
+impl IsFilter {
+    fn matches(self, card: &Card) -> bool {
+        let layout = card.layout.as_str();
+        match self {
+            // Without a layout, a double faced card has a name with two faces and, unlike
+            // split and adventure cards, no mana cost of its own
+            Self::Dfc if layout.is_empty() => {
+                card.name.contains(" // ") && card.mana_cost_string.is_empty()
+            }
+            Self::Dfc => layout == "transform" || layout == "modal_dfc" || layout == "meld",
+            Self::Mdfc => layout == "modal_dfc",
+            Self::Transform => layout == "transform",
+            Self::Split => layout == "split",
+            Self::Adventure => layout == "adventure",
+            Self::Permanent => {
+                let type_line = type_line(card);
+                let type_line = type_line.split("//").next().unwrap_or_default();
+                !type_line.is_empty()
+                    && !type_line.contains("Instant")
+                    && !type_line.contains("Sorcery")
+            }
+            Self::Spell => {
+                let type_line = type_line(card);
+                let type_line = type_line.split("//").next().unwrap_or_default();
+                type_line.contains("Instant") || type_line.contains("Sorcery")
+            }
+        }
+    }
+}
+
+// True if the card database has the oracle data of the card, such as its type line
+fn has_oracle_data(card: &Card) -> bool {
+    !card.type_line.is_empty()
+}
+
+// The type line of the card, or the type of its card kind
+fn type_line(card: &Card) -> Cow<'_, str> {
+    if has_oracle_data(card) {
+        return Cow::Borrowed(&card.type_line);
+    }
+    Cow::Borrowed(match card.kind {
+        CardKind::BasicLand => "Basic Land",
+        kind if kind.is_land() => "Land",
+        CardKind::Creature => "Creature",
+        CardKind::Enchantment => "Enchantment",
+        CardKind::Instant => "Instant",
+        CardKind::Planeswalker => "Planeswalker",
+        CardKind::Sorcery => "Sorcery",
+        CardKind::Artifact => "Artifact",
+        _ => "",
+    })
+}
+
+// The oracle text of the card, or how a land of its card kind enters the battlefield
+fn oracle_text(card: &Card) -> Cow<'_, str> {
+    if has_oracle_data(card) {
+        return Cow::Borrowed(&card.oracle_text);
+    }
+    Cow::Owned(match card.kind {
+        CardKind::TapLand => format!("{} enters tapped.", card.name),
+        CardKind::CheckLand | CardKind::FastLand | CardKind::SlowLand => {
+            format!(
+                "{} enters tapped unless you control other lands.",
+                card.name
+            )
+        }
+        CardKind::ShockLand => format!(
+            "As {} enters, you may pay 2 life. If you don't, it enters tapped.",
+            card.name
+        ),
+        _ => String::new(),
+    })
+}
+
+// The colors of the card, or the colors of its mana costs
+fn colors(card: &Card) -> u8 {
+    if has_oracle_data(card) {
+        return color_bits(&card.colors);
+    }
+    if card.is_land() {
+        return 0;
+    }
+    card.all_mana_costs
+        .iter()
+        .fold(color_bits(&card.mana_cost), |bits, cost| {
+            bits | color_bits(cost)
+        })
+}
+
+// The color identity of the card, or the colors of its mana costs. The mana cost of a
+// land represents the mana it produces
+fn color_identity(card: &Card) -> u8 {
+    if has_oracle_data(card) {
+        return color_bits(&card.color_identity);
+    }
+    if card.is_land() {
+        return color_bits(&card.mana_cost);
+    }
+    colors(card)
+}
+
+// The WUBRG bits of a color set
+fn color_bits(colors: &ManaCost) -> u8 {
+    colors.bits
+        & (ManaCost::W_BITS
+            | ManaCost::U_BITS
+            | ManaCost::B_BITS
+            | ManaCost::R_BITS
+            | ManaCost::G_BITS)
+}
+
+fn compare<T: Ord>(a: T, op: Comparison, b: T) -> bool {
+    match op {
+        Comparison::Less => a < b,
+        Comparison::LessEq => a <= b,
+        Comparison::Eq => a == b,
+        Comparison::NotEq => a != b,
+        Comparison::GreaterEq => a >= b,
+        Comparison::Greater => a > b,
+    }
+}
+
+// Compares color sets, where less means a subset and greater means a superset
+fn compare_colors(card: u8, op: Comparison, value: ColorValue) -> bool {
+    let colors = match value {
+        ColorValue::Multicolor => {
+            let multicolor = card.count_ones() >= 2;
+            return if op == Comparison::NotEq {
+                !multicolor
+            } else {
+                multicolor
+            };
+        }
+        ColorValue::Colors(colors) => colors,
+    };
+    let subset = card & !colors == 0;
+    let superset = colors & !card == 0;
+    match op {
+        Comparison::Less => subset && card != colors,
+        Comparison::LessEq => subset,
+        Comparison::Eq => card == colors,
+        Comparison::NotEq => card != colors,
+        Comparison::GreaterEq => superset,
+        Comparison::Greater => superset && card != colors,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Or,
+    And,
+    Not,
+    // A term and true if any part of the term was quoted
+    Term(String, bool),
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, SearchError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '-' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            _ => {
+                let mut term = String::new();
+                let mut quoted = false;
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    chars.next();
+                    if c == '"' {
+                        quoted = true;
+                        loop {
+                            match chars.next() {
+                                Some('"') => break,
+                                Some(c) => term.push(c),
+                                None => return Err(SearchError::Unbalanced),
+                            }
+                        }
+                    } else {
+                        term.push(c);
+                    }
+                }
+                let token = match term.to_lowercase().as_str() {
+                    "or" if !quoted => Token::Or,
+                    "and" if !quoted => Token::And,
+                    _ => Token::Term(term, quoted),
+                };
+                tokens.push(token);
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+// A recursive descent parser where "or" binds looser than "and", which binds looser than "-"
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn or(&mut self) -> Result<Query, SearchError> {
+        let mut queries = vec![self.and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            queries.push(self.and()?);
+        }
+        Ok(if queries.len() == 1 {
+            queries.remove(0)
+        } else {
+            Query::Or(queries)
+        })
+    }
+
+    fn and(&mut self) -> Result<Query, SearchError> {
+        let mut queries = vec![self.not()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => self.pos += 1,
+                Some(Token::Open) | Some(Token::Not) | Some(Token::Term(..)) => {}
+                _ => break,
+            }
+            queries.push(self.not()?);
+        }
+        Ok(if queries.len() == 1 {
+            queries.remove(0)
+        } else {
+            Query::And(queries)
+        })
+    }
+
+    fn not(&mut self) -> Result<Query, SearchError> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        match token {
+            Some(Token::Not) => Ok(Query::Not(Box::new(self.not()?))),
+            Some(Token::Open) => {
+                let query = self.or()?;
+                match self.peek() {
+                    Some(Token::Close) => {
+                        self.pos += 1;
+                        Ok(query)
+                    }
+                    _ => Err(SearchError::Unbalanced),
+                }
+            }
+            Some(Token::Term(term, quoted)) => term_filter(&term, quoted).map(Query::Filter),
+            Some(Token::Close) => Err(SearchError::Unbalanced),
+            _ => Err(SearchError::Empty),
+        }
+    }
+}
+
+// Returns the filter for a term, such as "t:land", or a name filter for a word
+fn term_filter(term: &str, quoted: bool) -> Result<Filter, SearchError> {
+    // ":" means the natural comparison of each keyword, handled below
+    const OPERATORS: [(&str, Comparison); 7] = [
+        ("<=", Comparison::LessEq),
+        (">=", Comparison::GreaterEq),
+        ("!=", Comparison::NotEq),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+        ("=", Comparison::Eq),
+        (":", Comparison::Eq),
+    ];
+    let keyword_len = term
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(term.len());
+    let rest = &term[keyword_len..];
+    let operator = OPERATORS
+        .iter()
+        .find_map(|(symbol, op)| Some((*op, rest.strip_prefix(symbol)?)));
+    let (op, value) = match operator {
+        Some(operator) if keyword_len > 0 => operator,
+        _ => return Ok(Filter::Name(term.to_lowercase())),
+    };
+    let is_colon = rest.starts_with(':');
+    let keyword = term[..keyword_len].to_lowercase();
+    let value = value.to_lowercase();
+    let invalid_value = || SearchError::InvalidValue {
+        keyword: keyword.clone(),
+        value: value.clone(),
+    };
+    let text_only = |filter: Filter| {
+        if is_colon || op == Comparison::Eq {
+            Ok(filter)
+        } else {
+            Err(SearchError::InvalidOperator {
+                keyword: keyword.clone(),
+            })
+        }
+    };
+    if value.is_empty() && !quoted {
+        return Err(invalid_value());
+    }
+    match keyword.as_str() {
+        "t" | "type" => text_only(Filter::Type(value.clone())),
+        "o" | "oracle" => text_only(Filter::Oracle(value.clone())),
+        "n" | "name" => text_only(Filter::Name(value.clone())),
+        "c" | "color" | "colors" => {
+            let colors = color_value(&value).ok_or_else(invalid_value)?;
+            // "c:rg" means at least red and green, while "c:c" means colorless
+            let op = match colors {
+                ColorValue::Colors(bits) if is_colon && bits != 0 => Comparison::GreaterEq,
+                _ => op,
+            };
+            Ok(Filter::Colors(op, colors))
+        }
+        "id" | "identity" | "ci" => {
+            let colors = color_value(&value).ok_or_else(invalid_value)?;
+            // "id:rg" means an identity that fits within red and green
+            let op = match colors {
+                ColorValue::Colors(_) if is_colon => Comparison::LessEq,
+                _ => op,
+            };
+            Ok(Filter::Identity(op, colors))
+        }
+        "mv" | "cmc" | "manavalue" => {
+            let mv = value.parse().map_err(|_| invalid_value())?;
+            Ok(Filter::ManaValue(op, mv))
+        }
+        "r" | "rarity" => {
+            let rarity = match value.as_str() {
+                "c" | "common" => Rarity::Common,
+                "u" | "uncommon" => Rarity::Uncommon,
+                "r" | "rare" => Rarity::Rare,
+                "m" | "mythic" => Rarity::Mythic,
+                _ => return Err(invalid_value()),
+            };
+            Ok(Filter::Rarity(op, rarity))
+        }
+        "s" | "set" | "e" | "edition" => {
+            let code: SetCode = value.parse().map_err(|_| invalid_value())?;
+            text_only(Filter::Set(ALL_SETS.resolve(code)))
+        }
+        "f" | "format" | "legal" => {
+            let format = serde_json::from_value(serde_json::Value::String(value.clone()));
+            match format {
+                Ok(GameFormat::Other) | Err(_) => Err(invalid_value()),
+                Ok(format) => text_only(Filter::Format(format)),
+            }
+        }
+        "is" => {
+            let is = match value.as_str() {
+                "dfc" => IsFilter::Dfc,
+                "mdfc" => IsFilter::Mdfc,
+                "transform" | "tdfc" => IsFilter::Transform,
+                "split" => IsFilter::Split,
+                "adventure" => IsFilter::Adventure,
+                "permanent" => IsFilter::Permanent,
+                "spell" => IsFilter::Spell,
+                _ => return Err(invalid_value()),
+            };
+            text_only(Filter::Is(is))
+        }
+        _ => Err(SearchError::UnknownKeyword(keyword.clone())),
+    }
+}
+
+// Returns the colors of a value such as "rg", "red", "m" or "colorless"
+fn color_value(value: &str) -> Option<ColorValue> {
+    let bits = match value {
+        "m" | "multicolor" => return Some(ColorValue::Multicolor),
+        "c" | "colorless" => 0,
+        "white" => ManaCost::W_BITS,
+        "blue" => ManaCost::U_BITS,
+        "black" => ManaCost::B_BITS,
+        "red" => ManaCost::R_BITS,
+        "green" => ManaCost::G_BITS,
+        _ => {
+            let mut bits = 0;
+            for c in value.chars() {
+                bits |= match c {
+                    'w' => ManaCost::W_BITS,
+                    'u' => ManaCost::U_BITS,
+                    'b' => ManaCost::B_BITS,
+                    'r' => ManaCost::R_BITS,
+                    'g' => ManaCost::G_BITS,
+                    _ => return None,
+                };
+            }
+            bits
+        }
+    };
+    Some(ColorValue::Colors(bits))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collection::Collection;
+    use crate::search::*;
+
+    fn card(name: &str, type_line: &str, colors: &str, identity: &str, oracle_text: &str) -> Card {
+        let mut card = card!(name).clone();
+        card.type_line = type_line.to_string();
+        card.oracle_text = oracle_text.to_string();
+        card.colors = colors.parse().unwrap();
+        card.color_identity = identity.parse().unwrap();
+        card
+    }
+
+    fn collection() -> Collection {
+        let mut cards = vec![
+            card("Shock", "Instant", "{R}", "{R}", "Shock deals 2 damage to any target."),
+            card("Negate", "Instant", "{U}", "{U}", "Counter target noncreature spell."),
+            card("Sulfur Falls", "Land", "{0}", "{U}{R}", "Sulfur Falls enters tapped unless you control an Island or a Mountain.\n{T}: Add {U} or {R}."),
+            card("Island", "Basic Land — Island", "{0}", "{U}", "({T}: Add {U}.)"),
+            card("Niv-Mizzet, Parun", "Legendary Creature — Dragon Wizard", "{U}{R}", "{U}{R}", "This spell can't be countered.\nFlying"),
+            card("Llanowar Elves", "Creature — Elf Druid", "{G}", "{G}", "{T}: Add {G}."),
+        ];
+        cards[4].rarity = Rarity::Mythic;
+        cards[5].layout = "normal".to_string();
+        cards[2].layout = "transform".to_string();
+        Collection::from_cards(cards)
+    }
+
+    fn names(query: &str) -> Vec<String> {
+        let mut names: Vec<_> = collection()
+            .search(query)
+            .unwrap()
+            .into_iter()
+            .map(|card| card.name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn search_keywords() {
+        assert_eq!(names("t:land"), vec!["Island", "Sulfur Falls"]);
+        assert_eq!(names("t:basic"), vec!["Island"]);
+        assert_eq!(names("c:rg"), Vec::<String>::new());
+        assert_eq!(names("c:r"), vec!["Niv-Mizzet, Parun", "Shock"]);
+        assert_eq!(names("c=r"), vec!["Shock"]);
+        assert_eq!(names("c:m"), vec!["Niv-Mizzet, Parun"]);
+        assert_eq!(names("c:c"), vec!["Island", "Sulfur Falls"]);
+        assert_eq!(names("id<=ur").len(), 5);
+        assert_eq!(names("id:g"), vec!["Llanowar Elves"]);
+        assert_eq!(
+            names("mv<=1"),
+            vec!["Island", "Llanowar Elves", "Shock", "Sulfur Falls"]
+        );
+        assert_eq!(names("cmc>=3"), vec!["Niv-Mizzet, Parun"]);
+        assert_eq!(names("r:mythic"), vec!["Niv-Mizzet, Parun"]);
+        assert_eq!(names("r>=rare"), vec!["Niv-Mizzet, Parun", "Sulfur Falls"]);
+        assert_eq!(names("o:\"enters tapped\""), vec!["Sulfur Falls"]);
+        assert_eq!(names("is:dfc"), vec!["Sulfur Falls"]);
+        assert_eq!(names("is:spell"), vec!["Negate", "Shock"]);
+        assert_eq!(
+            names("is:permanent t:creature"),
+            vec!["Llanowar Elves", "Niv-Mizzet, Parun"]
+        );
+        assert_eq!(names("falls"), vec!["Sulfur Falls"]);
+        assert_eq!(names("\"sulfur falls\""), vec!["Sulfur Falls"]);
+    }
+
+    #[test]
+    fn search_boolean_operators() {
+        assert_eq!(names("t:land -t:basic"), vec!["Sulfur Falls"]);
+        assert_eq!(names("t:land and -t:basic"), vec!["Sulfur Falls"]);
+        assert_eq!(names("c=r or c=g"), vec!["Llanowar Elves", "Shock"]);
+        assert_eq!(names("t:instant (c:r OR c:g)"), vec!["Shock"]);
+        assert_eq!(
+            names("-(t:land or t:instant)"),
+            vec!["Llanowar Elves", "Niv-Mizzet, Parun"]
+        );
+        assert_eq!(
+            names("t:land or t:instant c:u"),
+            vec!["Island", "Negate", "Sulfur Falls"]
+        );
+    }
+
+    #[test]
+    fn search_all_cards() {
+        use crate::data::ALL_CARDS;
+        let search = |query: &str| -> Vec<&str> {
+            ALL_CARDS
+                .search(query)
+                .unwrap()
+                .into_iter()
+                .map(|card| card.name.as_str())
+                .collect()
+        };
+        let lands = search("t:land");
+        assert!(lands.contains(&"Glacial Fortress"));
+        assert!(!lands.contains(&"Shock"));
+        let tapped = search("o:\"enters tapped\"");
+        assert!(tapped.contains(&"Glacial Fortress"));
+        assert!(tapped.contains(&"Watery Grave"));
+        assert!(!tapped.contains(&"Island"));
+        let red = search("c:r");
+        assert!(red.contains(&"Shock"));
+        assert!(red.contains(&"Fire // Ice"));
+        assert!(!red.contains(&"Negate"));
+        let identity = search("id<=wu");
+        assert!(identity.contains(&"Negate"));
+        assert!(identity.contains(&"Glacial Fortress"));
+        assert!(!identity.contains(&"Shock"));
+        let colorless = search("id:c");
+        assert!(colorless.contains(&"Sol Ring"));
+        assert!(!colorless.contains(&"Shock"));
+        let dfc = search("is:dfc");
+        assert!(dfc.contains(&"Delver of Secrets // Insectile Aberration"));
+        assert!(!dfc.contains(&"Fire // Ice"));
+        assert!(!search("f:standard").contains(&"Shock"));
+        let mut shock = card!("Shock").clone();
+        shock.set = "dmu".parse().unwrap();
+        let standard = Collection::from_cards(vec![shock]);
+        assert_eq!(standard.search("f:standard").unwrap().len(), 1);
+        assert!(standard.search("f:pioneer").unwrap().is_empty());
+    }
+
+    #[test]
+    fn search_errors() {
+        let err = |query: &str| collection().search(query).unwrap_err();
+        assert_eq!(err(""), SearchError::Empty);
+        assert_eq!(err("(t:land"), SearchError::Unbalanced);
+        assert_eq!(err("t:land)"), SearchError::Unbalanced);
+        assert_eq!(err("o:\"enters"), SearchError::Unbalanced);
+        assert_eq!(err("pow>3"), SearchError::UnknownKeyword("pow".to_string()));
+        assert_eq!(
+            err("r:shiny"),
+            SearchError::InvalidValue {
+                keyword: "r".to_string(),
+                value: "shiny".to_string()
+            }
+        );
+        assert_eq!(
+            err("t>land"),
+            SearchError::InvalidOperator {
+                keyword: "t".to_string()
+            }
+        );
+        assert!("s:dar".parse::<Query>().is_ok());
+        assert!("f:standard".parse::<Query>().is_ok());
+    }
+}
@@ -0,0 +1,350 @@
+//! # Set registry
+//!
+//! Sets are built from the [Scryfall sets data](https://scryfall.com/docs/api/sets)
+//! and bundled with the card database, see [`ALL_SETS`](../data/struct.ALL_SETS.html)
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::Deref;
+
+/// Standard rotates with the first core or expansion set released from the 1st of this month
+const ROTATION_MONTH: u32 = 8;
+
+/// The first year in which Standard keeps three years of sets rather than two
+const THREE_YEAR_STANDARD_SINCE: i32 = 2022;
+
+/// SetCode represents a set code, such as "DOM" or "M21"
+///
+/// Codes are stored uppercase. The default SetCode is the unknown set
+#[derive(Default, Clone, Copy, PartialOrd, PartialEq, Eq, Ord, Hash)]
+pub struct SetCode([u8; SetCode::MAX_LEN]);
+
+/// SetCodeError represents a string that is not a valid set code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetCodeError(pub String);
+
+impl SetCode {
+    /// The maximum length of a set code
+    pub const MAX_LEN: usize = 6;
+    /// The code of cards with an unknown set
+    pub const UNKNOWN: SetCode = SetCode([0; SetCode::MAX_LEN]);
+
+    /// Returns the code as a str, which is empty for the unknown set
+    pub fn as_str(&self) -> &str {
+        let len = self.0.iter().position(|b| *b == 0).unwrap_or(Self::MAX_LEN);
+        std::str::from_utf8(&self.0[..len]).expect("set codes are ascii")
+    }
+
+    /// Returns true if the set is unknown
+    pub fn is_unknown(&self) -> bool {
+        *self == Self::UNKNOWN
+    }
+}
+
+impl std::str::FromStr for SetCode {
+    type Err = SetCodeError;
+
+    /// Parses a set code, ignoring case, e.g. "dom" or "DOM"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() || s.len() > Self::MAX_LEN || !s.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(SetCodeError(s.to_string()));
+        }
+        let mut code = [0; Self::MAX_LEN];
+        for (b, c) in code.iter_mut().zip(s.bytes()) {
+            *b = c.to_ascii_uppercase();
+        }
+        Ok(Self(code))
+    }
+}
+
+impl std::fmt::Display for SetCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_unknown() {
+            write!(f, "Unknown")
+        } else {
+            write!(f, "{}", self.as_str())
+        }
+    }
+}
+
+impl std::fmt::Debug for SetCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl std::fmt::Display for SetCodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid set code \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for SetCodeError {}
+
+// Set codes serialize as strings, and any invalid code deserializes to the unknown set
+impl Serialize for SetCode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SetCode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Ok(s.parse().unwrap_or_default())
+    }
+}
+
+/// SetType represents the Scryfall [set type](https://scryfall.com/docs/api/sets)
+#[derive(
+    Default, Debug, Clone, Copy, Serialize, Deserialize, PartialOrd, PartialEq, Eq, Ord, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SetType {
+    Core,
+    Expansion,
+    Masters,
+    Alchemy,
+    Commander,
+    DraftInnovation,
+    Funny,
+    Promo,
+    Token,
+    Memorabilia,
+    #[default]
+    #[serde(other)]
+    Other,
+}
+
+/// Set represents a Magic: The Gathering set
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Set {
+    /// The set code, e.g. "DOM"
+    pub code: SetCode,
+    /// The set name, e.g. "Dominaria"
+    pub name: String,
+    /// The set type
+    pub set_type: SetType,
+    /// The date the set released
+    pub released_at: NaiveDate,
+    /// The code MTG Arena uses for the set, if it differs from `code`, e.g. "DAR" for "DOM"
+    pub arena_code: Option<SetCode>,
+}
+
+impl Set {
+    /// Returns true if cards from the set are legal in Standard while the set is in rotation
+    pub fn is_standard_type(&self) -> bool {
+        self.set_type == SetType::Core || self.set_type == SetType::Expansion
+    }
+}
+
+/// SetRegistry represents every known set, ordered by release date
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SetRegistry {
+    pub sets: Vec<Set>,
+}
+
+impl SetRegistry {
+    /// Returns a new registry of sets
+    pub fn from_sets(mut sets: Vec<Set>) -> Self {
+        sets.sort_by_key(|set| (set.released_at, set.code));
+        Self { sets }
+    }
+
+    /// Returns the set with the given code or Arena code
+    pub fn set_from_code(&self, code: SetCode) -> Option<&Set> {
+        if code.is_unknown() {
+            return None;
+        }
+        self.sets
+            .iter()
+            .find(|set| set.code == code)
+            .or_else(|| self.sets.iter().find(|set| set.arena_code == Some(code)))
+    }
+
+    /// Returns the canonical code for the given code or Arena code, e.g. "DOM" for "DAR".
+    /// Codes missing from the registry are returned as is
+    pub fn resolve(&self, code: SetCode) -> SetCode {
+        self.set_from_code(code).map_or(code, |set| set.code)
+    }
+
+    /// Returns the release date of the newest set in the registry
+    pub fn latest_release(&self) -> Option<NaiveDate> {
+        self.sets.last().map(|set| set.released_at)
+    }
+
+    /// Returns the sets in Standard on the given date, ordered by release date
+    ///
+    /// Standard rotates once a year with the release of the first core or expansion set
+    /// released on or after August 1st. Until 2021, Standard contains the sets released
+    /// since the previous rotation. From 2022 on, Standard keeps three years of sets and
+    /// contains the sets released since the rotation two years earlier
+    pub fn standard_sets_at(&self, date: NaiveDate) -> Vec<&Set> {
+        let released: Vec<_> = self
+            .sets
+            .iter()
+            .filter(|set| set.is_standard_type() && set.released_at <= date)
+            .collect();
+        let mut rotations = Vec::new();
+        for set in &released {
+            let year = set.released_at.year();
+            let is_fall = set.released_at.month() >= ROTATION_MONTH;
+            if is_fall && rotations.last().map(NaiveDate::year) != Some(year) {
+                rotations.push(set.released_at);
+            }
+        }
+        let window = match rotations.last() {
+            Some(date) if date.year() >= THREE_YEAR_STANDARD_SINCE => 3,
+            _ => 2,
+        };
+        let start = match rotations.len() {
+            n if n >= window => rotations[n - window],
+            _ => return released,
+        };
+        released
+            .into_iter()
+            .filter(|set| set.released_at >= start)
+            .collect()
+    }
+
+    /// Returns the sets in Standard as of the newest set in the registry
+    pub fn standard_sets(&self) -> Vec<&Set> {
+        match self.latest_release() {
+            Some(date) => self.standard_sets_at(date),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns true if the set with the given code or Arena code is in Standard
+    /// as of the newest set in the registry
+    pub fn in_standard(&self, code: SetCode) -> bool {
+        let code = self.resolve(code);
+        self.standard_sets().iter().any(|set| set.code == code)
+    }
+}
+
+impl Deref for SetRegistry {
+    type Target = [Set];
+
+    fn deref(&self) -> &Self::Target {
+        &self.sets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::ALL_SETS;
+    use crate::set::*;
+
+    fn code(s: &str) -> SetCode {
+        s.parse().unwrap()
+    }
+
+    fn set(c: &str, set_type: SetType, released_at: &str) -> Set {
+        Set {
+            code: code(c),
+            name: c.to_string(),
+            set_type,
+            released_at: released_at.parse().unwrap(),
+            arena_code: None,
+        }
+    }
+
+    #[test]
+    fn set_code_from_str() {
+        assert_eq!(code("dom"), code("DOM"));
+        assert_eq!(code(" m21 ").to_string(), "M21");
+        assert_eq!(SetCode::default(), SetCode::UNKNOWN);
+        assert_eq!(SetCode::UNKNOWN.to_string(), "Unknown");
+        assert!("".parse::<SetCode>().is_err());
+        assert!("TOOLONG".parse::<SetCode>().is_err());
+        assert!("D-M".parse::<SetCode>().is_err());
+    }
+
+    #[test]
+    fn set_code_serde() {
+        let json = serde_json::to_string(&code("ELD")).unwrap();
+        assert_eq!(json, "\"ELD\"");
+        assert_eq!(
+            serde_json::from_str::<SetCode>("\"eld\"").unwrap(),
+            code("ELD")
+        );
+        assert_eq!(
+            serde_json::from_str::<SetCode>("\"not a set\"").unwrap(),
+            SetCode::UNKNOWN
+        );
+    }
+
+    #[test]
+    fn arena_codes_resolve() {
+        let dom = ALL_SETS.set_from_code(code("DAR")).unwrap();
+        assert_eq!(dom.code, code("DOM"));
+        assert_eq!(dom.name, "Dominaria");
+        assert_eq!(ALL_SETS.resolve(code("DAR")), code("DOM"));
+        assert_eq!(ALL_SETS.resolve(code("XYZ")), code("XYZ"));
+        assert!(ALL_SETS.set_from_code(SetCode::UNKNOWN).is_none());
+    }
+
+    #[test]
+    fn standard_rotates_every_fall() {
+        let registry = SetRegistry::from_sets(vec![
+            set("M19", SetType::Core, "2018-07-13"),
+            set("GRN", SetType::Expansion, "2018-10-05"),
+            set("RNA", SetType::Expansion, "2019-01-25"),
+            set("MH1", SetType::DraftInnovation, "2019-06-14"),
+            set("ELD", SetType::Expansion, "2019-10-04"),
+            set("THB", SetType::Expansion, "2020-01-24"),
+            set("ZNR", SetType::Expansion, "2020-09-25"),
+        ]);
+        let codes = |date: &str| -> Vec<_> {
+            registry
+                .standard_sets_at(date.parse().unwrap())
+                .iter()
+                .map(|set| set.code.to_string())
+                .collect()
+        };
+        assert_eq!(codes("2019-06-30"), vec!["M19", "GRN", "RNA"]);
+        assert_eq!(codes("2019-10-04"), vec!["GRN", "RNA", "ELD"]);
+        assert_eq!(codes("2020-09-25"), vec!["ELD", "THB", "ZNR"]);
+        assert!(registry.in_standard(code("THB")));
+        assert!(!registry.in_standard(code("RNA")));
+        assert!(!registry.in_standard(code("MH1")));
+    }
+
+    #[test]
+    fn bundled_sets_are_first_class() {
+        assert!(ALL_SETS.len() > 22);
+        let latest = ALL_SETS.latest_release().unwrap();
+        assert!(latest > "2020-07-03".parse().unwrap());
+        assert!(!ALL_SETS.standard_sets().is_empty());
+        let codes = |date: &str| -> Vec<_> {
+            ALL_SETS
+                .standard_sets_at(date.parse().unwrap())
+                .iter()
+                .map(|set| set.code.to_string())
+                .collect()
+        };
+        // Nothing rotates with Dominaria United, Wilds of Eldraine rotates out Zendikar
+        // Rising to Adventures in the Forgotten Realms and Bloomburrow rotates out
+        // Innistrad: Midnight Hunt to Streets of New Capenna
+        assert_eq!(codes("2022-09-09").first().unwrap(), "ZNR");
+        assert_eq!(codes("2023-09-08").first().unwrap(), "MID");
+        assert_eq!(codes("2024-08-02").first().unwrap(), "DMU");
+        let standard = codes("2025-04-11");
+        for set in &["DMU", "BRO", "ONE", "MOM", "TDM"] {
+            assert!(
+                standard.contains(&set.to_string()),
+                "{} is in Standard",
+                set
+            );
+        }
+        assert!(!standard.contains(&"SNC".to_string()));
+    }
+}
@@ -1,7 +1,7 @@
 //! # Simulation engine and card observations
 use crate::card::Card;
 use crate::deck::Deck;
-use crate::hand::{AutoTapResult, Hand, PlayOrder, Scratch, SimCard};
+use crate::hand::{AutoTapResult, Hand, LandEntry, PlayOrder, Scratch, SimCard};
 use crate::mulligan::Mulligan;
 use rand::prelude::*;
 use rand::rngs::SmallRng;
@@ -12,6 +12,10 @@ pub struct SimulationConfig<'a, 'b, M: Mulligan> {
   pub deck: &'a Deck,
   pub mulligan: &'b M,
   pub on_the_play: bool,
+  /// When to pay life rather than mana, see `LifePolicy`
+  pub life_policy: LifePolicy,
+  /// How lands enter the battlefield, see `LandEntry`
+  pub land_entry: LandEntry,
 }
 
 #[derive(Debug, Default)]
@@ -20,6 +24,38 @@ pub struct Simulation {
   pub accumulated_opening_hand_size: usize,
   pub accumulated_opening_hand_land_count: usize,
   pub on_the_play: bool,
+  /// When to pay life rather than mana for [Phyrexian](https://mtg.gamepedia.com/Phyrexian_mana) pips
+  /// and for shock lands to enter untapped
+  pub life_policy: LifePolicy,
+  /// How lands enter the battlefield, see `LandEntry`
+  pub land_entry: LandEntry,
+}
+
+/// LifePolicy represents when the simulation pays life in place of mana
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LifePolicy {
+  /// Never pay life, every Phyrexian pip must be paid with colored mana
+  /// and shock lands always enter tapped
+  Never,
+  /// Pay life for a Phyrexian pip or a shock land only if the lands cannot pay
+  /// for the card otherwise, paying at most `max_life` life for a single card
+  IfColorMissing { max_life: u8 },
+}
+
+impl Default for LifePolicy {
+  fn default() -> Self {
+    Self::IfColorMissing { max_life: u8::MAX }
+  }
+}
+
+impl LifePolicy {
+  /// Returns true if the policy allows paying `life` life for a single card
+  pub fn allows(self, life: u8) -> bool {
+    match self {
+      Self::Never => life == 0,
+      Self::IfColorMissing { max_life } => life <= max_life,
+    }
+  }
 }
 
 #[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
@@ -29,6 +65,8 @@ pub struct Observations {
   pub play: usize,
   pub in_opening_hand: usize,
   pub total_runs: usize,
+  /// The total life paid over all mana events, for Phyrexian pips and shock lands
+  pub life_paid: usize,
 }
 
 impl Observations {
@@ -46,6 +84,15 @@ impl Observations {
   pub fn p_play(&self) -> f64 {
     self.play as f64 / self.total_runs as f64
   }
+
+  /// Returns the expected life paid to cast the card, given that we could pay for it
+  pub fn expected_life_paid(&self) -> f64 {
+    if self.mana == 0 {
+      0.0
+    } else {
+      self.life_paid as f64 / self.mana as f64
+    }
+  }
 }
 
 impl Simulation {
@@ -67,6 +114,8 @@ impl Simulation {
       accumulated_opening_hand_size,
       accumulated_opening_hand_land_count,
       on_the_play: config.on_the_play,
+      life_policy: config.life_policy,
+      land_entry: config.land_entry,
     }
   }
 
@@ -84,17 +133,28 @@ impl Simulation {
       PlayOrder::Second
     };
     'next_hand: for hand in &self.hands {
-      // Check all potential mana costs of a card
+      // Check all potential mana costs of a card. The costs are sorted by the
+      // life they pay, so we only pay life when the lands cannot pay with mana
       let mut result = AutoTapResult::new();
+      let mut life_paid = 0;
       for mana_cost in &card.all_mana_costs {
         // NOTE Do not mutate observations in this loop
+        if !self.life_policy.allows(mana_cost.life) {
+          continue;
+        }
         let goal = SimCard {
-          hash: card.hash,
           mana_cost: *mana_cost,
-          kind: card.kind,
+          ..SimCard::from(card)
         };
-        result = hand.auto_tap_with_scratch(&goal, turn, play_order, &mut scratch);
+        result = hand.auto_tap_with_scratch(&goal, turn, play_order, self.land_entry, &mut scratch);
+        // Shock lands only pay life when the lands cannot pay otherwise, so the
+        // card cannot be paid this way if the policy does not allow that life
+        let life = mana_cost.life.saturating_add(result.life_paid);
+        if result.paid && !self.life_policy.allows(life) {
+          result.paid = false;
+        }
         if result.paid {
+          life_paid = life as usize;
           break;
         }
       }
@@ -109,6 +169,7 @@ impl Simulation {
       // Can we pay? Count a mana on curve event
       if result.paid {
         observations.mana += 1;
+        observations.life_paid += life_paid;
         // Was the card in question in our initial hand? Did we draw it on curve?
         if result.in_opening_hand || result.in_draw_hand {
           observations.play += 1;
@@ -141,6 +202,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
   }
 
@@ -156,6 +219,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(&card);
     assert_eq!(obs.cmc, runs);
@@ -179,6 +244,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(&card!("Llanowar Elves"));
     assert_eq!(obs.cmc, runs);
@@ -203,6 +270,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: false,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(&card!("Llanowar Elves"));
     assert_eq!(obs.cmc, runs);
@@ -226,6 +295,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(&card!("Llanowar Elves"));
     assert_eq!(obs.cmc, runs);
@@ -250,6 +321,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: false,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(&card!("Llanowar Elves"));
     assert_eq!(obs.cmc, runs);
@@ -272,6 +345,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(&card);
     assert_eq!(obs.cmc, runs);
@@ -293,6 +368,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(&card);
     assert_eq!(obs.cmc, runs);
@@ -315,11 +392,121 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let o = sim.observations_for_card(card!("Integrity"));
     assert!(o.mana == o.cmc);
   }
 
+  #[test]
+  fn phyrexian_mana_pays_life_if_color_missing() {
+    let code = "
+            4 Dismember
+            20 Island
+        ";
+    let deck = decklist!(code);
+    let mut sim = Simulation::from_config(&SimulationConfig {
+      run_count: 100,
+      draw_count: 3,
+      mulligan: &Never::never(),
+      deck: &deck,
+      on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
+    });
+    let card = card!("Dismember");
+    let o = sim.observations_for_card(card);
+    assert!(o.cmc > 0);
+    assert_eq!(o.mana, o.cmc);
+    assert_eq!(o.expected_life_paid(), 4.0);
+    sim.life_policy = LifePolicy::IfColorMissing { max_life: 2 };
+    let o = sim.observations_for_card(card);
+    assert_eq!(o.mana, 0);
+    sim.life_policy = LifePolicy::Never;
+    let o = sim.observations_for_card(card);
+    assert_eq!(o.mana, 0);
+    assert_eq!(o.life_paid, 0);
+  }
+
+  #[test]
+  fn shock_lands_follow_life_policy() {
+    let code = "
+            4 Opt
+            20 Watery Grave
+        ";
+    let deck = decklist!(code);
+    let card = card!("Opt");
+    let observe = |life_policy| {
+      let sim = Simulation::from_config(&SimulationConfig {
+        run_count: 100,
+        draw_count: 1,
+        mulligan: &Never::never(),
+        deck: &deck,
+        on_the_play: true,
+        life_policy,
+        land_entry: LandEntry::Printed,
+      });
+      sim.observations_for_card(card)
+    };
+    let o = observe(LifePolicy::default());
+    assert_eq!(o.mana, o.cmc);
+    assert_eq!(o.expected_life_paid(), 2.0);
+    let o = observe(LifePolicy::IfColorMissing { max_life: 1 });
+    assert_eq!(o.mana, 0);
+    let o = observe(LifePolicy::Never);
+    assert!(o.cmc > 0);
+    assert_eq!(o.mana, 0);
+    assert_eq!(o.life_paid, 0);
+  }
+
+  #[test]
+  fn check_lands_follow_land_entry() {
+    let code = "
+            4 Opt
+            20 Glacial Fortress
+        ";
+    let deck = decklist!(code);
+    let observe = |land_entry| {
+      let sim = Simulation::from_config(&SimulationConfig {
+        run_count: 100,
+        draw_count: 1,
+        mulligan: &Never::never(),
+        deck: &deck,
+        on_the_play: true,
+        life_policy: LifePolicy::default(),
+        land_entry,
+      });
+      sim.observations_for_card(card!("Opt"))
+    };
+    let o = observe(LandEntry::default());
+    assert_eq!(o.mana, o.cmc);
+    let o = observe(LandEntry::Printed);
+    assert!(o.cmc > 0);
+    assert_eq!(o.mana, 0);
+  }
+
+  #[test]
+  fn phyrexian_mana_pays_mana_if_color_available() {
+    let code = "
+            4 Dismember
+            20 Swamp
+        ";
+    let deck = decklist!(code);
+    let sim = Simulation::from_config(&SimulationConfig {
+      run_count: 100,
+      draw_count: 3,
+      mulligan: &Never::never(),
+      deck: &deck,
+      on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
+    });
+    let o = sim.observations_for_card(card!("Dismember"));
+    assert_eq!(o.mana, o.cmc);
+    assert_eq!(o.life_paid, 0);
+  }
+
   #[test]
   fn hypergeometric_0() {
     let code = "
@@ -356,6 +543,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(card!("Opt"));
     let actual = obs.p_mana();
@@ -398,6 +587,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(card!("Opt"));
     let actual = obs.p_mana();
@@ -423,6 +614,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(card!("History of Benalia"));
     let actual = obs.p_mana();
@@ -449,6 +642,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(card!("Jadelight Ranger"));
     let actual = obs.p_mana();
@@ -478,6 +673,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(card);
     assert_eq!(obs.mana, runs);
@@ -506,6 +703,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(card);
     assert_eq!(obs.mana, runs);
@@ -534,6 +733,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(card);
     assert_eq!(obs.mana, runs);
@@ -562,6 +763,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(card);
     assert_eq!(obs.mana, runs);
@@ -590,6 +793,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(card);
     assert_eq!(obs.mana, runs);
@@ -614,6 +819,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(card);
     assert_eq!(obs.cmc, runs);
@@ -652,6 +859,8 @@ mod tests {
       mulligan: &Never::never(),
       deck: &deck,
       on_the_play: true,
+      life_policy: LifePolicy::default(),
+      land_entry: LandEntry::default(),
     });
     let obs = sim.observations_for_card(card);
     dbg!(obs);